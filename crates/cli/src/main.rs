@@ -27,6 +27,11 @@ fn main() {
                         .default_value("ws://localhost:7777")
                         .empty_values(false)
                         .help("Connects to the following node"),
+                )
+                .arg(
+                    Arg::with_name("insecure")
+                        .long("insecure")
+                        .help("Accepts invalid TLS certificates from the node (testing only)"),
                 ),
         );
     let matches = app.get_matches();
@@ -52,7 +57,14 @@ fn main() {
         };
 
         let url = matches.value_of("node_url").unwrap();
-        Wallet::new(home, url).start();
+        let insecure = matches.is_present("insecure");
+        match Wallet::new(home, url, insecure) {
+            Ok(wallet) => wallet.start(),
+            Err(e) => {
+                println!("{}", e);
+                std::process::exit(1);
+            }
+        }
     } else {
         println!("Failed to match subcommand");
         std::process::exit(1);
@@ -1,7 +1,10 @@
 use super::*;
 use clap::ArgMatches;
 use db::WalletAccount;
-use godcoin::tx::CreateAccountTx;
+use godcoin::{
+    crypto::{decode_wif, WifKey},
+    tx::CreateAccountTx,
+};
 
 pub fn account_id_to_address(_wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
     let id = args
@@ -183,7 +186,7 @@ pub fn build_update_tx(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), Str
 
 pub fn import(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
     check_unlocked!(wallet);
-    let acc_name = args.value_of("name").unwrap();
+    let acc_name = db::normalize_account_name(args.value_of("name").unwrap())?;
     let acc_address = args.value_of("account").unwrap();
     let acc_address = AccountId::from_wif(acc_address)
         .map_err(|_| format!("Failed to parse account address: {}", acc_address))?;
@@ -191,17 +194,21 @@ pub fn import(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
         let mut keys = vec![];
         let wifs: Vec<&str> = args.values_of("wif").unwrap().collect();
         for wif in wifs {
-            let wif = PrivateKey::from_wif(wif).map_err(|_| format!("Invalid wif: {}", wif))?;
-            keys.push(wif);
+            match decode_wif(wif).map_err(|_| format!("Invalid wif: {}", wif))? {
+                WifKey::Private(key) => keys.push(key),
+                WifKey::Public(_) => {
+                    return Err(format!(
+                        "Expected a private key but got a public key: {}",
+                        wif
+                    ));
+                }
+            }
         }
         keys
     };
 
     for (name, acc) in wallet.db.get_accounts() {
-        if name == acc_name {
-            println!("An account with this name already exists");
-            return Ok(());
-        } else if acc.id == acc_address {
+        if acc.id == acc_address {
             println!(
                 "Account address already exists under the account name `{}`",
                 name
@@ -215,8 +222,7 @@ pub fn import(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
         keys,
     };
 
-    wallet.db.set_account(acc_name, wallet_acc);
-    Ok(())
+    wallet.db.create_account(acc_name, wallet_acc)
 }
 
 pub fn get(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
@@ -254,6 +260,30 @@ pub fn get_acc_info(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String
     Ok(())
 }
 
+pub fn balance(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
+    check_unlocked!(wallet);
+
+    let account_id = args.value_of("account").unwrap();
+    let account_id = match wallet.db.get_account(account_id) {
+        Some(acc) => acc.id,
+        None => AccountId::from_wif(account_id)
+            .map_err(|e| format!("Invalid account or key: {:?}", e))?,
+    };
+
+    let res = send_rpc_req(wallet, rpc::Request::GetAccount(account_id))?;
+    println!("Balance: {}", balance_from_res(res)?.to_string());
+    Ok(())
+}
+
+fn balance_from_res(res: Msg) -> Result<Asset, String> {
+    match res.body {
+        Body::Response(rpc::Response::GetAccount(Some(acc))) => Ok(acc.balance),
+        Body::Response(rpc::Response::GetAccount(None)) => Err("Account not found".to_string()),
+        Body::Error(e) => Err(format!("Failed to get account: {:?}", e)),
+        _ => Err(format!("Unexpected response: {:?}", res)),
+    }
+}
+
 pub fn delete(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
     check_unlocked!(wallet);
     let account_name = args.value_of("name").unwrap();
@@ -265,11 +295,99 @@ pub fn delete(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
     Ok(())
 }
 
-pub fn list(wallet: &mut Wallet, _args: &ArgMatches) -> Result<(), String> {
+pub fn list(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
     check_unlocked!(wallet);
+    let accounts = wallet.db.get_accounts();
+    let balances = if args.is_present("balances") {
+        fetch_balances(wallet, &accounts)
+    } else {
+        vec![None; accounts.len()]
+    };
+
     println!("Accounts:");
-    for (name, acc) in wallet.db.get_accounts() {
-        println!("  {} => {} (raw ID: {})", name, acc.id.to_wif(), acc.id);
+    for ((name, acc), balance) in accounts.iter().zip(balances) {
+        println!("{}", account_line(name, acc.id, balance.as_deref()));
     }
     Ok(())
 }
+
+/// Fetches the balance of every account from the node. Accounts the node doesn't know about are
+/// marked as unknown. If the node can't be reached, the remaining balances are left out.
+fn fetch_balances(
+    wallet: &mut Wallet,
+    accounts: &[(String, WalletAccount)],
+) -> Vec<Option<String>> {
+    let mut balances = Vec::with_capacity(accounts.len());
+    for chunk in accounts.chunks(usize::from(rpc::MAX_BALANCE_BATCH)) {
+        let ids = chunk.iter().map(|(_, acc)| acc.id).collect();
+        let res =
+            send_rpc_req(wallet, rpc::Request::GetBalances(ids)).and_then(|res| match res.body {
+                Body::Response(rpc::Response::GetBalances(res)) => Ok(res),
+                Body::Error(e) => Err(format!("Failed to get balances: {:?}", e)),
+                _ => Err(format!("Unexpected response: {:?}", res)),
+            });
+        match res {
+            Ok(res) => balances.extend(res.into_iter().map(|balance| match balance {
+                Some(balance) => Some(balance.to_string()),
+                None => Some("unknown".to_string()),
+            })),
+            Err(e) => {
+                println!("Unable to fetch balances: {}", e);
+                break;
+            }
+        }
+    }
+    balances.resize(accounts.len(), None);
+    balances
+}
+
+fn account_line(name: &str, id: AccountId, balance: Option<&str>) -> String {
+    let mut line = format!("  {} => {} (raw ID: {})", name, id.to_wif(), id);
+    if let Some(balance) = balance {
+        line.push_str(&format!(" balance: {}", balance));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn balance_from_account_res() {
+        let acc = Account {
+            id: 1,
+            balance: Asset::new(12345),
+            script: Script::new(vec![]),
+            permissions: Permissions {
+                threshold: 1,
+                keys: vec![KeyPair::gen().0],
+            },
+            destroyed: false,
+        };
+        let res = Msg {
+            id: 0,
+            body: Body::Response(rpc::Response::GetAccount(Some(acc))),
+        };
+        assert_eq!(balance_from_res(res), Ok(Asset::new(12345)));
+
+        let res = Msg {
+            id: 0,
+            body: Body::Response(rpc::Response::GetAccount(None)),
+        };
+        assert_eq!(balance_from_res(res), Err("Account not found".to_string()));
+    }
+
+    #[test]
+    fn account_line_with_balance() {
+        let addr = AccountId::to_wif(&1);
+        assert_eq!(
+            account_line("foo", 1, None),
+            format!("  foo => {} (raw ID: 1)", addr)
+        );
+        assert_eq!(
+            account_line("foo", 1, Some("1.00000 TEST")),
+            format!("  foo => {} (raw ID: 1) balance: 1.00000 TEST", addr)
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use super::*;
+use clap::ArgMatches;
+
+pub fn add(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
+    check_unlocked!(wallet);
+    let label = args.value_of("label").unwrap();
+    let address = args.value_of("address").unwrap();
+    let address = AccountId::from_wif(address)
+        .map_err(|_| format!("Failed to parse account address: {}", address))?;
+    wallet.db.add_contact(label, address)?;
+    println!("Contact added");
+    Ok(())
+}
+
+pub fn remove(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
+    check_unlocked!(wallet);
+    let label = args.value_of("label").unwrap();
+    if wallet.db.del_contact(label) {
+        println!("Contact removed");
+    } else {
+        println!("Contact not found");
+    }
+    Ok(())
+}
+
+pub fn list(wallet: &mut Wallet, _args: &ArgMatches) -> Result<(), String> {
+    check_unlocked!(wallet);
+    println!("Contacts:");
+    for (label, id) in wallet.db.get_contacts() {
+        println!("  {} => {} (raw ID: {})", label, id.to_wif(), id);
+    }
+    Ok(())
+}
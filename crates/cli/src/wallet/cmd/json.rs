@@ -0,0 +1,151 @@
+use godcoin::prelude::*;
+use serde_json::{json, Map, Value};
+
+fn to_hex(bytes: &[u8]) -> String {
+    faster_hex::hex_string(bytes).unwrap()
+}
+
+fn perms_to_json(perms: &Permissions) -> Value {
+    json!({
+        "threshold": perms.threshold,
+        "keys": perms.keys.iter().map(|k| k.to_wif().to_string()).collect::<Vec<_>>(),
+    })
+}
+
+pub fn tx_to_json(tx: &TxVariant) -> Value {
+    let mut obj = Map::new();
+    obj.insert("txid".into(), to_hex(tx.calc_txid().as_ref()).into());
+
+    match tx {
+        TxVariant::V0(var) => {
+            obj.insert("version".into(), 0.into());
+            obj.insert("nonce".into(), var.nonce.into());
+            obj.insert("expiry".into(), var.expiry.into());
+            obj.insert("fee".into(), var.fee.to_string().into());
+            let sigs = var
+                .signature_pairs
+                .iter()
+                .map(sig_pair_to_json)
+                .collect::<Vec<_>>();
+            obj.insert("signature_pairs".into(), sigs.into());
+
+            let (tx_type, fields) = match var {
+                TxVariantV0::OwnerTx(tx) => (
+                    "owner",
+                    json!({
+                        "minter": tx.minter.to_wif().to_string(),
+                        "wallet": tx.wallet.to_wif().to_string(),
+                    }),
+                ),
+                TxVariantV0::MintTx(tx) => (
+                    "mint",
+                    json!({
+                        "to": tx.to.to_wif().to_string(),
+                        "amount": tx.amount.to_string(),
+                        "attachment": to_hex(&tx.attachment),
+                        "attachment_name": tx.attachment_name,
+                    }),
+                ),
+                TxVariantV0::CreateAccountTx(tx) => (
+                    "create_account",
+                    json!({
+                        "creator": tx.creator.to_wif().to_string(),
+                        "account": {
+                            "id": tx.account.id,
+                            "address": tx.account.id.to_wif().to_string(),
+                            "balance": tx.account.balance.to_string(),
+                            "script": to_hex(&tx.account.script),
+                            "permissions": perms_to_json(&tx.account.permissions),
+                        },
+                    }),
+                ),
+                TxVariantV0::UpdateAccountTx(tx) => (
+                    "update_account",
+                    json!({
+                        "account": tx.account_id.to_wif().to_string(),
+                        "new_script": tx.new_script.as_ref().map(|s| to_hex(s)),
+                        "new_permissions": tx.new_permissions.as_ref().map(perms_to_json),
+                    }),
+                ),
+                TxVariantV0::TransferTx(tx) => (
+                    "transfer",
+                    json!({
+                        "from": tx.from.to_wif().to_string(),
+                        "call_fn": tx.call_fn,
+                        "args": to_hex(&tx.args),
+                        "amount": tx.amount.to_string(),
+                        "memo": to_hex(&tx.memo),
+                    }),
+                ),
+            };
+            obj.insert("type".into(), tx_type.into());
+            if let Value::Object(fields) = fields {
+                obj.extend(fields);
+            }
+        }
+    }
+
+    Value::Object(obj)
+}
+
+fn sig_pair_to_json(pair: &SigPair) -> Value {
+    json!({
+        "pub_key": pair.pub_key.to_wif().to_string(),
+        "signature": to_hex(pair.signature.as_ref()),
+    })
+}
+
+fn log_entry_to_json(entry: &LogEntry) -> Value {
+    match entry {
+        LogEntry::Transfer(to, amount) => json!({
+            "type": "transfer",
+            "to": to.to_wif().to_string(),
+            "amount": amount.to_string(),
+        }),
+        LogEntry::Destroy(to) => json!({
+            "type": "destroy",
+            "to": to.to_wif().to_string(),
+        }),
+    }
+}
+
+pub fn header_to_json(header: &BlockHeader) -> Value {
+    let version = match header {
+        BlockHeader::V0(_) => 0,
+        BlockHeader::V1(_) => 1,
+    };
+    json!({
+        "version": version,
+        "hash": to_hex(header.calc_hash().as_ref()),
+        "previous_hash": to_hex(header.previous_hash().as_ref()),
+        "height": header.height(),
+        "timestamp": header.timestamp(),
+        "receipt_root": to_hex(header.receipt_root().as_ref()),
+    })
+}
+
+pub fn block_to_json(block: &Block) -> Value {
+    let mut obj = Map::new();
+    if let Value::Object(header) = header_to_json(&block.header()) {
+        obj.extend(header);
+    }
+    let signers = block
+        .signers()
+        .iter()
+        .map(sig_pair_to_json)
+        .collect::<Vec<_>>();
+    obj.insert("signers".into(), signers.into());
+    obj.insert("rewards".into(), block.rewards().to_string().into());
+    let receipts = block
+        .receipts()
+        .iter()
+        .map(|r| {
+            json!({
+                "tx": tx_to_json(&r.tx),
+                "log": r.log.iter().map(log_entry_to_json).collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+    obj.insert("receipts".into(), receipts.into());
+    Value::Object(obj)
+}
@@ -1,15 +1,17 @@
 use super::*;
 use clap::ArgMatches;
-use godcoin::{constants::*, prelude::*};
+use godcoin::{constants::*, crypto::seal_memo, prelude::*};
 use std::{
-    fs::File,
-    io::{Cursor, Read},
+    fs::{self, File, OpenOptions},
+    io::{Cursor, Read, Write},
     path::Path,
 };
 
 #[macro_use]
 pub mod util;
 pub mod account;
+pub mod contact;
+pub mod json;
 
 use util::{send_print_rpc_req, send_rpc_req};
 
@@ -55,6 +57,36 @@ pub fn unlock(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
     Ok(())
 }
 
+pub fn export_wallet(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
+    check_unlocked!(wallet);
+
+    let path = Path::new(args.value_of("path").unwrap());
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|e| format!("Failed to create file: {:?}", e))?;
+    let bytes = wallet.db.export_accounts();
+    file.write_all(&bytes)
+        .map_err(|e| format!("Failed to write file: {:?}", e))?;
+    println!("Exported wallet to {:?}", path);
+    Ok(())
+}
+
+pub fn import_wallet(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
+    check_unlocked!(wallet);
+
+    let path = Path::new(args.value_of("path").unwrap());
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read file: {:?}", e))?;
+    let pass = args.value_of("password").unwrap();
+    let overwrite = args.is_present("overwrite");
+    let count = wallet
+        .db
+        .import_accounts(&bytes, pass.as_bytes(), overwrite)?;
+    println!("Imported {} account(s)", count);
+    Ok(())
+}
+
 pub fn build_script(_wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
     let ops: Vec<&str> = args.values_of("ops").unwrap().collect();
     let script = script_builder::build(&ops);
@@ -67,6 +99,7 @@ pub fn build_script(_wallet: &mut Wallet, args: &ArgMatches) -> Result<(), Strin
                 );
             }
             println!("{:?}", script);
+            println!("Script hash: {}", script.hash().0);
         }
         Err(e) => {
             println!("{:?}", e);
@@ -112,44 +145,132 @@ pub fn check_script_size(_wallet: &mut Wallet, args: &ArgMatches) -> Result<(),
 }
 
 pub fn decode_tx(_wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
-    let hex = args.value_of("hex").unwrap();
+    let hex = util::read_hex_arg(args.value_of("hex").unwrap())?;
+    let format = args.value_of("format").unwrap();
+    println!("{}", format_decoded_tx(&hex, format)?);
+
+    Ok(())
+}
+
+fn format_decoded_tx(hex: &str, format: &str) -> Result<String, String> {
     let tx_bytes = hex_to_bytes!(hex)?;
     let cursor = &mut Cursor::<&[u8]>::new(&tx_bytes);
     let tx = TxVariant::deserialize(cursor).ok_or("Failed to decode tx")?;
-    println!("{:#?}", tx);
+    match format {
+        "json" => Ok(json::tx_to_json(&tx).to_string()),
+        _ => Ok(format!("{:#?}", tx)),
+    }
+}
+
+pub fn sign_tx(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
+    let tx = signed_tx(wallet, args)?;
+
+    let mut tx_bytes = Vec::with_capacity(tx.byte_size());
+    tx.serialize(&mut tx_bytes);
+    println!("{}", faster_hex::hex_string(&tx_bytes).unwrap());
 
     Ok(())
 }
 
-pub fn sign_tx(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
-    check_unlocked!(wallet);
-    let hex = args.value_of("hex").unwrap();
-    let accounts: Vec<&str> = args.values_of("account").unwrap().collect();
+fn signed_tx(wallet: &mut Wallet, args: &ArgMatches) -> Result<TxVariant, String> {
+    let hex = util::read_hex_arg(args.value_of("hex").unwrap())?;
+    let tx_bytes = hex_to_bytes!(hex)?;
+    let mut tx = {
+        let cursor = &mut Cursor::<&[u8]>::new(&tx_bytes);
+        TxVariant::deserialize(cursor).ok_or("Failed to decode tx")?
+    };
+    append_sigs(wallet, args, &mut tx)?;
+    Ok(tx)
+}
+
+pub fn resign_tx(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
+    let tx = resigned_tx(wallet, args)?;
+
+    let mut tx_bytes = Vec::with_capacity(tx.byte_size());
+    tx.serialize(&mut tx_bytes);
+    println!("{}", faster_hex::hex_string(&tx_bytes).unwrap());
 
-    let mut tx_bytes = hex_to_bytes!(hex)?;
+    Ok(())
+}
+
+fn resigned_tx(wallet: &mut Wallet, args: &ArgMatches) -> Result<TxVariant, String> {
+    let hex = args.value_of("hex").unwrap();
+    let tx_bytes = hex_to_bytes!(hex)?;
     let mut tx = {
         let cursor = &mut Cursor::<&[u8]>::new(&tx_bytes);
         TxVariant::deserialize(cursor).ok_or("Failed to decode tx")?
     };
 
-    for account in accounts {
-        let account = wallet
-            .db
-            .get_account(account)
-            .ok_or("Account does not exist")?;
-        for key in account.keys {
-            tx.append_sign(&key);
+    let sigs = tx.sigs_mut();
+    if !sigs.is_empty() {
+        println!("WARNING: discarding {} existing signature(s)", sigs.len());
+        sigs.clear();
+    }
+    append_sigs(wallet, args, &mut tx)?;
+    Ok(tx)
+}
+
+fn append_sigs(wallet: &mut Wallet, args: &ArgMatches, tx: &mut TxVariant) -> Result<(), String> {
+    // Keys are paired with their argument position so the signatures are appended in the same
+    // order the accounts and WIFs were provided. Private keys are zeroed when dropped.
+    let mut signers: Vec<(usize, KeyPair)> = vec![];
+    if let (Some(indices), Some(accounts)) = (args.indices_of("account"), args.values_of("account"))
+    {
+        check_unlocked!(wallet);
+        for (index, account) in indices.zip(accounts) {
+            let account = wallet
+                .db
+                .get_account(account)
+                .ok_or("Account does not exist")?;
+            for key in account.keys {
+                signers.push((index, key));
+            }
+        }
+    }
+    if let (Some(indices), Some(wifs)) = (args.indices_of("wif"), args.values_of("wif")) {
+        for (index, wif) in indices.zip(wifs) {
+            let key = PrivateKey::from_wif(wif).map_err(|e| format!("Invalid wif: {}", e))?;
+            signers.push((index, key));
         }
     }
+    signers.sort_by_key(|(index, _)| *index);
 
-    tx_bytes.clear();
-    tx_bytes.reserve(128);
-    tx.serialize(&mut tx_bytes);
-    println!("{}", faster_hex::hex_string(&tx_bytes).unwrap());
+    for (_, key) in &signers {
+        tx.append_sign(key);
+    }
+    Ok(())
+}
+
+pub fn verify_tx(_wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
+    let tx_bytes = hex_to_bytes!(args.value_of("hex").unwrap())?;
+    let tx = {
+        let cursor = &mut Cursor::<&[u8]>::new(&tx_bytes);
+        TxVariant::deserialize(cursor).ok_or("Failed to decode tx")?
+    };
+
+    let txid = tx.calc_txid();
+    println!("TxId: {}", faster_hex::hex_string(txid.as_ref()).unwrap());
+
+    let results = verify_sigs(&tx);
+    for (index, (pub_key, valid)) in results.iter().enumerate() {
+        let marker = if *valid { "valid" } else { "invalid" };
+        println!("[{}] {} ({})", index, pub_key.to_wif(), marker);
+    }
+    let valid_count = results.iter().filter(|(_, valid)| *valid).count();
+    println!("{}/{} signatures are valid", valid_count, results.len());
 
     Ok(())
 }
 
+/// Verifies every signature pair in the transaction against its transaction ID.
+fn verify_sigs(tx: &TxVariant) -> Vec<(PublicKey, bool)> {
+    let txid = tx.calc_txid();
+    tx.sigs()
+        .iter()
+        .map(|pair| (pair.pub_key.clone(), pair.verify(txid.as_ref())))
+        .collect()
+}
+
 pub fn unsign_tx(_wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
     let sig_pos: usize = args
         .value_of("index")
@@ -174,18 +295,112 @@ pub fn unsign_tx(_wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String>
     Ok(())
 }
 
+pub fn export_sigs(_wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
+    let tx_bytes = hex_to_bytes!(args.value_of("hex").unwrap())?;
+    let tx = {
+        let cursor = &mut Cursor::<&[u8]>::new(&tx_bytes);
+        TxVariant::deserialize(cursor).ok_or("Failed to decode tx")?
+    };
+
+    for (index, pair) in tx.sigs().iter().enumerate() {
+        println!("[{}] {} {}", index, pair.pub_key.to_wif(), pair);
+    }
+
+    Ok(())
+}
+
+pub fn attach_sig(_wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
+    let tx = attached_tx(args)?;
+
+    let mut tx_bytes = Vec::with_capacity(tx.byte_size());
+    tx.serialize(&mut tx_bytes);
+    println!("{}", faster_hex::hex_string(&tx_bytes).unwrap());
+
+    Ok(())
+}
+
+fn attached_tx(args: &ArgMatches) -> Result<TxVariant, String> {
+    let tx_bytes = hex_to_bytes!(args.value_of("hex").unwrap())?;
+    let mut tx = {
+        let cursor = &mut Cursor::<&[u8]>::new(&tx_bytes);
+        TxVariant::deserialize(cursor).ok_or("Failed to decode tx")?
+    };
+
+    let txid = tx.calc_txid();
+    for sig in args.values_of("sig").unwrap() {
+        let pair: SigPair = sig
+            .parse()
+            .map_err(|e| format!("Failed to parse signature {}: {}", sig, e))?;
+        if !pair.verify(txid.as_ref()) {
+            return Err(format!("Signature {} does not sign this transaction", sig));
+        }
+        tx.sigs_mut().push(pair);
+    }
+    Ok(tx)
+}
+
 pub fn broadcast(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
-    let hex = args.value_of("hex").unwrap();
+    let hex = util::read_hex_arg(args.value_of("hex").unwrap())?;
     let tx_bytes = hex_to_bytes!(hex)?;
     let tx = {
         let cursor = &mut Cursor::<&[u8]>::new(&tx_bytes);
         TxVariant::deserialize(cursor).ok_or("Failed to decode tx")?
     };
 
+    if !args.is_present("force") {
+        if let Some(req_fee) = get_required_fee(wallet, &tx)? {
+            if tx.fee() < req_fee {
+                return Err(format!(
+                    "WARNING: Transaction fee {} is below the required fee {}, use --force to \
+                     broadcast anyway",
+                    tx.fee().to_string(),
+                    req_fee.to_string()
+                ));
+            }
+        }
+    }
+
     send_print_rpc_req(wallet, rpc::Request::Broadcast(tx));
     Ok(())
 }
 
+/// Queries the node for the minimum fee the account paying for the transaction is currently
+/// required to pay. Returns `None` when the transaction has no paying account or the node can't
+/// provide its fee, in which case the node reports any fee errors on broadcast.
+fn get_required_fee(wallet: &mut Wallet, tx: &TxVariant) -> Result<Option<Asset>, String> {
+    // Account transactions pay a multiple of the total fee
+    let (payer, fee_mult) = match tx {
+        TxVariant::V0(tx) => match tx {
+            TxVariantV0::OwnerTx(_) | TxVariantV0::MintTx(_) => return Ok(None),
+            TxVariantV0::CreateAccountTx(tx) => (tx.creator, Some(GRAEL_ACC_CREATE_FEE_MULT)),
+            TxVariantV0::UpdateAccountTx(tx) => (tx.account_id, Some(GRAEL_ACC_CREATE_FEE_MULT)),
+            TxVariantV0::TransferTx(tx) => (tx.from, None),
+        },
+    };
+
+    let res = send_rpc_req(wallet, rpc::Request::GetAccountInfo(payer))?;
+    let fee = match res.body {
+        Body::Response(rpc::Response::GetAccountInfo(info)) => info.total_fee(),
+        _ => None,
+    };
+    Ok(match fee_mult {
+        Some(mult) => fee.and_then(|fee| fee.checked_mul(mult)),
+        None => fee,
+    })
+}
+
+pub fn check_tx(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
+    let hex = args.value_of("hex").unwrap();
+    let tx_bytes = hex_to_bytes!(hex)?;
+    let tx = {
+        let cursor = &mut Cursor::<&[u8]>::new(&tx_bytes);
+        TxVariant::deserialize(cursor).ok_or("Failed to decode tx")?
+    };
+
+    send_print_rpc_req(wallet, rpc::Request::CheckTx(tx));
+    Ok(())
+}
+
 pub fn build_mint_tx(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
     let nonce: u32 = {
         let mut nonce = [0; 4];
@@ -242,7 +457,7 @@ pub fn build_mint_tx(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), Strin
         base: Tx {
             nonce,
             expiry,
-            fee: Asset::new(0),
+            fee: Asset::zero(),
             signature_pairs: vec![],
         },
         to: owner_wallet.clone(),
@@ -250,16 +465,23 @@ pub fn build_mint_tx(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), Strin
         attachment,
         attachment_name: attachment_name.to_string(),
     }));
-    let mut buf = Vec::with_capacity(4096);
+    let mut buf = Vec::with_capacity(mint_tx.byte_size());
     mint_tx.serialize(&mut buf);
     println!("{}", faster_hex::hex_string(&buf).unwrap());
 
     Ok(())
 }
 
-pub fn build_transfer_tx(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
-    check_unlocked!(wallet);
+pub fn build_owner_tx(_wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
+    let owner_tx = create_owner_tx(args)?;
+    let mut buf = Vec::with_capacity(owner_tx.byte_size());
+    owner_tx.serialize(&mut buf);
+    println!("{}", faster_hex::hex_string(&buf).unwrap());
+
+    Ok(())
+}
 
+fn create_owner_tx(args: &ArgMatches) -> Result<TxVariant, String> {
     let nonce: u32 = {
         let mut nonce = [0; 4];
         sodiumoxide::randombytes::randombytes_into(&mut nonce);
@@ -275,6 +497,40 @@ pub fn build_transfer_tx(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), S
         godcoin::get_epoch_time() + expiry
     };
 
+    let minter = PublicKey::from_wif(args.value_of("minter").unwrap())
+        .map_err(|e| format!("Failed to parse minter public key: {:?}", e))?;
+    let wallet_acc = AccountId::from_wif(args.value_of("wallet").unwrap())
+        .map_err(|e| format!("Failed to parse wallet account address: {:?}", e))?;
+    let fee = args
+        .value_of("fee")
+        .unwrap()
+        .parse()
+        .map_err(|_| "Failed to parse asset fee")?;
+
+    Ok(TxVariant::V0(TxVariantV0::OwnerTx(OwnerTx {
+        base: Tx {
+            nonce,
+            expiry,
+            fee,
+            signature_pairs: vec![],
+        },
+        minter,
+        wallet: wallet_acc,
+    })))
+}
+
+pub fn build_transfer_tx(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
+    check_unlocked!(wallet);
+
+    let expiry: u64 = {
+        let expiry: u64 = args
+            .value_of("expiry")
+            .unwrap()
+            .parse()
+            .map_err(|_| "Failed to parse expiry ms".to_string())?;
+        godcoin::get_epoch_time() + expiry
+    };
+
     let from_acc = args.value_of("from_account").unwrap();
     let from_acc = match wallet.db.get_account(from_acc) {
         Some(acc) => acc.id,
@@ -282,28 +538,72 @@ pub fn build_transfer_tx(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), S
             .map_err(|e| format!("Failed to parse account address: {:?}", e))?,
     };
 
+    let nonce: u32 = match args.value_of("nonce") {
+        Some(nonce) => nonce
+            .parse()
+            .map_err(|_| "Failed to parse nonce".to_string())?,
+        // The nonce must be greater than the nonce of the last transfer sent from the account
+        None => {
+            let res = send_rpc_req(wallet, rpc::Request::GetAccountNonce(from_acc))
+                .map_err(|e| format!("{}, pass --nonce to build the transaction offline", e))?;
+            match res.body {
+                Body::Response(rpc::Response::GetAccountNonce(Some(nonce))) => nonce
+                    .checked_add(1)
+                    .ok_or("The account has no transfer nonces left")?,
+                Body::Response(rpc::Response::GetAccountNonce(None)) => 0,
+                Body::Error(e) => return Err(format!("Failed to get account nonce: {:?}", e)),
+                _ => return Err(format!("Unexpected response: {:?}", res)),
+            }
+        }
+    };
+
     let call_fn = args
         .value_of("call_fn")
         .unwrap()
         .parse()
         .map_err(|e| format!("Failed to parse call_fn id: {}", e))?;
-    let call_args = if let Some(args) = args.value_of("args") {
-        hex_to_bytes!(args)?
-    } else {
-        vec![]
-    };
-
     let amount = args
         .value_of("amount")
         .unwrap()
         .parse()
         .map_err(|_| "Failed to parse asset amount")?;
+    let call_args = if let Some(to) = args.value_of("to") {
+        // Build the arguments of the default account script transfer function
+        let to = wallet
+            .db
+            .resolve_address(to)
+            .ok_or_else(|| format!("Unknown contact or account address: {}", to))?;
+        let mut call_args = Vec::with_capacity(16);
+        call_args.push_u64(to);
+        call_args.push_asset(amount);
+        call_args
+    } else if let Some(args) = args.value_of("args") {
+        hex_to_bytes!(args)?
+    } else {
+        vec![]
+    };
+
     let fee = args
         .value_of("fee")
         .unwrap()
         .parse()
         .map_err(|_| "Failed to parse asset fee")?;
     let memo = args.value_of("memo").unwrap_or("").as_bytes();
+    let memo = match args.value_of("encrypt_memo") {
+        Some(recipient) => {
+            let recipient = PublicKey::from_wif(recipient)
+                .map_err(|e| format!("Failed to parse recipient public key: {:?}", e))?;
+            seal_memo(memo, &recipient).ok_or("Failed to encrypt memo")?
+        }
+        None => memo.to_vec(),
+    };
+    if memo.len() > MAX_MEMO_BYTE_SIZE {
+        return Err(format!(
+            "Memo is {} bytes, exceeding the maximum of {} bytes",
+            memo.len(),
+            MAX_MEMO_BYTE_SIZE
+        ));
+    }
 
     let transfer_tx = TxVariant::V0(TxVariantV0::TransferTx(TransferTx {
         base: Tx {
@@ -316,10 +616,10 @@ pub fn build_transfer_tx(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), S
         call_fn,
         args: call_args,
         amount,
-        memo: memo.into(),
+        memo,
     }));
 
-    let mut buf = Vec::with_capacity(4096);
+    let mut buf = Vec::with_capacity(transfer_tx.byte_size());
     transfer_tx.serialize(&mut buf);
     println!("{}", faster_hex::hex_string(&buf).unwrap());
 
@@ -338,6 +638,677 @@ pub fn get_block(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
         .parse()
         .map_err(|_| "Failed to parse height argument".to_string())?;
 
-    send_print_rpc_req(wallet, rpc::Request::GetBlock(height));
+    let format = args.value_of("format").unwrap();
+    if format == "debug" {
+        send_print_rpc_req(wallet, rpc::Request::GetBlock(height));
+        return Ok(());
+    }
+
+    let res = send_rpc_req(wallet, rpc::Request::GetBlock(height))?;
+    match res.body {
+        Body::Response(rpc::Response::GetBlock(block)) => {
+            println!("{}", format_block(&block, format));
+            Ok(())
+        }
+        Body::Error(e) => Err(format!("Failed to get block: {:?}", e)),
+        _ => Err(format!("Unexpected response: {:?}", res)),
+    }
+}
+
+fn format_block(block: &FilteredBlock, format: &str) -> String {
+    match (block, format) {
+        (FilteredBlock::Block(block), "hex") => {
+            let mut buf = Vec::with_capacity(4096);
+            block.serialize(&mut buf);
+            faster_hex::hex_string(&buf).unwrap()
+        }
+        (FilteredBlock::Header((header, _)), "hex") => {
+            let mut buf = Vec::with_capacity(1024);
+            header.serialize(&mut buf);
+            faster_hex::hex_string(&buf).unwrap()
+        }
+        (FilteredBlock::Block(block), "json") => json::block_to_json(block).to_string(),
+        (FilteredBlock::Header((header, _)), "json") => json::header_to_json(header).to_string(),
+        _ => format!("{:#?}", block),
+    }
+}
+
+pub fn get_tx(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
+    let txid = godcoin::crypto::Digest::from_hex(args.value_of("txid").unwrap())
+        .map_err(|e| format!("Invalid txid: {}", e))?;
+    send_print_rpc_req(
+        wallet,
+        rpc::Request::GetTransaction(TxId::from_digest(txid)),
+    );
     Ok(())
 }
+
+pub fn get_script(wallet: &mut Wallet, args: &ArgMatches) -> Result<(), String> {
+    let hash = godcoin::crypto::Digest::from_hex(args.value_of("address").unwrap())
+        .map_err(|e| format!("Invalid script hash: {}", e))?;
+    let res = send_rpc_req(wallet, rpc::Request::GetScript(ScriptHash(hash)))?;
+    match res.body {
+        Body::Response(rpc::Response::GetScript(Some(script))) => {
+            let ops = script
+                .disassemble()
+                .map_err(|e| format!("Failed to disassemble script: {:?}", e))?;
+            for op in ops {
+                println!("{:?}", op);
+            }
+            Ok(())
+        }
+        Body::Response(rpc::Response::GetScript(None)) => {
+            println!("Script not found, no account uses a script with this hash");
+            Ok(())
+        }
+        Body::Error(e) => Err(format!("Failed to get script: {:?}", e)),
+        _ => Err(format!("Unexpected response: {:?}", res)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::WalletAccount;
+    use native_tls::{Identity, TlsAcceptor};
+    use std::{
+        env, fs,
+        net::TcpListener,
+        path::PathBuf,
+        sync::{mpsc, Arc},
+        thread,
+    };
+    use tungstenite::Message;
+
+    struct TestWallet(Wallet, PathBuf);
+
+    impl TestWallet {
+        fn new() -> Self {
+            Self::with_url("ws://localhost", false)
+        }
+
+        fn with_url(url: &str, insecure_tls: bool) -> Self {
+            let mut home = env::temp_dir();
+            let mut num = [0; 8];
+            sodiumoxide::randombytes::randombytes_into(&mut num);
+            home.push(format!("godcoin_wallet_test_{}", u64::from_be_bytes(num)));
+            fs::create_dir(&home).unwrap();
+            Self(Wallet::new(home.clone(), url, insecure_tls).unwrap(), home)
+        }
+    }
+
+    impl Drop for TestWallet {
+        fn drop(&mut self) {
+            fs::remove_dir_all(&self.1).expect("Failed to rm dir");
+        }
+    }
+
+    fn create_tx_hex() -> (TxVariant, String) {
+        let tx = TxVariant::V0(TxVariantV0::TransferTx(TransferTx {
+            base: Tx {
+                nonce: 123,
+                expiry: 1234567890,
+                fee: Asset::new(100_000),
+                signature_pairs: vec![],
+            },
+            from: 1,
+            call_fn: 0,
+            args: vec![],
+            amount: Asset::new(0),
+            memo: vec![],
+        }));
+        let mut buf = vec![];
+        tx.serialize(&mut buf);
+        let hex = faster_hex::hex_string(&buf).unwrap();
+        (tx, hex)
+    }
+
+    fn sign_with_args(wallet: &mut Wallet, args: &[&str]) -> Result<TxVariant, String> {
+        let matches = cli().get_matches_from_safe(args).map_err(|e| e.message)?;
+        let args = matches.subcommand_matches("sign_tx").unwrap();
+        signed_tx(wallet, args)
+    }
+
+    #[test]
+    fn sign_tx_with_wif() {
+        let mut wallet = TestWallet::new();
+        let (tx, hex) = create_tx_hex();
+        let key = KeyPair::gen();
+        let wif = key.1.to_wif();
+
+        let signed = sign_with_args(&mut wallet.0, &["sign_tx", "--hex", &hex, "--wif", &wif]);
+        let signed = signed.unwrap();
+        assert_eq!(signed.sigs().len(), 1);
+        let pair = &signed.sigs()[0];
+        assert_eq!(pair.pub_key, key.0);
+        assert!(pair.verify(tx.calc_txid().as_ref()));
+
+        let res = sign_with_args(&mut wallet.0, &["sign_tx", "--hex", &hex, "--wif", "abc"]);
+        assert!(res.is_err());
+    }
+
+    /// Spawns a TLS websocket server with a self-signed certificate that answers a single request
+    /// with an invalid request error. Returns the port the server is listening on.
+    fn spawn_wss_server() -> u16 {
+        let identity = Identity::from_pkcs12(
+            include_bytes!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/testdata/localhost.p12"
+            )),
+            "godcoin",
+        )
+        .unwrap();
+        let acceptor = TlsAcceptor::new(identity).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let stream = match acceptor.accept(stream) {
+                Ok(stream) => stream,
+                Err(_) => return,
+            };
+            let mut ws = tungstenite::accept(stream).unwrap();
+            let req = match ws.read_message().unwrap() {
+                Message::Binary(buf) => Msg::deserialize(&mut Cursor::<&[u8]>::new(&buf)).unwrap(),
+                msg => panic!("Unexpected message: {:?}", msg),
+            };
+            let res = Msg {
+                id: req.id,
+                body: Body::Error(net::ErrorKind::InvalidRequest),
+            };
+            let mut buf = vec![];
+            res.serialize(&mut buf);
+            ws.write_message(Message::Binary(buf)).unwrap();
+            let _ = ws.close(None);
+        });
+
+        port
+    }
+
+    /// Spawns a plain websocket node that answers every request with the `handler` response. The
+    /// received requests are sent to the returned channel.
+    fn spawn_ws_node<F>(handler: F) -> (u16, mpsc::Receiver<rpc::Request>)
+    where
+        F: Fn(&rpc::Request) -> Body + Send + 'static,
+    {
+        spawn_ws_node_with_msgs(move |id, req| {
+            vec![Msg {
+                id,
+                body: handler(req),
+            }]
+        })
+    }
+
+    /// Spawns a node which replies to each request with every message returned by the handler.
+    fn spawn_ws_node_with_msgs<F>(handler: F) -> (u16, mpsc::Receiver<rpc::Request>)
+    where
+        F: Fn(u32, &rpc::Request) -> Vec<Msg> + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut ws = tungstenite::accept(stream.unwrap()).unwrap();
+                let req = match ws.read_message().unwrap() {
+                    Message::Binary(buf) => {
+                        Msg::deserialize(&mut Cursor::<&[u8]>::new(&buf)).unwrap()
+                    }
+                    msg => panic!("Unexpected message: {:?}", msg),
+                };
+                let req_body = match req.body {
+                    Body::Request(req_body) => req_body,
+                    body => panic!("Expected a request: {:?}", body),
+                };
+                for res in handler(req.id, &req_body) {
+                    let mut buf = vec![];
+                    res.serialize(&mut buf);
+                    ws.write_message(Message::Binary(buf)).unwrap();
+                }
+                let _ = ws.close(None);
+                if tx.send(req_body).is_err() {
+                    break;
+                }
+            }
+        });
+
+        (port, rx)
+    }
+
+    #[test]
+    fn responses_are_matched_by_req_id() {
+        let (port, _reqs) = spawn_ws_node_with_msgs(|id, _| {
+            vec![
+                Msg {
+                    id: id.wrapping_add(1),
+                    body: Body::Response(rpc::Response::GetScript(None)),
+                },
+                Msg {
+                    id: u32::MAX,
+                    body: Body::Response(rpc::Response::GetScript(None)),
+                },
+                Msg {
+                    id,
+                    body: Body::Response(rpc::Response::Broadcast),
+                },
+            ]
+        });
+        let mut wallet = TestWallet::with_url(&format!("ws://127.0.0.1:{}", port), false);
+
+        wallet.0.req_id = u32::MAX - 2;
+        for expected_id in &[u32::MAX - 2, u32::MAX - 1, 0, 1] {
+            let tx = create_tx_hex().0;
+            let res = send_rpc_req(&mut wallet.0, rpc::Request::Broadcast(tx)).unwrap();
+            assert_eq!(res.id, *expected_id);
+            assert_eq!(res.body, Body::Response(rpc::Response::Broadcast));
+        }
+    }
+
+    #[test]
+    fn broadcast_checks_fee() {
+        let (port, reqs) = spawn_ws_node(|req| match req {
+            rpc::Request::GetAccountInfo(id) => {
+                let account = Account::create_default(
+                    *id,
+                    Permissions {
+                        threshold: 1,
+                        keys: vec![KeyPair::gen().0],
+                    },
+                );
+                Body::Response(rpc::Response::GetAccountInfo(AccountInfo {
+                    account,
+                    net_fee: Asset::new(100_000),
+                    account_fee: Asset::new(50_000),
+                }))
+            }
+            rpc::Request::Broadcast(_) => Body::Error(net::ErrorKind::TxValidation(
+                blockchain::TxErr::InvalidFeeAmount,
+            )),
+            req => panic!("Unexpected request: {:?}", req),
+        });
+        let mut wallet = TestWallet::with_url(&format!("ws://127.0.0.1:{}", port), false);
+
+        // The tx fee of 1.00000 is below the required fee of 1.50000
+        let (_, hex) = create_tx_hex();
+        let matches = cli().get_matches_from_safe(["broadcast", &hex]).unwrap();
+        let args = matches.subcommand_matches("broadcast").unwrap();
+        let err = broadcast(&mut wallet.0, args).unwrap_err();
+        let req_fee = Asset::new(150_000).to_string();
+        assert!(
+            err.contains(&format!("below the required fee {}", req_fee)),
+            "{}",
+            err
+        );
+        assert_eq!(reqs.recv().unwrap(), rpc::Request::GetAccountInfo(1));
+        assert!(reqs.try_recv().is_err());
+
+        let matches = cli()
+            .get_matches_from_safe(["broadcast", &hex, "--force"])
+            .unwrap();
+        let args = matches.subcommand_matches("broadcast").unwrap();
+        assert_eq!(broadcast(&mut wallet.0, args), Ok(()));
+        match reqs.recv().unwrap() {
+            rpc::Request::Broadcast(_) => {}
+            req => panic!("Expected a broadcast request: {:?}", req),
+        }
+    }
+
+    #[test]
+    fn wss_handshake_insecure() {
+        let port = spawn_wss_server();
+        let mut wallet = TestWallet::with_url(&format!("wss://localhost:{}", port), true);
+        let res = send_rpc_req(&mut wallet.0, rpc::Request::GetProperties).unwrap();
+        assert_eq!(res.id, 0);
+        assert_eq!(res.body, Body::Error(net::ErrorKind::InvalidRequest));
+    }
+
+    #[test]
+    fn wss_rejects_untrusted_cert() {
+        let port = spawn_wss_server();
+        let mut wallet = TestWallet::with_url(&format!("wss://localhost:{}", port), false);
+        let res = send_rpc_req(&mut wallet.0, rpc::Request::GetProperties);
+        assert!(res
+            .unwrap_err()
+            .starts_with("Failed to establish TLS connection"));
+    }
+
+    #[test]
+    fn rpc_req_without_node_errors() {
+        let mut wallet = TestWallet::new();
+        let res = send_rpc_req(&mut wallet.0, rpc::Request::GetProperties);
+        assert!(res.unwrap_err().starts_with("Failed to connect to host"));
+    }
+
+    #[test]
+    fn list_balances_without_node() {
+        let mut wallet = TestWallet::new();
+        wallet.0.db.set_password(b"password");
+        assert!(wallet.0.db.unlock(b"password"));
+        let acc = WalletAccount {
+            id: 1,
+            keys: vec![KeyPair::gen()],
+        };
+        wallet.0.db.set_account("foo", acc).unwrap();
+
+        let matches = cli()
+            .get_matches_from_safe(["list_accounts", "--balances"])
+            .unwrap();
+        let args = matches.subcommand_matches("list_accounts").unwrap();
+        assert_eq!(account::list(&mut wallet.0, args), Ok(()));
+    }
+
+    #[test]
+    fn build_owner_tx_decodes() {
+        let fee = Asset::new(100_000).to_string();
+        let minter = KeyPair::gen();
+        let wallet_acc: AccountId = 1234;
+        let args = [
+            "build_owner_tx",
+            "--minter",
+            &minter.0.to_wif(),
+            "--wallet",
+            &wallet_acc.to_wif(),
+            "--fee",
+            &fee,
+        ];
+        let matches = cli().get_matches_from_safe(args).unwrap();
+        let tx = create_owner_tx(matches.subcommand_matches("build_owner_tx").unwrap()).unwrap();
+
+        let mut buf = vec![];
+        tx.serialize(&mut buf);
+        let hex = faster_hex::hex_string(&buf).unwrap();
+        let bytes = hex_to_bytes!(hex).unwrap();
+        let decoded = TxVariant::deserialize(&mut Cursor::<&[u8]>::new(&bytes)).unwrap();
+        assert_eq!(decoded, tx);
+        match decoded {
+            TxVariant::V0(TxVariantV0::OwnerTx(owner)) => {
+                assert_eq!(owner.minter, minter.0);
+                assert_eq!(owner.wallet, wallet_acc);
+                assert_eq!(owner.fee, Asset::new(100_000));
+                assert!(owner.signature_pairs.is_empty());
+            }
+            _ => panic!("Expected an owner tx: {:?}", decoded),
+        }
+    }
+
+    #[test]
+    fn build_owner_tx_rejects_invalid_keys() {
+        let fee = Asset::new(100_000).to_string();
+        let args = [
+            "build_owner_tx",
+            "--minter",
+            "GOD123",
+            "--wallet",
+            &1234.to_wif(),
+            "--fee",
+            &fee,
+        ];
+        let matches = cli().get_matches_from_safe(args).unwrap();
+        let err = create_owner_tx(matches.subcommand_matches("build_owner_tx").unwrap());
+        assert!(err
+            .unwrap_err()
+            .starts_with("Failed to parse minter public key"));
+
+        let args = [
+            "build_owner_tx",
+            "--minter",
+            &KeyPair::gen().0.to_wif(),
+            "--wallet",
+            "1234",
+            "--fee",
+            &fee,
+        ];
+        let matches = cli().get_matches_from_safe(args).unwrap();
+        let err = create_owner_tx(matches.subcommand_matches("build_owner_tx").unwrap());
+        assert!(err
+            .unwrap_err()
+            .starts_with("Failed to parse wallet account address"));
+    }
+
+    #[test]
+    fn resign_tx_replaces_sigs() {
+        let mut wallet = TestWallet::new();
+        let (mut tx, _) = create_tx_hex();
+        let old_key = KeyPair::gen();
+        tx.append_sign(&old_key);
+        let hex = {
+            let mut buf = vec![];
+            tx.serialize(&mut buf);
+            faster_hex::hex_string(&buf).unwrap()
+        };
+
+        let keys = [KeyPair::gen(), KeyPair::gen()];
+        wallet.0.db.set_password(b"password");
+        assert!(wallet.0.db.unlock(b"password"));
+        wallet
+            .0
+            .db
+            .set_account(
+                "acc",
+                WalletAccount {
+                    id: 1,
+                    keys: keys.to_vec(),
+                },
+            )
+            .unwrap();
+
+        let args = ["resign_tx", "--hex", &hex, "--account", "acc"];
+        let matches = cli().get_matches_from_safe(args).unwrap();
+        let resigned = resigned_tx(
+            &mut wallet.0,
+            matches.subcommand_matches("resign_tx").unwrap(),
+        );
+        let resigned = resigned.unwrap();
+
+        assert_eq!(
+            verify_sigs(&resigned),
+            vec![(keys[0].0.clone(), true), (keys[1].0.clone(), true)]
+        );
+        assert!(resigned.sigs().iter().all(|pair| pair.pub_key != old_key.0));
+    }
+
+    #[test]
+    fn verify_tx_sigs() {
+        let (mut tx, _) = create_tx_hex();
+        let good_key = KeyPair::gen();
+        let bad_key = KeyPair::gen();
+        tx.append_sign(&good_key);
+        // Sign data that isn't the transaction ID
+        tx.sigs_mut().push(bad_key.sign(b"not a txid"));
+
+        assert_eq!(
+            verify_sigs(&tx),
+            vec![(good_key.0.clone(), true), (bad_key.0.clone(), false)]
+        );
+    }
+
+    #[test]
+    fn attach_exported_sigs() {
+        let (tx, hex) = create_tx_hex();
+        let txid = tx.calc_txid();
+        let pairs = [
+            KeyPair::gen().sign(txid.as_ref()),
+            KeyPair::gen().sign(txid.as_ref()),
+        ];
+        let sigs = [pairs[0].to_string(), pairs[1].to_string()];
+
+        let attach = |args: &[&str]| {
+            let matches = cli().get_matches_from_safe(args).unwrap();
+            attached_tx(matches.subcommand_matches("attach_sig").unwrap())
+        };
+
+        let args = [
+            "attach_sig",
+            "--hex",
+            &hex,
+            "--sig",
+            &sigs[0],
+            "--sig",
+            &sigs[1],
+        ];
+        let attached = attach(&args).unwrap();
+        assert_eq!(attached.sigs(), &pairs);
+
+        // Signatures over other data are rejected
+        let other = KeyPair::gen().sign(b"not a txid").to_string();
+        assert!(attach(&["attach_sig", "--hex", &hex, "--sig", &other]).is_err());
+        assert!(attach(&["attach_sig", "--hex", &hex, "--sig", &sigs[0][1..]]).is_err());
+    }
+
+    #[test]
+    fn sign_tx_with_accounts_and_wifs_in_order() {
+        let mut wallet = TestWallet::new();
+        let (tx, hex) = create_tx_hex();
+        let keys = [KeyPair::gen(), KeyPair::gen(), KeyPair::gen()];
+        let wifs = [keys[0].1.to_wif(), keys[2].1.to_wif()];
+
+        wallet.0.db.set_password(b"password");
+        assert!(wallet.0.db.unlock(b"password"));
+        wallet
+            .0
+            .db
+            .set_account(
+                "acc",
+                WalletAccount {
+                    id: 1,
+                    keys: vec![keys[1].clone()],
+                },
+            )
+            .unwrap();
+
+        let signed = sign_with_args(
+            &mut wallet.0,
+            &[
+                "sign_tx",
+                "--hex",
+                &hex,
+                "--wif",
+                &wifs[0],
+                "--account",
+                "acc",
+                "--wif",
+                &wifs[1],
+            ],
+        )
+        .unwrap();
+        let txid = tx.calc_txid();
+        assert_eq!(signed.sigs().len(), 3);
+        for (pair, key) in signed.sigs().iter().zip(&keys) {
+            assert_eq!(pair.pub_key, key.0);
+            assert!(pair.verify(txid.as_ref()));
+        }
+    }
+
+    #[test]
+    fn decode_tx_as_json() {
+        let key = KeyPair::gen();
+        let mut tx = TxVariant::V0(TxVariantV0::TransferTx(TransferTx {
+            base: Tx {
+                nonce: 123,
+                expiry: 1234567890,
+                fee: Asset::new(100_000),
+                signature_pairs: vec![],
+            },
+            from: 1,
+            call_fn: 2,
+            args: vec![0xAB],
+            amount: Asset::new(1_000_000),
+            memo: vec![0x01, 0x02],
+        }));
+        tx.append_sign(&key);
+
+        let mut buf = vec![];
+        tx.serialize(&mut buf);
+        let hex = faster_hex::hex_string(&buf).unwrap();
+
+        let output = format_decoded_tx(&hex, "json").unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["type"], "transfer");
+        assert_eq!(value["nonce"], 123);
+        assert_eq!(value["expiry"], 1234567890);
+        assert_eq!(value["fee"], Asset::new(100_000).to_string());
+        assert_eq!(value["from"], &*1u64.to_wif());
+        assert_eq!(value["call_fn"], 2);
+        assert_eq!(value["args"], "ab");
+        assert_eq!(value["amount"], Asset::new(1_000_000).to_string());
+        assert_eq!(value["memo"], "0102");
+        assert_eq!(
+            value["txid"],
+            faster_hex::hex_string(tx.calc_txid().as_ref()).unwrap()
+        );
+        assert_eq!(value["signature_pairs"][0]["pub_key"], &*key.0.to_wif());
+
+        let output = format_decoded_tx(&hex, "debug").unwrap();
+        assert_eq!(output, format!("{:#?}", tx));
+    }
+
+    #[test]
+    fn decode_tx_from_file_and_stdin() {
+        let wallet = TestWallet::new();
+        let (_, hex) = create_tx_hex();
+        let expected = format_decoded_tx(&util::read_hex_arg(&hex).unwrap(), "debug").unwrap();
+
+        // Split the hex across lines with surrounding whitespace
+        let (first, second) = hex.split_at(hex.len() / 2);
+        let wrapped = format!("  {}\n\t{}\n", first, second);
+
+        let path = wallet.1.join("tx.hex");
+        fs::write(&path, &wrapped).unwrap();
+        let file_hex = util::read_hex_arg(&format!("@{}", path.display())).unwrap();
+        assert_eq!(file_hex, hex);
+        assert_eq!(format_decoded_tx(&file_hex, "debug").unwrap(), expected);
+
+        let stdin_hex = util::read_hex_arg_from("-", wrapped.as_bytes()).unwrap();
+        assert_eq!(stdin_hex, hex);
+        assert_eq!(format_decoded_tx(&stdin_hex, "debug").unwrap(), expected);
+
+        let missing = wallet.1.join("missing.hex");
+        let err = util::read_hex_arg(&format!("@{}", missing.display())).unwrap_err();
+        assert!(err.starts_with("Failed to read"), "{}", err);
+    }
+
+    #[test]
+    fn format_block_as_hex_and_json() {
+        let (tx, _) = create_tx_hex();
+        let receipts = vec![Receipt {
+            tx,
+            log: vec![LogEntry::Transfer(2, Asset::new(1000))],
+        }];
+        let mut block = Block::V0(BlockV0 {
+            header: BlockHeaderV0 {
+                previous_hash: godcoin::crypto::double_sha256(&[1, 2, 3]),
+                height: 1,
+                timestamp: 1234567890,
+                receipt_root: godcoin::blockchain::block::calc_receipt_root(&receipts),
+            },
+            signer: None,
+            rewards: Asset::new(0),
+            receipts,
+        });
+        block.sign(&KeyPair::gen());
+        let filtered = FilteredBlock::Block(Arc::new(block.clone()));
+
+        let hex = format_block(&filtered, "hex");
+        let bytes = hex_to_bytes!(hex).unwrap();
+        let cursor = &mut Cursor::<&[u8]>::new(&bytes);
+        assert_eq!(Block::deserialize(cursor), Some(block.clone()));
+
+        let output = format_block(&filtered, "json");
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["height"], 1);
+        assert_eq!(value["timestamp"], 1234567890);
+        assert_eq!(
+            value["hash"],
+            faster_hex::hex_string(block.calc_header_hash().as_ref()).unwrap()
+        );
+        assert_eq!(value["signers"].as_array().unwrap().len(), 1);
+        assert_eq!(value["receipts"][0]["tx"]["type"], "transfer");
+        assert_eq!(value["receipts"][0]["log"][0]["type"], "transfer");
+        assert_eq!(
+            value["receipts"][0]["log"][0]["amount"],
+            Asset::new(1000).to_string()
+        );
+    }
+}
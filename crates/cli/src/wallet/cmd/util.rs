@@ -2,7 +2,8 @@ use crate::Wallet;
 use godcoin::net::*;
 use native_tls::TlsConnector;
 use std::{
-    io::Cursor,
+    fs,
+    io::{self, Cursor, Read},
     net::{SocketAddr, TcpStream, ToSocketAddrs},
     time::Duration,
 };
@@ -28,6 +29,27 @@ macro_rules! hex_to_bytes {
     }};
 }
 
+/// Reads a hex argument which is either provided inline, read from a file when prefixed with `@`,
+/// or read from stdin when set to `-`. Any whitespace in the input is stripped.
+pub fn read_hex_arg(arg: &str) -> Result<String, String> {
+    read_hex_arg_from(arg, io::stdin())
+}
+
+pub(super) fn read_hex_arg_from<R: Read>(arg: &str, mut stdin: R) -> Result<String, String> {
+    let hex = if arg == "-" {
+        let mut hex = String::new();
+        stdin
+            .read_to_string(&mut hex)
+            .map_err(|e| format!("Failed to read from stdin: {}", e))?;
+        hex
+    } else if let Some(path) = arg.strip_prefix('@') {
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?
+    } else {
+        arg.to_string()
+    };
+    Ok(hex.split_whitespace().collect())
+}
+
 pub fn send_print_rpc_req(wallet: &mut Wallet, body: rpc::Request) {
     let res = send_rpc_req(wallet, body);
     match res {
@@ -37,16 +59,8 @@ pub fn send_print_rpc_req(wallet: &mut Wallet, body: rpc::Request) {
 }
 
 pub fn send_rpc_req(wallet: &mut Wallet, body: rpc::Request) -> Result<Msg, String> {
+    let req_id = next_req_id(wallet);
     let buf = {
-        let req_id = {
-            let id = wallet.req_id;
-            wallet.req_id += 1;
-            if wallet.req_id == u32::max_value() {
-                wallet.req_id = 0;
-            }
-            id
-        };
-
         let mut buf = Vec::with_capacity(8192);
         let req = Msg {
             id: req_id,
@@ -59,7 +73,7 @@ pub fn send_rpc_req(wallet: &mut Wallet, body: rpc::Request) -> Result<Msg, Stri
     let mut ws = {
         let mut addr = (wallet.url.host_str().unwrap(), wallet.url.port().unwrap())
             .to_socket_addrs()
-            .unwrap();
+            .map_err(|e| format!("Failed to resolve host: {:?}", e))?;
 
         let addr = loop {
             match addr.next() {
@@ -76,11 +90,14 @@ pub fn send_rpc_req(wallet: &mut Wallet, body: rpc::Request) -> Result<Msg, Stri
         let stream = match wallet.url.scheme() {
             "ws" => Stream::Plain(stream),
             "wss" => {
-                let connector = TlsConnector::new().unwrap();
+                let connector = TlsConnector::builder()
+                    .danger_accept_invalid_certs(wallet.insecure_tls)
+                    .build()
+                    .map_err(|e| format!("Failed to create TLS connector: {:?}", e))?;
                 Stream::Tls(
                     connector
                         .connect(wallet.url.host_str().unwrap(), stream)
-                        .unwrap(),
+                        .map_err(|e| format!("Failed to establish TLS connection: {:?}", e))?,
                 )
             }
             _ => panic!("Expected node url scheme to be ws or wss"),
@@ -90,18 +107,24 @@ pub fn send_rpc_req(wallet: &mut Wallet, body: rpc::Request) -> Result<Msg, Stri
             .map_err(|e| format!("Failed to init ws socket: {:?}", e))?;
         ws
     };
-    ws.write_message(Message::Binary(buf)).unwrap();
-    ws.write_pending().unwrap();
+    ws.write_message(Message::Binary(buf))
+        .map_err(|e| format!("Failed to send request: {:?}", e))?;
+    ws.write_pending()
+        .map_err(|e| format!("Failed to send request: {:?}", e))?;
 
     let msg = loop {
-        let msg = ws.read_message().unwrap();
+        let msg = ws
+            .read_message()
+            .map_err(|e| format!("Failed to read response: {:?}", e))?;
         match msg {
             Message::Binary(res) => {
                 let mut cursor = Cursor::<&[u8]>::new(&res);
                 let msg = Msg::deserialize(&mut cursor)
                     .map_err(|e| format!("Failed to deserialize response: {}", e))?;
                 match msg.body {
-                    Body::Error(_) | Body::Response(_) => break msg,
+                    // Errors the server could not associate with a request use the reserved id
+                    Body::Error(_) if msg.id == req_id || msg.id == u32::MAX => break msg,
+                    Body::Response(_) if msg.id == req_id => break msg,
                     Body::Ping(nonce) => {
                         let msg = Msg {
                             id: msg.id,
@@ -109,8 +132,10 @@ pub fn send_rpc_req(wallet: &mut Wallet, body: rpc::Request) -> Result<Msg, Stri
                         };
                         let mut buf = Vec::with_capacity(16);
                         msg.serialize(&mut buf);
-                        ws.write_message(Message::Binary(buf)).unwrap();
-                        ws.write_pending().unwrap();
+                        ws.write_message(Message::Binary(buf))
+                            .map_err(|e| format!("Failed to send pong: {:?}", e))?;
+                        ws.write_pending()
+                            .map_err(|e| format!("Failed to send pong: {:?}", e))?;
                     }
                     _ => continue,
                 }
@@ -122,3 +147,11 @@ pub fn send_rpc_req(wallet: &mut Wallet, body: rpc::Request) -> Result<Msg, Stri
 
     Ok(msg)
 }
+
+/// Returns the id for the next request. The id wraps around before reaching `u32::MAX`, which is
+/// reserved for messages the server sends without a request.
+fn next_req_id(wallet: &mut Wallet) -> u32 {
+    let id = wallet.req_id;
+    wallet.req_id = if id >= u32::MAX - 1 { 0 } else { id + 1 };
+    id
+}
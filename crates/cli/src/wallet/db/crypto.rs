@@ -11,6 +11,9 @@ pub fn encrypt_with_key(msg: &[u8], key: &secretbox::Key) -> Vec<u8> {
 }
 
 pub fn decrypt_with_key(msg: &[u8], key: &secretbox::Key) -> Option<Vec<u8>> {
+    if msg.len() < secretbox::NONCEBYTES {
+        return None;
+    }
     let nonce = &msg[0..secretbox::NONCEBYTES];
     let nonce = secretbox::Nonce::from_slice(nonce).unwrap();
     let msg = &msg[secretbox::NONCEBYTES..];
@@ -18,9 +18,12 @@ mod crypto;
 use self::crypto::*;
 
 pub const CF_ACCOUNTS: &str = "accounts";
+pub const CF_CONTACTS: &str = "contacts";
 
 pub const PROP_INIT: &[u8] = b"init";
 
+pub const MAX_ACCOUNT_NAME_LEN: usize = 64;
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum DbState {
     New,
@@ -39,7 +42,10 @@ impl Db {
         let mut db_opts = Options::default();
         db_opts.create_missing_column_families(true);
         db_opts.create_if_missing(true);
-        let col_families = vec![ColumnFamilyDescriptor::new(CF_ACCOUNTS, Options::default())];
+        let col_families = vec![
+            ColumnFamilyDescriptor::new(CF_ACCOUNTS, Options::default()),
+            ColumnFamilyDescriptor::new(CF_CONTACTS, Options::default()),
+        ];
         let db = DB::open_cf_descriptors(&db_opts, path, col_families).unwrap();
         let state = if db.get(PROP_INIT).unwrap().is_some() {
             DbState::Locked
@@ -72,18 +78,7 @@ impl Db {
         };
 
         let perm_key = {
-            let temp_key = {
-                let mut bytes = [0; secretbox::KEYBYTES];
-                argon2id13::derive_key(
-                    &mut bytes,
-                    &pass,
-                    &salt,
-                    argon2id13::OPSLIMIT_MODERATE,
-                    argon2id13::MEMLIMIT_MODERATE,
-                )
-                .unwrap();
-                Key(bytes)
-            };
+            let temp_key = derive_pass_key(pass, &salt);
 
             let perm_key_dec = match self.state {
                 DbState::New => Cow::Owned(gen_key()),
@@ -107,28 +102,10 @@ impl Db {
         assert_eq!(self.state, DbState::Locked);
 
         let init_bytes = self.db.get(PROP_INIT).unwrap().unwrap();
-        let salt = argon2id13::Salt::from_slice(&init_bytes[0..argon2id13::SALTBYTES]).unwrap();
-
-        let temp_key = {
-            let mut bytes = [0; secretbox::KEYBYTES];
-            argon2id13::derive_key(
-                &mut bytes,
-                &pass,
-                &salt,
-                argon2id13::OPSLIMIT_MODERATE,
-                argon2id13::MEMLIMIT_MODERATE,
-            )
-            .unwrap();
-            Key(bytes)
-        };
-
-        let key_enc = &init_bytes[argon2id13::SALTBYTES..];
-        match decrypt_with_key(&key_enc, &temp_key) {
-            Some(mut key) => {
-                self.key = Some(Key::from_slice(&key).unwrap());
+        match decrypt_init_key(&init_bytes, pass) {
+            Some(key) => {
+                self.key = Some(key);
                 self.state = DbState::Unlocked;
-                sodiumoxide::utils::memzero(&mut key);
-
                 true
             }
             None => false,
@@ -157,36 +134,250 @@ impl Db {
     }
 
     pub fn get_account(&self, account: &str) -> Option<WalletAccount> {
+        // Names are compared trimmed so accounts stored before normalization remain readable
+        let account = account.trim();
         for (acc, pair) in self.get_accounts() {
-            if acc == account {
+            if acc.trim() == account {
                 return Some(pair);
             }
         }
         None
     }
 
-    pub fn set_account(&self, name: &str, account: WalletAccount) {
+    /// Stores a new account under its normalized name. Fails if an account with the same
+    /// normalized name already exists.
+    pub fn create_account(&self, name: &str, account: WalletAccount) -> Result<(), String> {
+        let name = normalize_account_name(name)?;
+        if self.get_account(name).is_some() {
+            return Err(format!(
+                "An account with the name `{}` already exists",
+                name
+            ));
+        }
+        self.set_account(name, account)
+    }
+
+    pub fn set_account(&self, name: &str, account: WalletAccount) -> Result<(), String> {
+        let name = normalize_account_name(name)?;
         let secret = self.key.as_ref().expect("wallet not unlocked");
         let enc_key = encrypt_with_key(name.as_bytes(), secret);
         let enc_value = encrypt_with_key(&account.serialize(), secret);
         let cf = self.db.cf_handle(CF_ACCOUNTS).unwrap();
         self.db.put_cf(cf, &enc_key, &enc_value).unwrap();
+        Ok(())
+    }
+
+    /// Exports all accounts encrypted with the wallet key. The exported bytes embed the wallet
+    /// key encrypted with the wallet password so they can be imported into any wallet.
+    pub fn export_accounts(&self) -> Vec<u8> {
+        let secret = self.key.as_ref().expect("wallet not unlocked");
+        let init_bytes = self.db.get(PROP_INIT).unwrap().unwrap();
+
+        let accounts = self.get_accounts();
+        let mut plain = Vec::with_capacity(4096);
+        plain.push_u32(accounts.len() as u32);
+        for (name, acc) in accounts {
+            plain.push_bytes(name.as_bytes());
+            let mut acc_bytes = acc.serialize();
+            plain.push_bytes(&acc_bytes);
+            sodiumoxide::utils::memzero(&mut acc_bytes);
+        }
+        let enc = encrypt_with_key(&plain, secret);
+        sodiumoxide::utils::memzero(&mut plain);
+
+        let mut buf = Vec::with_capacity(init_bytes.len() + enc.len() + 8);
+        buf.push_bytes(&init_bytes);
+        buf.push_bytes(&enc);
+        buf
+    }
+
+    /// Imports accounts previously exported with `export_accounts` using the password of the
+    /// exporting wallet. Name collisions are rejected unless `overwrite` is set, in which case
+    /// the existing accounts are replaced. Returns the number of imported accounts.
+    pub fn import_accounts(
+        &self,
+        bytes: &[u8],
+        pass: &[u8],
+        overwrite: bool,
+    ) -> Result<usize, String> {
+        let accounts = {
+            let cur = &mut Cursor::<&[u8]>::new(bytes);
+            let init_bytes = cur.take_bytes().map_err(|_| "Failed to read export")?;
+            let enc = cur.take_bytes().map_err(|_| "Failed to read export")?;
+
+            let key = decrypt_init_key(&init_bytes, pass).ok_or("Invalid export password")?;
+            let mut plain = decrypt_with_key(&enc, &key).ok_or("Failed to decrypt export")?;
+            let accounts = Self::read_exported_accounts(&plain);
+            sodiumoxide::utils::memzero(&mut plain);
+            accounts.ok_or("Failed to read exported accounts")?
+        };
+
+        let accounts = accounts
+            .into_iter()
+            .map(|(name, acc)| Ok((normalize_account_name(&name)?.to_owned(), acc)))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let existing = self.get_accounts();
+        for (i, (name, _)) in accounts.iter().enumerate() {
+            let exists = !overwrite && existing.iter().any(|(acc, _)| acc.trim() == name);
+            if exists || accounts[..i].iter().any(|(acc, _)| acc == name) {
+                return Err(format!(
+                    "An account with the name `{}` already exists",
+                    name
+                ));
+            }
+        }
+
+        let len = accounts.len();
+        for (name, acc) in accounts {
+            self.del_account(&name);
+            self.set_account(&name, acc)?;
+        }
+        Ok(len)
+    }
+
+    fn read_exported_accounts(plain: &[u8]) -> Option<Vec<(String, WalletAccount)>> {
+        let cur = &mut Cursor::<&[u8]>::new(plain);
+        let len = cur.take_u32().ok()?;
+        let mut accounts = Vec::with_capacity(len as usize);
+        for _ in 0..len {
+            let name = String::from_utf8(cur.take_bytes().ok()?).ok()?;
+            let mut acc_bytes = cur.take_bytes().ok()?;
+            let acc = WalletAccount::deserialize(&mut Cursor::new(&acc_bytes));
+            sodiumoxide::utils::memzero(&mut acc_bytes);
+            accounts.push((name, acc));
+        }
+        Some(accounts)
     }
 
     pub fn del_account(&self, name: &str) -> bool {
+        let name = name.trim();
         let secret = self.key.as_ref().expect("wallet not unlocked");
         let cf = self.db.cf_handle(CF_ACCOUNTS).unwrap();
         let iter = self.db.iterator_cf(cf, IteratorMode::Start);
         for (key, _) in iter {
             let dec_key = decrypt_with_key(&key, secret).unwrap();
             let dec_key = String::from_utf8(dec_key).unwrap();
-            if dec_key == name {
+            if dec_key.trim() == name {
+                self.db.delete_cf(cf, &key).unwrap();
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns the labeled external addresses stored in the address book.
+    pub fn get_contacts(&self) -> Vec<(String, AccountId)> {
+        let secret = self.key.as_ref().expect("wallet not unlocked");
+        let mut contacts = Vec::new();
+
+        let cf = self.db.cf_handle(CF_CONTACTS).unwrap();
+        let iter = self.db.iterator_cf(cf, IteratorMode::Start);
+        for (key, value) in iter {
+            let label = decrypt_with_key(&key, secret).unwrap();
+            let label = String::from_utf8(label).unwrap();
+            let id = decrypt_with_key(&value, secret).unwrap();
+            let id = Cursor::<&[u8]>::new(&id).take_u64().unwrap();
+            contacts.push((label, id));
+        }
+        contacts
+    }
+
+    pub fn get_contact(&self, label: &str) -> Option<AccountId> {
+        let label = label.trim();
+        self.get_contacts()
+            .into_iter()
+            .find(|(l, _)| l == label)
+            .map(|(_, id)| id)
+    }
+
+    /// Stores a labeled external address. Labels follow the account name rules and must be
+    /// unique within the address book.
+    pub fn add_contact(&self, label: &str, address: AccountId) -> Result<(), String> {
+        let label = normalize_account_name(label)?;
+        if self.get_contact(label).is_some() {
+            return Err(format!(
+                "A contact with the label `{}` already exists",
+                label
+            ));
+        }
+
+        let secret = self.key.as_ref().expect("wallet not unlocked");
+        let enc_key = encrypt_with_key(label.as_bytes(), secret);
+        let mut value = Vec::with_capacity(8);
+        value.push_u64(address);
+        let enc_value = encrypt_with_key(&value, secret);
+        let cf = self.db.cf_handle(CF_CONTACTS).unwrap();
+        self.db.put_cf(cf, &enc_key, &enc_value).unwrap();
+        Ok(())
+    }
+
+    pub fn del_contact(&self, label: &str) -> bool {
+        let label = label.trim();
+        let secret = self.key.as_ref().expect("wallet not unlocked");
+        let cf = self.db.cf_handle(CF_CONTACTS).unwrap();
+        let iter = self.db.iterator_cf(cf, IteratorMode::Start);
+        for (key, _) in iter {
+            let dec_key = decrypt_with_key(&key, secret).unwrap();
+            if dec_key == label.as_bytes() {
                 self.db.delete_cf(cf, &key).unwrap();
                 return true;
             }
         }
         false
     }
+
+    /// Resolves a contact label or account address to an account ID. Labels take precedence over
+    /// addresses.
+    pub fn resolve_address(&self, label_or_address: &str) -> Option<AccountId> {
+        self.get_contact(label_or_address)
+            .or_else(|| AccountId::from_wif(label_or_address.trim()).ok())
+    }
+}
+
+fn derive_pass_key(pass: &[u8], salt: &argon2id13::Salt) -> Key {
+    let mut bytes = [0; secretbox::KEYBYTES];
+    argon2id13::derive_key(
+        &mut bytes,
+        pass,
+        salt,
+        argon2id13::OPSLIMIT_MODERATE,
+        argon2id13::MEMLIMIT_MODERATE,
+    )
+    .unwrap();
+    Key(bytes)
+}
+
+/// Decrypts the wallet key stored in the init property with the wallet password.
+fn decrypt_init_key(init_bytes: &[u8], pass: &[u8]) -> Option<Key> {
+    if init_bytes.len() < argon2id13::SALTBYTES {
+        return None;
+    }
+    let salt = argon2id13::Salt::from_slice(&init_bytes[0..argon2id13::SALTBYTES]).unwrap();
+    let temp_key = derive_pass_key(pass, &salt);
+
+    let key_enc = &init_bytes[argon2id13::SALTBYTES..];
+    let mut key = decrypt_with_key(key_enc, &temp_key)?;
+    let perm_key = Key::from_slice(&key);
+    sodiumoxide::utils::memzero(&mut key);
+    perm_key
+}
+
+/// Trims the surrounding whitespace of an account name and validates the result.
+pub fn normalize_account_name(name: &str) -> Result<&str, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        Err("Account name must not be empty".to_string())
+    } else if name.chars().count() > MAX_ACCOUNT_NAME_LEN {
+        Err(format!(
+            "Account name must not exceed {} characters",
+            MAX_ACCOUNT_NAME_LEN
+        ))
+    } else if name.chars().any(char::is_control) {
+        Err("Account name must not contain control characters".to_string())
+    } else {
+        Ok(name)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -221,3 +412,237 @@ impl WalletAccount {
         WalletAccount { id, keys }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs};
+
+    struct TestDb(Db, PathBuf);
+
+    impl TestDb {
+        fn new() -> Self {
+            let mut path = env::temp_dir();
+            let mut num = [0; 8];
+            randombytes_into(&mut num);
+            path.push(format!(
+                "godcoin_wallet_db_test_{}",
+                u64::from_be_bytes(num)
+            ));
+            fs::create_dir(&path).unwrap();
+
+            let mut db = Db::new(path.join("wallet_db"));
+            db.set_password(b"password");
+            assert!(db.unlock(b"password"));
+            Self(db, path)
+        }
+    }
+
+    impl Drop for TestDb {
+        fn drop(&mut self) {
+            fs::remove_dir_all(&self.1).expect("Failed to rm dir");
+        }
+    }
+
+    fn test_account(id: AccountId) -> WalletAccount {
+        WalletAccount {
+            id,
+            keys: vec![KeyPair::gen()],
+        }
+    }
+
+    #[test]
+    fn contacts() {
+        let db = TestDb::new();
+        assert!(db.0.get_contacts().is_empty());
+
+        db.0.add_contact("alice ", 10).unwrap();
+        db.0.add_contact("bob", 11).unwrap();
+        let mut contacts = db.0.get_contacts();
+        contacts.sort();
+        assert_eq!(
+            contacts,
+            vec![("alice".to_string(), 10), ("bob".to_string(), 11)]
+        );
+        assert_eq!(db.0.get_contact(" alice"), Some(10));
+        assert_eq!(db.0.get_contact("carol"), None);
+
+        assert_eq!(
+            db.0.add_contact("alice", 12),
+            Err("A contact with the label `alice` already exists".to_string())
+        );
+        assert_eq!(db.0.get_contact("alice"), Some(10));
+        assert!(db.0.add_contact(" ", 12).is_err());
+
+        assert!(db.0.del_contact("alice"));
+        assert!(!db.0.del_contact("alice"));
+        assert_eq!(db.0.get_contacts(), vec![("bob".to_string(), 11)]);
+
+        // Contacts are stored separately from accounts
+        assert!(db.0.get_accounts().is_empty());
+    }
+
+    #[test]
+    fn resolve_contact_address() {
+        let db = TestDb::new();
+        db.0.add_contact("alice", 10).unwrap();
+
+        assert_eq!(db.0.resolve_address("alice"), Some(10));
+        assert_eq!(db.0.resolve_address(&AccountId::to_wif(&11)), Some(11));
+        assert_eq!(db.0.resolve_address("carol"), None);
+    }
+
+    #[test]
+    fn normalize_names() {
+        assert_eq!(normalize_account_name("foo"), Ok("foo"));
+        assert_eq!(normalize_account_name("  foo bar \t"), Ok("foo bar"));
+        assert!(normalize_account_name("").is_err());
+        assert!(normalize_account_name("   ").is_err());
+        assert!(normalize_account_name("foo\u{7}bar").is_err());
+
+        let name = "a".repeat(MAX_ACCOUNT_NAME_LEN);
+        assert_eq!(normalize_account_name(&name), Ok(name.as_str()));
+        let name = "a".repeat(MAX_ACCOUNT_NAME_LEN + 1);
+        assert!(normalize_account_name(&name).is_err());
+    }
+
+    #[test]
+    fn set_account_normalizes_name() {
+        let db = TestDb::new();
+        db.0.set_account("foo  ", test_account(1)).unwrap();
+
+        let accounts = db.0.get_accounts();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].0, "foo");
+
+        assert_eq!(db.0.get_account("foo").unwrap().id, 1);
+        assert_eq!(db.0.get_account(" foo ").unwrap().id, 1);
+        assert!(db.0.get_account("bar").is_none());
+
+        assert!(db.0.set_account(" ", test_account(2)).is_err());
+        assert!(db.0.set_account("foo\nbar", test_account(2)).is_err());
+        assert_eq!(db.0.get_accounts().len(), 1);
+
+        assert!(db.0.del_account("foo "));
+        assert!(db.0.get_accounts().is_empty());
+    }
+
+    /// Stores an account without normalizing its name, as wallets did before names were
+    /// normalized.
+    fn set_raw_account(db: &Db, name: &str, account: &WalletAccount) {
+        let secret = db.key.as_ref().unwrap();
+        let enc_key = encrypt_with_key(name.as_bytes(), secret);
+        let enc_value = encrypt_with_key(&account.serialize(), secret);
+        let cf = db.db.cf_handle(CF_ACCOUNTS).unwrap();
+        db.db.put_cf(cf, &enc_key, &enc_value).unwrap();
+    }
+
+    #[test]
+    fn create_account_rejects_normalized_duplicates() {
+        let db = TestDb::new();
+        db.0.create_account("foo", test_account(1)).unwrap();
+        assert_eq!(
+            db.0.create_account("foo ", test_account(2)),
+            Err("An account with the name `foo` already exists".to_string())
+        );
+        assert_eq!(db.0.get_accounts().len(), 1);
+        assert_eq!(db.0.get_account("foo").unwrap().id, 1);
+
+        db.0.create_account(" bar", test_account(2)).unwrap();
+        assert_eq!(db.0.get_account("bar").unwrap().id, 2);
+    }
+
+    #[test]
+    fn import_rejects_normalized_duplicates() {
+        let src = TestDb::new();
+        set_raw_account(&src.0, "foo ", &test_account(1));
+        let export = src.0.export_accounts();
+
+        let dst = TestDb::new();
+        let foo = test_account(2);
+        dst.0.set_account("foo", foo.clone()).unwrap();
+        assert_eq!(
+            dst.0.import_accounts(&export, b"password", false),
+            Err("An account with the name `foo` already exists".to_string())
+        );
+        assert_eq!(dst.0.get_accounts().len(), 1);
+        assert_keys_eq(&dst.0.get_account("foo").unwrap(), &foo);
+
+        // Overwriting replaces the account instead of adding a second `foo`
+        assert_eq!(dst.0.import_accounts(&export, b"password", true), Ok(1));
+        let accounts = dst.0.get_accounts();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].0, "foo");
+        assert_eq!(accounts[0].1.id, 1);
+
+        // Names that only differ in surrounding whitespace collide within an export
+        set_raw_account(&src.0, "foo", &test_account(3));
+        let export = src.0.export_accounts();
+        let dst = TestDb::new();
+        assert_eq!(
+            dst.0.import_accounts(&export, b"password", false),
+            Err("An account with the name `foo` already exists".to_string())
+        );
+        assert!(dst.0.get_accounts().is_empty());
+    }
+
+    fn assert_keys_eq(a: &WalletAccount, b: &WalletAccount) {
+        assert_eq!(a.id, b.id);
+        let a_keys = a.keys.iter().map(|k| &k.1).collect::<Vec<_>>();
+        let b_keys = b.keys.iter().map(|k| &k.1).collect::<Vec<_>>();
+        assert_eq!(a_keys, b_keys);
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let db = TestDb::new();
+        let foo = test_account(1);
+        let bar = WalletAccount {
+            id: 2,
+            keys: vec![KeyPair::gen(), KeyPair::gen()],
+        };
+        db.0.set_account("foo", foo.clone()).unwrap();
+        db.0.set_account("bar", bar.clone()).unwrap();
+
+        let export = db.0.export_accounts();
+        assert!(db.0.del_account("foo"));
+        assert!(db.0.del_account("bar"));
+        assert!(db.0.get_accounts().is_empty());
+
+        assert!(db.0.import_accounts(&export, b"wrong", false).is_err());
+        assert_eq!(db.0.import_accounts(&export, b"password", false), Ok(2));
+        assert_keys_eq(&db.0.get_account("foo").unwrap(), &foo);
+        assert_keys_eq(&db.0.get_account("bar").unwrap(), &bar);
+    }
+
+    #[test]
+    fn import_merges_accounts() {
+        let src = TestDb::new();
+        let foo = test_account(1);
+        src.0.set_account("foo", foo.clone()).unwrap();
+        let export = src.0.export_accounts();
+
+        let dst = TestDb::new();
+        let baz = test_account(3);
+        dst.0.set_account("baz", baz.clone()).unwrap();
+        assert_eq!(dst.0.import_accounts(&export, b"password", false), Ok(1));
+        assert_eq!(dst.0.get_accounts().len(), 2);
+        assert_keys_eq(&dst.0.get_account("foo").unwrap(), &foo);
+        assert_keys_eq(&dst.0.get_account("baz").unwrap(), &baz);
+
+        // Name collisions are rejected unless overwriting
+        let other = test_account(4);
+        dst.0.del_account("foo");
+        dst.0.set_account("foo", other.clone()).unwrap();
+        let res = dst.0.import_accounts(&export, b"password", false);
+        assert_eq!(
+            res,
+            Err("An account with the name `foo` already exists".to_string())
+        );
+        assert_keys_eq(&dst.0.get_account("foo").unwrap(), &other);
+
+        assert_eq!(dst.0.import_accounts(&export, b"password", true), Ok(1));
+        assert_eq!(dst.0.get_accounts().len(), 2);
+        assert_keys_eq(&dst.0.get_account("foo").unwrap(), &foo);
+    }
+}
@@ -13,13 +13,16 @@ use self::db::{Db, DbState};
 pub struct Wallet {
     prompt: String,
     url: Url,
+    // Whether to accept invalid TLS certificates
+    insecure_tls: bool,
     db: Db,
     // Current ID to be sent when making requests
     req_id: u32,
 }
 
 impl Wallet {
-    pub fn new(home: PathBuf, url: &str) -> Wallet {
+    pub fn new(home: PathBuf, url: &str, insecure_tls: bool) -> Result<Wallet, String> {
+        let url = parse_node_url(url)?;
         let db = Db::new(home.join("wallet_db"));
         let prompt = (if db.state() == DbState::Locked {
             "locked>> "
@@ -28,24 +31,13 @@ impl Wallet {
         })
         .to_string();
 
-        let mut url: Url = url.parse().unwrap();
-        if url.host_str().is_none() {
-            panic!("Expected url to have host");
-        }
-        if url.port().is_none() {
-            url.set_port(Some(7777)).unwrap();
-        }
-        match url.scheme() {
-            "ws" | "wss" => {}
-            _ => panic!("Expected node URL scheme to be ws or wss"),
-        }
-
-        Wallet {
+        Ok(Wallet {
             db,
             prompt,
             url,
+            insecure_tls,
             req_id: 0,
-        }
+        })
     }
 
     pub fn start(mut self) {
@@ -91,413 +83,14 @@ impl Wallet {
         }
         println!();
 
-        let cli = App::new("")
-            .setting(AppSettings::NoBinaryName)
-            .setting(AppSettings::DisableVersion)
-            .setting(AppSettings::VersionlessSubcommands)
-            .subcommand(
-                SubCommand::with_name("new")
-                    .about("Create a new wallet")
-                    .arg(
-                        Arg::with_name("password")
-                            .required(true)
-                            .help("Password used to encrypt the wallet"),
-                    ),
-            )
-            .subcommand(
-                SubCommand::with_name("unlock")
-                    .about("Unlocks an existing wallet")
-                    .arg(
-                        Arg::with_name("password")
-                            .required(true)
-                            .help("Password used to unlock the wallet"),
-                    ),
-            )
-            .subcommand(
-                SubCommand::with_name("gen_keypair")
-                    .about("Generates a keypair for use with accounts")
-            )
-            .subcommand(
-                SubCommand::with_name("account_id_to_address")
-                    .about("Converts an ID to a pay-to-address WIF")
-                    .arg(
-                        Arg::with_name("id")
-                            .required(true)
-                            .takes_value(true)
-                            .help("Decimal ID to convert")
-                    )
-            )
-            .subcommand(
-                SubCommand::with_name("import_account")
-                    .about("Import an account")
-                    .arg(
-                        Arg::with_name("name")
-                            .long("name")
-                            .required(true)
-                            .takes_value(true)
-                            .help("The name of the account (stored in the wallet only)"),
-                    )
-                    .arg(
-                        Arg::with_name("account")
-                            .long("account")
-                            .required(true)
-                            .takes_value(true)
-                            .help("The account address"),
-                    )
-                    .arg(
-                        Arg::with_name("wif")
-                            .long("wif")
-                            .required(true)
-                            .takes_value(true)
-                            .multiple(true)
-                            .help(
-                                "Private WIF key for the account [accepts multiple for multisig]",
-                            ),
-                    ),
-            )
-            .subcommand(
-                SubCommand::with_name("delete_account")
-                    .about("Delete an account")
-                    .arg(
-                        Arg::with_name("name")
-                            .long("name")
-                            .required(true)
-                            .takes_value(true)
-                            .help("Name of the account to delete"),
-                    ),
-            )
-            .subcommand(
-                SubCommand::with_name("list_accounts")
-                    .about("List available accounts in the wallet"),
-            )
-            .subcommand(
-                SubCommand::with_name("get_account")
-                    .about("Retrieve account keys and addresses")
-                    .arg(
-                        Arg::with_name("name")
-                            .long("name")
-                            .required(true)
-                            .takes_value(true)
-                            .help("Name of the account"),
-                    ),
-            )
-            .subcommand(
-                SubCommand::with_name("get_account_info")
-                    .about("Retrieve address information from the blockchain")
-                    .arg(
-                        Arg::with_name("account")
-                            .long("account")
-                            .required(true)
-                            .takes_value(true)
-                            .help("Wallet account name or ID"),
-                    ),
-            )
-            .subcommand(
-                SubCommand::with_name("build_script")
-                    .about("Builds a script with the provided ops")
-                    .arg(
-                        Arg::with_name("ops")
-                            .required(true)
-                            .takes_value(true)
-                            .multiple(true)
-                            .help("Script operations"),
-                    ),
-            )
-            .subcommand(
-                SubCommand::with_name("args_to_bin")
-                    .about("Convert an array of arguments to hex to pass as script args")
-                    .arg(
-                        Arg::with_name("arg")
-                            .required(true)
-                            .takes_value(true)
-                            .multiple(true)
-                            .help("An argument that starts address={} or asset={}")
-                    )
-            )
-            .subcommand(
-                SubCommand::with_name("check_script_size")
-                    .about("Checks if the script is too large and prints the size in bytes")
-                    .arg(
-                        Arg::with_name("hex")
-                            .required(true)
-                            .takes_value(true)
-                            .help("Binary script in hex format"),
-                    ),
-            )
-            .subcommand(
-                SubCommand::with_name("decode_tx")
-                    .about("Decodes a transaction and prints it to console")
-                    .arg(
-                        Arg::with_name("hex")
-                            .required(true)
-                            .takes_value(true)
-                            .help("Binary transaction in hex format"),
-                    ),
-            )
-            .subcommand(
-                SubCommand::with_name("sign_tx")
-                    .about("Signs a raw transaction")
-                    .arg(
-                        Arg::with_name("hex")
-                            .long("hex")
-                            .required(true)
-                            .takes_value(true)
-                            .help("Binary transaction in hex format"),
-                    )
-                    .arg(
-                        Arg::with_name("account")
-                            .long("account")
-                            .required(true)
-                            .takes_value(true)
-                            .multiple(true)
-                            .help("Account to sign the transaction, accepts multiple"),
-                    ),
-            )
-            .subcommand(
-                SubCommand::with_name("unsign_tx")
-                    .about("Removes a signature from a raw transaction")
-                    .arg(
-                        Arg::with_name("hex")
-                            .long("hex")
-                            .required(true)
-                            .takes_value(true)
-                            .help("Binary transaction in hex format"),
-                    )
-                    .arg(
-                        Arg::with_name("index")
-                            .long("index")
-                            .required(true)
-                            .takes_value(true)
-                            .help("Index position of the signature to remove"),
-                    ),
-            )
-            .subcommand(
-                SubCommand::with_name("broadcast")
-                    .about("Broadcast a transaction to the network")
-                    .arg(
-                        Arg::with_name("hex")
-                            .required(true)
-                            .takes_value(true)
-                            .help("Binary transaction in hex format"),
-                    ),
-            )
-            .subcommand(
-                SubCommand::with_name("build_create_account_tx")
-                    .about("Builds a create account transaction")
-                    .arg(
-                        Arg::with_name("expiry")
-                            .long("expiry")
-                            .takes_value(true)
-                            .required(true)
-                            .default_value("60000")
-                            .help("The time in milliseconds when a transaction expires from now"),
-                    )
-                    .arg(
-                        Arg::with_name("fee")
-                            .long("fee")
-                            .takes_value(true)
-                            .required(true)
-                            .help("The fee to pay for the transaction"),
-                    )
-                    .arg(
-                        Arg::with_name("creator")
-                            .long("creator")
-                            .takes_value(true)
-                            .required(true)
-                            .help("The creator account or account address"),
-                    )
-                    .arg(
-                        Arg::with_name("balance")
-                            .long("balance")
-                            .takes_value(true)
-                            .required(true)
-                            .help("The balance to be deposited in the new account"),
-                    )
-                    .arg(
-                        Arg::with_name("script")
-                            .long("script")
-                            .takes_value(true)
-                            .required(false)
-                            .help(
-                                "The script to apply to the account, otherwise use a default script"
-                            ),
-                    )
-                    .arg(
-                        Arg::with_name("threshold")
-                            .long("threshold")
-                            .takes_value(true)
-                            .required(true)
-                            .help("Account permissions signatory threshold for verification"),
-                    )
-                    .arg(
-                        Arg::with_name("public_wif")
-                            .long("public-wif")
-                            .takes_value(true)
-                            .required(true)
-                            .multiple(true)
-                            .help("Public key allowed for signing to meet the signatory threshold"),
-                    )
-            )
-            .subcommand(
-                SubCommand::with_name("build_update_account_tx")
-                    .about("Builds an update account transaction")
-                    .arg(
-                        Arg::with_name("expiry")
-                            .long("expiry")
-                            .takes_value(true)
-                            .required(true)
-                            .default_value("60000")
-                            .help("The time in milliseconds when a transaction expires from now"),
-                    )
-                    .arg(
-                        Arg::with_name("fee")
-                            .long("fee")
-                            .takes_value(true)
-                            .required(true)
-                            .help("The fee to pay for the transaction"),
-                    )
-                    .arg(
-                        Arg::with_name("account")
-                            .long("account")
-                            .takes_value(true)
-                            .required(true)
-                            .help("The account to update"),
-                    )
-                    .arg(
-                        Arg::with_name("script")
-                            .long("script")
-                            .takes_value(true)
-                            .required(false)
-                            .help("New account script in hex format")
-                    )
-                    .arg(
-                        Arg::with_name("threshold")
-                            .long("threshold")
-                            .takes_value(true)
-                            .required(false)
-                            .requires("public_wif")
-                            .help(
-                                "New permissions threshold. This requires providing new \
-                                signing keys."
-                            )
-                    )
-                    .arg(
-                        Arg::with_name("public_wif")
-                            .long("public-wif")
-                            .takes_value(true)
-                            .required(false)
-                            .multiple(true)
-                            .requires("threshold")
-                            .help(
-                                "New account signing keys, will replace all current keys \
-                                associated with the account. This requires setting a new \
-                                threshold."
-                            )
-                    )
-            )
-            .subcommand(
-                SubCommand::with_name("build_mint_tx")
-                    .about("Builds a mint transaction")
-                    .arg(
-                        Arg::with_name("expiry")
-                            .long("expiry")
-                            .takes_value(true)
-                            .required(true)
-                            .default_value("60000")
-                            .help("The time in milliseconds when a transaction expires from now"),
-                    )
-                    .arg(
-                        Arg::with_name("amount")
-                            .long("amount")
-                            .takes_value(true)
-                            .required(true)
-                            .help("The amount of tokens to be minted"),
-                    )
-                    .arg(
-                        Arg::with_name("attachment_path")
-                            .long("attachment-path")
-                            .takes_value(true)
-                            .requires("attachment_name")
-                            .help("The path to the attachment for the transaction"),
-                    )
-                    .arg(
-                        Arg::with_name("attachment_name")
-                            .long("attachment-name")
-                            .takes_value(true)
-                            .help("The name of the attachment"),
-                    ),
-            )
-            .subcommand(
-                SubCommand::with_name("build_transfer_tx")
-                    .about("Builds a transfer transaction")
-                    .arg(
-                        Arg::with_name("expiry")
-                            .long("expiry")
-                            .takes_value(true)
-                            .required(true)
-                            .default_value("60000")
-                            .help("The time in milliseconds when a transaction expires from now"),
-                    )
-                    .arg(
-                        Arg::with_name("from_account")
-                            .long("from-account")
-                            .takes_value(true)
-                            .required(true)
-                            .help("The account to transfer from"),
-                    )
-                    .arg(
-                        Arg::with_name("call_fn")
-                            .long("call-fn")
-                            .takes_value(true)
-                            .required(true)
-                            .help("The function to call in the script"),
-                    )
-                    .arg(
-                        Arg::with_name("args")
-                            .long("args")
-                            .takes_value(true)
-                            .help("The hex value of the arguments that the script requires"),
-                    )
-                    .arg(
-                        Arg::with_name("amount")
-                            .long("amount")
-                            .takes_value(true)
-                            .required(true)
-                            .help("The amount of tokens allowed to be transferred"),
-                    )
-                    .arg(
-                        Arg::with_name("fee")
-                            .long("fee")
-                            .takes_value(true)
-                            .required(true)
-                            .help("The fee to pay for the transaction"),
-                    )
-                    .arg(
-                        Arg::with_name("memo")
-                            .long("memo")
-                            .takes_value(true)
-                            .help("The memo to send with the transaction"),
-                    ),
-            )
-            .subcommand(
-                SubCommand::with_name("get_properties").about("Retrieve network properties"),
-            )
-            .subcommand(
-                SubCommand::with_name("get_block")
-                    .about("Retrieve a block from the network")
-                    .arg(
-                        Arg::with_name("height")
-                            .required(true)
-                            .takes_value(true)
-                            .help("The height of the block to retrieve"),
-                    ),
-            )
-            .get_matches_from_safe(args);
+        let cli = cli().get_matches_from_safe(args);
 
         match cli {
             Ok(args) => match args.subcommand() {
                 ("new", Some(args)) => (false, cmd::create_wallet(self, args)),
                 ("unlock", Some(args)) => (false, cmd::unlock(self, args)),
+                ("export_wallet", Some(args)) => (true, cmd::export_wallet(self, args)),
+                ("import_wallet", Some(args)) => (false, cmd::import_wallet(self, args)),
                 ("gen_keypair", Some(_)) => (true, Ok(crate::generate_keypair())),
                 ("account_id_to_address", Some(args)) => {
                     (true, cmd::account::account_id_to_address(self, args))
@@ -506,14 +99,24 @@ impl Wallet {
                 ("delete_account", Some(args)) => (true, cmd::account::delete(self, args)),
                 ("list_accounts", Some(args)) => (true, cmd::account::list(self, args)),
                 ("get_account", Some(args)) => (true, cmd::account::get(self, args)),
+                ("add_contact", Some(args)) => (true, cmd::contact::add(self, args)),
+                ("remove_contact", Some(args)) => (true, cmd::contact::remove(self, args)),
+                ("list_contacts", Some(args)) => (true, cmd::contact::list(self, args)),
                 ("get_account_info", Some(args)) => (true, cmd::account::get_acc_info(self, args)),
+                ("balance", Some(args)) => (true, cmd::account::balance(self, args)),
                 ("build_script", Some(args)) => (true, cmd::build_script(self, args)),
                 ("args_to_bin", Some(args)) => (true, cmd::args_to_bin(self, args)),
                 ("check_script_size", Some(args)) => (true, cmd::check_script_size(self, args)),
                 ("decode_tx", Some(args)) => (true, cmd::decode_tx(self, args)),
-                ("sign_tx", Some(args)) => (true, cmd::sign_tx(self, args)),
+                // Avoid storing private keys in the history
+                ("sign_tx", Some(args)) => (!args.is_present("wif"), cmd::sign_tx(self, args)),
+                ("resign_tx", Some(args)) => (true, cmd::resign_tx(self, args)),
+                ("verify_tx", Some(args)) => (true, cmd::verify_tx(self, args)),
                 ("unsign_tx", Some(args)) => (true, cmd::unsign_tx(self, args)),
+                ("export_sigs", Some(args)) => (true, cmd::export_sigs(self, args)),
+                ("attach_sig", Some(args)) => (true, cmd::attach_sig(self, args)),
                 ("broadcast", Some(args)) => (true, cmd::broadcast(self, args)),
+                ("check_tx", Some(args)) => (true, cmd::check_tx(self, args)),
                 ("build_create_account_tx", Some(args)) => {
                     (true, cmd::account::build_create_tx(self, args))
                 }
@@ -521,12 +124,718 @@ impl Wallet {
                     (true, cmd::account::build_update_tx(self, args))
                 }
                 ("build_mint_tx", Some(args)) => (true, cmd::build_mint_tx(self, args)),
+                ("build_owner_tx", Some(args)) => (true, cmd::build_owner_tx(self, args)),
                 ("build_transfer_tx", Some(args)) => (true, cmd::build_transfer_tx(self, args)),
                 ("get_properties", Some(args)) => (true, cmd::get_properties(self, args)),
                 ("get_block", Some(args)) => (true, cmd::get_block(self, args)),
+                ("get_tx", Some(args)) => (true, cmd::get_tx(self, args)),
+                ("get_script", Some(args)) => (true, cmd::get_script(self, args)),
                 _ => panic!("No subcommands matched: {:#?}", args),
             },
             Err(e) => (true, Err(format!("{}", e.message))),
         }
     }
 }
+
+fn parse_node_url(url: &str) -> Result<Url, String> {
+    let mut url: Url = url.parse().map_err(|e| match e {
+        url::ParseError::EmptyHost => "node URL must include a host".to_string(),
+        e => format!("Invalid node URL: {}", e),
+    })?;
+    match url.scheme() {
+        "ws" | "wss" => {}
+        _ => return Err("node URL must use ws:// or wss://".to_string()),
+    }
+    if url.host_str().is_none() {
+        return Err("node URL must include a host".to_string());
+    }
+    if url.port().is_none() {
+        url.set_port(Some(7777)).unwrap();
+    }
+    Ok(url)
+}
+
+fn cli() -> App<'static, 'static> {
+    App::new("")
+        .setting(AppSettings::NoBinaryName)
+        .setting(AppSettings::DisableVersion)
+        .setting(AppSettings::VersionlessSubcommands)
+        .subcommand(
+            SubCommand::with_name("new")
+                .about("Create a new wallet")
+                .arg(
+                    Arg::with_name("password")
+                        .required(true)
+                        .help("Password used to encrypt the wallet"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("unlock")
+                .about("Unlocks an existing wallet")
+                .arg(
+                    Arg::with_name("password")
+                        .required(true)
+                        .help("Password used to unlock the wallet"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("export_wallet")
+                .about("Exports the wallet accounts to an encrypted file")
+                .arg(
+                    Arg::with_name("path")
+                        .long("path")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Path of the file to create"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("import_wallet")
+                .about("Imports wallet accounts from an encrypted file")
+                .arg(
+                    Arg::with_name("path")
+                        .long("path")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Path of the exported file"),
+                )
+                .arg(
+                    Arg::with_name("password")
+                        .long("password")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Password of the wallet that exported the file"),
+                )
+                .arg(
+                    Arg::with_name("overwrite")
+                        .long("overwrite")
+                        .help("Replace existing accounts with the same name"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("gen_keypair").about("Generates a keypair for use with accounts"),
+        )
+        .subcommand(
+            SubCommand::with_name("account_id_to_address")
+                .about("Converts an ID to a pay-to-address WIF")
+                .arg(
+                    Arg::with_name("id")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Decimal ID to convert"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("import_account")
+                .about("Import an account")
+                .arg(
+                    Arg::with_name("name")
+                        .long("name")
+                        .required(true)
+                        .takes_value(true)
+                        .help("The name of the account (stored in the wallet only)"),
+                )
+                .arg(
+                    Arg::with_name("account")
+                        .long("account")
+                        .required(true)
+                        .takes_value(true)
+                        .help("The account address"),
+                )
+                .arg(
+                    Arg::with_name("wif")
+                        .long("wif")
+                        .required(true)
+                        .takes_value(true)
+                        .multiple(true)
+                        .help("Private WIF key for the account [accepts multiple for multisig]"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("delete_account")
+                .about("Delete an account")
+                .arg(
+                    Arg::with_name("name")
+                        .long("name")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Name of the account to delete"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("list_accounts")
+                .about("List available accounts in the wallet")
+                .arg(
+                    Arg::with_name("balances")
+                        .long("balances")
+                        .help("Fetch the balance of each account from the node"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("get_account")
+                .about("Retrieve account keys and addresses")
+                .arg(
+                    Arg::with_name("name")
+                        .long("name")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Name of the account"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("add_contact")
+                .about("Add a labeled external address to the address book")
+                .arg(
+                    Arg::with_name("label")
+                        .required(true)
+                        .takes_value(true)
+                        .help("The unique label of the contact"),
+                )
+                .arg(
+                    Arg::with_name("address")
+                        .required(true)
+                        .takes_value(true)
+                        .help("The account address of the contact"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("remove_contact")
+                .about("Remove a contact from the address book")
+                .arg(
+                    Arg::with_name("label")
+                        .required(true)
+                        .takes_value(true)
+                        .help("The label of the contact to remove"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("list_contacts").about("List contacts in the address book"),
+        )
+        .subcommand(
+            SubCommand::with_name("get_account_info")
+                .about("Retrieve address information from the blockchain")
+                .arg(
+                    Arg::with_name("account")
+                        .long("account")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Wallet account name or ID"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("balance")
+                .about("Retrieve the balance of an account from the blockchain")
+                .arg(
+                    Arg::with_name("account")
+                        .long("account")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Wallet account name or address"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("build_script")
+                .about("Builds a script with the provided ops")
+                .arg(
+                    Arg::with_name("ops")
+                        .required(true)
+                        .takes_value(true)
+                        .multiple(true)
+                        .help("Script operations"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("args_to_bin")
+                .about("Convert an array of arguments to hex to pass as script args")
+                .arg(
+                    Arg::with_name("arg")
+                        .required(true)
+                        .takes_value(true)
+                        .multiple(true)
+                        .help("An argument that starts address={} or asset={}"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("check_script_size")
+                .about("Checks if the script is too large and prints the size in bytes")
+                .arg(
+                    Arg::with_name("hex")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Binary script in hex format"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("decode_tx")
+                .about("Decodes a transaction and prints it to console")
+                .arg(
+                    Arg::with_name("hex")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Binary transaction in hex format, @<path> to read a file or - for stdin"),
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .takes_value(true)
+                        .possible_values(&["debug", "json"])
+                        .default_value("debug")
+                        .help("Output format of the decoded transaction"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("sign_tx")
+                .about("Signs a raw transaction")
+                .arg(
+                    Arg::with_name("hex")
+                        .long("hex")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Binary transaction in hex format, @<path> to read a file or - for stdin"),
+                )
+                .arg(
+                    Arg::with_name("account")
+                        .long("account")
+                        .required_unless("wif")
+                        .takes_value(true)
+                        .multiple(true)
+                        .help("Account to sign the transaction, accepts multiple"),
+                )
+                .arg(
+                    Arg::with_name("wif")
+                        .long("wif")
+                        .takes_value(true)
+                        .multiple(true)
+                        .help("Private WIF key to sign the transaction, accepts multiple"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("resign_tx")
+                .about("Replaces all signatures of a raw transaction with new signatures")
+                .arg(
+                    Arg::with_name("hex")
+                        .long("hex")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Binary transaction in hex format"),
+                )
+                .arg(
+                    Arg::with_name("account")
+                        .long("account")
+                        .required(true)
+                        .takes_value(true)
+                        .multiple(true)
+                        .help("Account to sign the transaction, accepts multiple"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("verify_tx")
+                .about("Verifies the signatures of a raw transaction against its ID")
+                .arg(
+                    Arg::with_name("hex")
+                        .long("hex")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Binary transaction in hex format"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("unsign_tx")
+                .about("Removes a signature from a raw transaction")
+                .arg(
+                    Arg::with_name("hex")
+                        .long("hex")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Binary transaction in hex format"),
+                )
+                .arg(
+                    Arg::with_name("index")
+                        .long("index")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Index position of the signature to remove"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("export_sigs")
+                .about("Prints the signatures of a raw transaction to share with other signers")
+                .arg(
+                    Arg::with_name("hex")
+                        .long("hex")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Binary transaction in hex format"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("attach_sig")
+                .about("Attaches signatures exported from another signer to a raw transaction")
+                .arg(
+                    Arg::with_name("hex")
+                        .long("hex")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Binary transaction in hex format"),
+                )
+                .arg(
+                    Arg::with_name("sig")
+                        .long("sig")
+                        .required(true)
+                        .takes_value(true)
+                        .multiple(true)
+                        .help("Exported signature to attach, accepts multiple"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("broadcast")
+                .about("Broadcast a transaction to the network")
+                .arg(
+                    Arg::with_name("hex")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Binary transaction in hex format, @<path> to read a file or - for stdin"),
+                )
+                .arg(
+                    Arg::with_name("force")
+                        .long("force")
+                        .help("Broadcast even if the fee is below the required fee"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("check_tx")
+                .about("Checks whether the network would accept a transaction without broadcasting")
+                .arg(
+                    Arg::with_name("hex")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Binary transaction in hex format"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("build_create_account_tx")
+                .about("Builds a create account transaction")
+                .arg(
+                    Arg::with_name("expiry")
+                        .long("expiry")
+                        .takes_value(true)
+                        .required(true)
+                        .default_value("60000")
+                        .help("The time in milliseconds when a transaction expires from now"),
+                )
+                .arg(
+                    Arg::with_name("fee")
+                        .long("fee")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The fee to pay for the transaction"),
+                )
+                .arg(
+                    Arg::with_name("creator")
+                        .long("creator")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The creator account or account address"),
+                )
+                .arg(
+                    Arg::with_name("balance")
+                        .long("balance")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The balance to be deposited in the new account"),
+                )
+                .arg(
+                    Arg::with_name("script")
+                        .long("script")
+                        .takes_value(true)
+                        .required(false)
+                        .help("The script to apply to the account, otherwise use a default script"),
+                )
+                .arg(
+                    Arg::with_name("threshold")
+                        .long("threshold")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Account permissions signatory threshold for verification"),
+                )
+                .arg(
+                    Arg::with_name("public_wif")
+                        .long("public-wif")
+                        .takes_value(true)
+                        .required(true)
+                        .multiple(true)
+                        .help("Public key allowed for signing to meet the signatory threshold"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("build_update_account_tx")
+                .about("Builds an update account transaction")
+                .arg(
+                    Arg::with_name("expiry")
+                        .long("expiry")
+                        .takes_value(true)
+                        .required(true)
+                        .default_value("60000")
+                        .help("The time in milliseconds when a transaction expires from now"),
+                )
+                .arg(
+                    Arg::with_name("fee")
+                        .long("fee")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The fee to pay for the transaction"),
+                )
+                .arg(
+                    Arg::with_name("account")
+                        .long("account")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The account to update"),
+                )
+                .arg(
+                    Arg::with_name("script")
+                        .long("script")
+                        .takes_value(true)
+                        .required(false)
+                        .help("New account script in hex format"),
+                )
+                .arg(
+                    Arg::with_name("threshold")
+                        .long("threshold")
+                        .takes_value(true)
+                        .required(false)
+                        .requires("public_wif")
+                        .help(
+                            "New permissions threshold. This requires providing new \
+                            signing keys.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("public_wif")
+                        .long("public-wif")
+                        .takes_value(true)
+                        .required(false)
+                        .multiple(true)
+                        .requires("threshold")
+                        .help(
+                            "New account signing keys, will replace all current keys \
+                            associated with the account. This requires setting a new \
+                            threshold.",
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("build_mint_tx")
+                .about("Builds a mint transaction")
+                .arg(
+                    Arg::with_name("expiry")
+                        .long("expiry")
+                        .takes_value(true)
+                        .required(true)
+                        .default_value("60000")
+                        .help("The time in milliseconds when a transaction expires from now"),
+                )
+                .arg(
+                    Arg::with_name("amount")
+                        .long("amount")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The amount of tokens to be minted"),
+                )
+                .arg(
+                    Arg::with_name("attachment_path")
+                        .long("attachment-path")
+                        .takes_value(true)
+                        .requires("attachment_name")
+                        .help("The path to the attachment for the transaction"),
+                )
+                .arg(
+                    Arg::with_name("attachment_name")
+                        .long("attachment-name")
+                        .takes_value(true)
+                        .help("The name of the attachment"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("build_owner_tx")
+                .about("Builds an owner transaction")
+                .arg(
+                    Arg::with_name("expiry")
+                        .long("expiry")
+                        .takes_value(true)
+                        .required(true)
+                        .default_value("60000")
+                        .help("The time in milliseconds when a transaction expires from now"),
+                )
+                .arg(
+                    Arg::with_name("minter")
+                        .long("minter")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The public key of the new minter"),
+                )
+                .arg(
+                    Arg::with_name("wallet")
+                        .long("wallet")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The account address of the new hot wallet"),
+                )
+                .arg(
+                    Arg::with_name("fee")
+                        .long("fee")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The fee to pay for the transaction"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("build_transfer_tx")
+                .about("Builds a transfer transaction")
+                .arg(
+                    Arg::with_name("expiry")
+                        .long("expiry")
+                        .takes_value(true)
+                        .required(true)
+                        .default_value("60000")
+                        .help("The time in milliseconds when a transaction expires from now"),
+                )
+                .arg(
+                    Arg::with_name("from_account")
+                        .long("from-account")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The account to transfer from"),
+                )
+                .arg(
+                    Arg::with_name("call_fn")
+                        .long("call-fn")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The function to call in the script"),
+                )
+                .arg(
+                    Arg::with_name("args")
+                        .long("args")
+                        .takes_value(true)
+                        .help("The hex value of the arguments that the script requires"),
+                )
+                .arg(
+                    Arg::with_name("to")
+                        .long("to")
+                        .takes_value(true)
+                        .conflicts_with("args")
+                        .help(
+                            "The contact label or account address to transfer to, builds the \
+                             arguments of the default account script",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("amount")
+                        .long("amount")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The amount of tokens allowed to be transferred"),
+                )
+                .arg(
+                    Arg::with_name("fee")
+                        .long("fee")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The fee to pay for the transaction"),
+                )
+                .arg(
+                    Arg::with_name("memo")
+                        .long("memo")
+                        .takes_value(true)
+                        .help("The memo to send with the transaction"),
+                )
+                .arg(
+                    Arg::with_name("nonce")
+                        .long("nonce")
+                        .takes_value(true)
+                        .help(
+                            "The transaction nonce, must be greater than the nonce of the last \
+                             transfer from the account, defaults to the nonce of the last \
+                             transfer as known by the node plus one, required to build the \
+                             transaction offline",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("encrypt_memo")
+                        .long("encrypt-memo")
+                        .takes_value(true)
+                        .requires("memo")
+                        .help("The public key of the recipient to encrypt the memo to"),
+                ),
+        )
+        .subcommand(SubCommand::with_name("get_properties").about("Retrieve network properties"))
+        .subcommand(
+            SubCommand::with_name("get_block")
+                .about("Retrieve a block from the network")
+                .arg(
+                    Arg::with_name("height")
+                        .required(true)
+                        .takes_value(true)
+                        .help("The height of the block to retrieve"),
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .takes_value(true)
+                        .possible_values(&["debug", "hex", "json"])
+                        .default_value("debug")
+                        .help("Output format of the block"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("get_tx")
+                .about("Retrieve an included transaction from the network")
+                .arg(
+                    Arg::with_name("txid")
+                        .long("txid")
+                        .required(true)
+                        .takes_value(true)
+                        .help("The transaction ID in hex format"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("get_script")
+                .about("Retrieve the script behind a script hash from the network")
+                .arg(
+                    Arg::with_name("address")
+                        .long("address")
+                        .required(true)
+                        .takes_value(true)
+                        .help("The script hash in hex format"),
+                ),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_url_errors() {
+        let home = PathBuf::from("/nonexistent");
+        let err = |url| Wallet::new(home.clone(), url, false).err().unwrap();
+
+        assert_eq!(
+            err("ws://localhost:port"),
+            "Invalid node URL: invalid port number"
+        );
+        assert_eq!(err("ws://"), "node URL must include a host");
+        assert_eq!(err("ws://:7777"), "node URL must include a host");
+        assert_eq!(err("http://localhost"), "node URL must use ws:// or wss://");
+        assert_eq!(err("localhost:7777"), "node URL must use ws:// or wss://");
+    }
+
+    #[test]
+    fn node_url_default_port() {
+        let url = parse_node_url("ws://localhost").unwrap();
+        assert_eq!(url.port(), Some(7777));
+        let url = parse_node_url("wss://localhost:1234").unwrap();
+        assert_eq!(url.port(), Some(1234));
+    }
+}
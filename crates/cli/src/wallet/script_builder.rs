@@ -52,6 +52,14 @@ pub fn build(ops: &[&str]) -> Result<Script, BuildError> {
                         }
                         None => return Err(BuildError::MissingArgForOp(op.to_string())),
                     },
+                    "OP_INT" => {
+                        let int = iter
+                            .next()
+                            .ok_or_else(|| BuildError::MissingArgForOp(op.to_string()))?
+                            .parse()
+                            .map_err(|e: ParseIntError| BuildError::Other(format!("{}", e)))?;
+                        builder.push(OpFrame::Int(int))
+                    }
                     // Arithmetic
                     "OP_LOADAMT" => builder.push(OpFrame::OpLoadAmt),
                     "OP_LOADREMAMT" => builder.push(OpFrame::OpLoadRemAmt),
@@ -112,6 +120,10 @@ pub fn build(ops: &[&str]) -> Result<Script, BuildError> {
                             .map_err(|e: ParseIntError| BuildError::Other(format!("{}", e)))?;
                         builder.push(OpFrame::OpCheckTimeFastFail(time))
                     }
+                    // Comparison
+                    "OP_NUMEQUAL" => builder.push(OpFrame::OpNumEqual),
+                    "OP_LESSTHAN" => builder.push(OpFrame::OpLessThan),
+                    "OP_GREATERTHAN" => builder.push(OpFrame::OpGreaterThan),
                     _ => return Err(BuildError::UnknownOp(op.to_string())),
                 })
             }
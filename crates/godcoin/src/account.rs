@@ -1,9 +1,11 @@
 use crate::{
     asset::Asset,
+    constants::MAX_SCRIPT_DECODE_SIZE,
     crypto::{PublicKey, SigPair},
     script::{Arg, Builder, FnBuilder, OpFrame, Script},
     serializer::*,
 };
+use sodiumoxide::crypto::sign::PUBLICKEYBYTES;
 use std::io::{self, Cursor};
 
 pub type AccountId = u64;
@@ -40,7 +42,19 @@ impl Account {
         }
     }
 
+    /// Returns the exact number of bytes `serialize` writes.
+    pub fn byte_size(&self) -> usize {
+        2 + 8
+            + var_i64_byte_size(self.balance.amount)
+            + bytes_byte_size(&self.script)
+            + self.permissions.byte_size()
+            + 1
+    }
+
     pub fn serialize(&self, buf: &mut Vec<u8>) {
+        // Account version (2 bytes)
+        buf.push_u16(0x00);
+
         buf.push_u64(self.id);
         buf.push_asset(self.balance);
         buf.push_bytes(&self.script);
@@ -49,18 +63,27 @@ impl Account {
     }
 
     pub fn deserialize(cur: &mut Cursor<&[u8]>) -> io::Result<Self> {
-        let id = cur.take_u64()?;
-        let balance = cur.take_asset()?;
-        let script = Script::new(cur.take_bytes()?);
-        let permissions = Permissions::deserialize(cur)?;
-        let destroyed = cur.take_u8()? != 0;
-        Ok(Self {
-            id,
-            balance,
-            script,
-            permissions,
-            destroyed,
-        })
+        let version = cur.take_u16()?;
+        match version {
+            0x00 => {
+                let id = cur.take_u64()?;
+                let balance = cur.take_asset()?;
+                let script = Script::new(cur.take_bytes_capped(MAX_SCRIPT_DECODE_SIZE)?);
+                let permissions = Permissions::deserialize(cur)?;
+                let destroyed = cur.take_u8()? != 0;
+                Ok(Self {
+                    id,
+                    balance,
+                    script,
+                    permissions,
+                    destroyed,
+                })
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unknown account version",
+            )),
+        }
     }
 }
 
@@ -124,6 +147,12 @@ impl Permissions {
         true
     }
 
+    /// Returns the exact number of bytes `serialize` writes.
+    #[inline]
+    pub fn byte_size(&self) -> usize {
+        2 + self.keys.len() * PUBLICKEYBYTES
+    }
+
     pub fn serialize(&self, buf: &mut Vec<u8>) {
         buf.push(self.threshold);
         buf.push(self.keys.len() as u8);
@@ -311,6 +340,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn serialize_account_v0() {
+        let (mut account, _) = create_dummy_account(2, 3);
+        account.id = 1234;
+        account.balance = Asset::new(100);
+        account.script = Script::new(vec![0x01, 0x02, 0x03]);
+        account.destroyed = true;
+
+        let mut buf = vec![];
+        account.serialize(&mut buf);
+        assert_eq!(&buf[0..2], &[0x00, 0x00]);
+
+        let cur = &mut Cursor::<&[u8]>::new(&buf);
+        assert_eq!(Account::deserialize(cur).unwrap(), account);
+        assert_eq!(cur.position() as usize, buf.len());
+    }
+
+    #[test]
+    fn deserialize_unknown_account_version() {
+        let (account, _) = create_dummy_account(1, 1);
+        let mut buf = vec![];
+        account.serialize(&mut buf);
+        buf[0..2].copy_from_slice(&1u16.to_be_bytes());
+
+        let cur = &mut Cursor::<&[u8]>::new(&buf);
+        let err = Account::deserialize(cur).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
     fn create_dummy_account(threshold: u8, key_count: u8) -> (Account, Vec<KeyPair>) {
         let keys: Vec<KeyPair> = (0..key_count).map(|_| KeyPair::gen()).collect();
         let account = Account {
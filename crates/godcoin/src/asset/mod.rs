@@ -1,6 +1,6 @@
 use num_bigint::BigInt;
 use num_traits::ToPrimitive;
-use std::{fmt, str::FromStr};
+use std::{convert::TryFrom, fmt, str::FromStr};
 
 mod precision;
 use self::precision::*;
@@ -17,6 +17,19 @@ pub const ASSET_SYMBOL: &str = "TEST";
 pub const MAX_STR_LEN: usize = 26;
 pub const MAX_PRECISION: u8 = 5;
 
+/// Number of basis points that represent the whole amount.
+pub const MAX_BASIS_POINTS: u16 = 10_000;
+
+/// Display options for `Asset::format`. The formatted string is meant for display only and cannot
+/// be parsed back into an asset unless every option is disabled.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FormatOpts {
+    /// Separates every three digits of the whole amount with a comma.
+    pub grouping: bool,
+    /// Removes trailing zeros of the decimals, including the decimal point of whole amounts.
+    pub trim_zeros: bool,
+}
+
 #[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Asset {
     pub amount: i64,
@@ -28,6 +41,16 @@ impl Asset {
         Asset { amount }
     }
 
+    #[inline]
+    pub const fn zero() -> Asset {
+        Asset { amount: 0 }
+    }
+
+    #[inline]
+    pub fn is_zero(self) -> bool {
+        self.amount == 0
+    }
+
     #[inline]
     pub fn checked_add(self, other: Self) -> Option<Self> {
         Some(Asset {
@@ -42,6 +65,16 @@ impl Asset {
         })
     }
 
+    /// Negates the asset, returning `None` when the amount is `i64::MIN`. Negative assets are only
+    /// valid as intermediate values of a computation, such as reversing a balance change, and must
+    /// never be used as an amount in a transaction.
+    #[inline]
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Asset {
+            amount: self.amount.checked_neg()?,
+        })
+    }
+
     pub fn checked_mul(self, other: Self) -> Option<Self> {
         const MUL_PRECISION: u8 = MAX_PRECISION * 2;
         let mul = i128::from(self.amount).checked_mul(i128::from(other.amount))?;
@@ -55,7 +88,7 @@ impl Asset {
     }
 
     pub fn checked_div(self, other: Self) -> Option<Self> {
-        if other.amount == 0 {
+        if other.is_zero() {
             return None;
         }
         const DIV_PRECISION: u8 = MAX_PRECISION * 2;
@@ -94,6 +127,74 @@ impl Asset {
             amount: res.to_i64()?,
         })
     }
+
+    /// Calculates the share of the asset represented by `bps` basis points, rounding toward zero.
+    /// Basis points above `MAX_BASIS_POINTS` return a larger amount than the asset.
+    pub fn percent(self, bps: u16) -> Option<Self> {
+        let amount = i128::from(self.amount) * i128::from(bps) / i128::from(MAX_BASIS_POINTS);
+        Some(Asset {
+            amount: i64::try_from(amount).ok()?,
+        })
+    }
+
+    /// Divides the asset into `n` parts that sum to the asset exactly. The remainder of the division
+    /// is distributed one unit at a time to the first parts. Returns no parts when `n` is 0.
+    pub fn split(self, n: u32) -> Vec<Self> {
+        if n == 0 {
+            return vec![];
+        }
+        let n = i64::from(n);
+        let part = self.amount / n;
+        let rem = self.amount % n;
+        (0..n)
+            .map(|i| {
+                let extra = if i < rem.abs() { rem.signum() } else { 0 };
+                Asset::new(part + extra)
+            })
+            .collect()
+    }
+}
+
+impl Asset {
+    /// Formats the asset for display. Use `to_string` for the canonical form.
+    pub fn format(self, opts: FormatOpts) -> String {
+        let s = self.to_string();
+        let amount = &s[..s.len() - ASSET_SYMBOL.len() - 1];
+        let (sign, amount) = match amount.strip_prefix('-') {
+            Some(amount) => ("-", amount),
+            None => ("", amount),
+        };
+        let (mut whole, mut decimals) = amount.split_at(amount.find('.').unwrap());
+        decimals = &decimals[1..];
+        if whole.is_empty() {
+            whole = "0";
+        }
+
+        let mut out = String::with_capacity(s.len() + whole.len() / 3);
+        out.push_str(sign);
+        if opts.grouping {
+            for (i, c) in whole.chars().enumerate() {
+                if i > 0 && (whole.len() - i) % 3 == 0 {
+                    out.push(',');
+                }
+                out.push(c);
+            }
+        } else {
+            out.push_str(whole);
+        }
+
+        if opts.trim_zeros {
+            decimals = decimals.trim_end_matches('0');
+        }
+        if !decimals.is_empty() {
+            out.push('.');
+            out.push_str(decimals);
+        }
+
+        out.push(' ');
+        out.push_str(ASSET_SYMBOL);
+        out
+    }
 }
 
 impl fmt::Debug for Asset {
@@ -236,6 +337,48 @@ mod tests {
         c(get_asset("1.00000 TEST"), "1.00000 TEST");
     }
 
+    #[test]
+    fn format_asset() {
+        let c = |asset: &str, opts: FormatOpts, s: &str| {
+            assert_eq!(get_asset(asset).format(opts), s);
+        };
+        let grouping = FormatOpts {
+            grouping: true,
+            trim_zeros: false,
+        };
+        let trim_zeros = FormatOpts {
+            grouping: false,
+            trim_zeros: true,
+        };
+        let both = FormatOpts {
+            grouping: true,
+            trim_zeros: true,
+        };
+
+        c("1000.00000 TEST", grouping, "1,000.00000 TEST");
+        c("100.00000 TEST", grouping, "100.00000 TEST");
+        c("1234567.00001 TEST", grouping, "1,234,567.00001 TEST");
+        c("-123456.50000 TEST", grouping, "-123,456.50000 TEST");
+        c("0.00001 TEST", grouping, "0.00001 TEST");
+
+        c("1.50000 TEST", trim_zeros, "1.5 TEST");
+        c("1.00000 TEST", trim_zeros, "1 TEST");
+        c("0.00000 TEST", trim_zeros, "0 TEST");
+        c("-0.10010 TEST", trim_zeros, "-0.1001 TEST");
+        c("1000.00001 TEST", trim_zeros, "1000.00001 TEST");
+
+        c("-1234567.25000 TEST", both, "-1,234,567.25 TEST");
+        c("1000000.00000 TEST", both, "1,000,000 TEST");
+
+        // The default options keep the canonical form
+        for asset in &["1234567.50000 TEST", "-0.00001 TEST", "0.00000 TEST"] {
+            let a = get_asset(asset);
+            assert_eq!(a.format(FormatOpts::default()), a.to_string());
+            assert_eq!(a.to_string(), *asset);
+            assert_eq!(get_asset(&a.to_string()), a);
+        }
+    }
+
     #[test]
     fn fail_parsing_invalid_input() {
         let c = |asset: &str, err: AssetErrorKind| {
@@ -343,6 +486,81 @@ mod tests {
         assert_eq!(a.checked_mul(b), None);
     }
 
+    #[test]
+    fn negate_asset() {
+        let a = get_asset("10.00000 TEST");
+        assert_eq!(a.checked_neg(), Some(get_asset("-10.00000 TEST")));
+        assert_eq!(a.checked_neg().unwrap().checked_neg(), Some(a));
+        assert_eq!(Asset::zero().checked_neg(), Some(Asset::zero()));
+        assert_eq!(
+            Asset::new(i64::MAX).checked_neg(),
+            Some(Asset::new(-i64::MAX))
+        );
+        assert_eq!(Asset::new(i64::MIN).checked_neg(), None);
+    }
+
+    #[test]
+    fn zero_asset() {
+        assert!(Asset::zero().is_zero());
+        assert_eq!(Asset::zero(), Asset::default());
+        assert_eq!(Asset::zero(), get_asset("0.00000 TEST"));
+        assert_eq!(Asset::zero().to_string(), "0.00000 TEST");
+
+        assert!(!Asset::new(1).is_zero());
+        assert!(!get_asset("-0.00001 TEST").is_zero());
+        assert!(!get_asset("1.00000 TEST").is_zero());
+    }
+
+    #[test]
+    fn percent_of_asset() {
+        let a = get_asset("123.45678 TEST");
+        assert_eq!(a.percent(MAX_BASIS_POINTS), Some(a));
+        assert_eq!(a.percent(0), Some(Asset::default()));
+        assert_eq!(a.percent(5000), Some(get_asset("61.72839 TEST")));
+        assert_eq!(a.percent(1), Some(get_asset("0.01234 TEST")));
+        assert_eq!(a.percent(u16::MAX), Some(get_asset("809.07400 TEST")));
+        assert_eq!(
+            get_asset("-1.00000 TEST").percent(2500),
+            Some(get_asset("-0.25000 TEST"))
+        );
+        assert_eq!(Asset::new(i64::MAX).percent(20_000), None);
+    }
+
+    #[test]
+    fn split_asset() {
+        let sum = |parts: &[Asset]| {
+            parts
+                .iter()
+                .fold(Asset::default(), |acc, a| acc.checked_add(*a).unwrap())
+        };
+
+        let a = get_asset("10.00000 TEST");
+        assert_eq!(a.split(4), vec![get_asset("2.50000 TEST"); 4]);
+        assert_eq!(a.split(1), vec![a]);
+        assert!(a.split(0).is_empty());
+
+        let parts = Asset::new(10).split(3);
+        assert_eq!(parts, vec![Asset::new(4), Asset::new(3), Asset::new(3)]);
+        assert_eq!(sum(&parts), Asset::new(10));
+
+        let parts = Asset::new(-10).split(3);
+        assert_eq!(parts, vec![Asset::new(-4), Asset::new(-3), Asset::new(-3)]);
+        assert_eq!(sum(&parts), Asset::new(-10));
+
+        // More parts than the smallest unit of the asset
+        let parts = Asset::new(2).split(5);
+        assert_eq!(sum(&parts), Asset::new(2));
+        assert_eq!(&parts[2..], &[Asset::default(); 3]);
+
+        for (amount, n) in &[(123_456_789, 7), (i64::MAX, 13), (i64::MIN, 9)] {
+            let asset = Asset::new(*amount);
+            let parts = asset.split(*n);
+            assert_eq!(parts.len(), *n as usize);
+            let total = parts.iter().map(|a| i128::from(a.amount)).sum::<i128>();
+            assert_eq!(total, i128::from(*amount));
+        }
+    }
+
     fn get_asset(s: &str) -> Asset {
         Asset::from_str(s).unwrap()
     }
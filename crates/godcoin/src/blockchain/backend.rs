@@ -0,0 +1,313 @@
+use parking_lot::RwLock;
+use rocksdb::{
+    BlockBasedOptions, ColumnFamilyDescriptor, DBCompressionType, Direction, IteratorMode, Options,
+    DB,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    ops::Bound,
+    path::Path,
+};
+
+use super::index::{IndexerError, IndexerOptions};
+
+/// Name of the column family that holds keys without a dedicated column family.
+pub const CF_DEFAULT: &str = "default";
+
+pub type KvIter<'a> = Box<dyn Iterator<Item = (Box<[u8]>, Box<[u8]>)> + 'a>;
+
+/// Key-value storage of the `Indexer`. Keys are grouped into column families and ordered
+/// bytewise within each column family.
+pub trait IndexBackend: Debug + Send + Sync {
+    fn get(&self, cf: &str, key: &[u8]) -> Result<Option<Vec<u8>>, IndexerError>;
+
+    /// Reads the value of every key in the same order as `keys`. All keys are read from a
+    /// consistent view of the column family, unaffected by writes made while reading.
+    fn multi_get(&self, cf: &str, keys: &[&[u8]]) -> Result<Vec<Option<Vec<u8>>>, IndexerError>;
+
+    /// Iterates over the entries of the column family in key order. `IterMode::End` iterates in
+    /// reverse order from the last key.
+    fn iter(&self, cf: &str, mode: IterMode<'_>) -> KvIter<'_>;
+
+    /// Applies every operation of the batch atomically.
+    fn write(&self, batch: Batch) -> Result<(), IndexerError>;
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum IterMode<'a> {
+    Start,
+    End,
+    From(&'a [u8]),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BatchOp {
+    Put(&'static str, Vec<u8>, Vec<u8>),
+    Delete(&'static str, Vec<u8>),
+    /// Deletes the keys from the first key inclusively to the second key exclusively.
+    DeleteRange(&'static str, Vec<u8>, Vec<u8>),
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Batch(Vec<BatchOp>);
+
+impl Batch {
+    #[inline]
+    pub fn ops(&self) -> &[BatchOp] {
+        &self.0
+    }
+
+    pub fn put<K: AsRef<[u8]>, V: AsRef<[u8]>>(&mut self, cf: &'static str, key: K, value: V) {
+        self.0.push(BatchOp::Put(
+            cf,
+            key.as_ref().to_vec(),
+            value.as_ref().to_vec(),
+        ));
+    }
+
+    pub fn delete<K: AsRef<[u8]>>(&mut self, cf: &'static str, key: K) {
+        self.0.push(BatchOp::Delete(cf, key.as_ref().to_vec()));
+    }
+
+    pub fn delete_range<K: AsRef<[u8]>>(&mut self, cf: &'static str, from: K, to: K) {
+        self.0.push(BatchOp::DeleteRange(
+            cf,
+            from.as_ref().to_vec(),
+            to.as_ref().to_vec(),
+        ));
+    }
+}
+
+/// Stores the index in a RocksDB database on disk.
+#[derive(Debug)]
+pub struct RocksBackend {
+    pub(crate) db: DB,
+}
+
+impl RocksBackend {
+    pub fn open(path: &Path, opts: IndexerOptions, col_families: &[&str]) -> Self {
+        let mut db_opts = to_db_options(opts);
+        db_opts.create_missing_column_families(true);
+        db_opts.create_if_missing(true);
+        db_opts.set_wal_recovery_mode(opts.wal_recovery_mode);
+
+        let col_families = col_families
+            .iter()
+            .map(|name| ColumnFamilyDescriptor::new(*name, to_db_options(opts)))
+            .collect::<Vec<_>>();
+        let db = DB::open_cf_descriptors(&db_opts, path, col_families).unwrap();
+        RocksBackend { db }
+    }
+}
+
+impl IndexBackend for RocksBackend {
+    fn get(&self, cf: &str, key: &[u8]) -> Result<Option<Vec<u8>>, IndexerError> {
+        Ok(if cf == CF_DEFAULT {
+            self.db.get(key)?
+        } else {
+            let cf = self.db.cf_handle(cf).unwrap();
+            self.db.get_cf(cf, key)?
+        })
+    }
+
+    fn multi_get(&self, cf: &str, keys: &[&[u8]]) -> Result<Vec<Option<Vec<u8>>>, IndexerError> {
+        let snapshot = self.db.snapshot();
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            values.push(if cf == CF_DEFAULT {
+                snapshot.get(key)?
+            } else {
+                snapshot.get_cf(self.db.cf_handle(cf).unwrap(), key)?
+            });
+        }
+        Ok(values)
+    }
+
+    fn iter(&self, cf: &str, mode: IterMode<'_>) -> KvIter<'_> {
+        let mode = match mode {
+            IterMode::Start => IteratorMode::Start,
+            IterMode::End => IteratorMode::End,
+            IterMode::From(key) => IteratorMode::From(key, Direction::Forward),
+        };
+        if cf == CF_DEFAULT {
+            Box::new(self.db.iterator(mode))
+        } else {
+            let cf = self.db.cf_handle(cf).unwrap();
+            Box::new(self.db.iterator_cf(cf, mode))
+        }
+    }
+
+    fn write(&self, batch: Batch) -> Result<(), IndexerError> {
+        let mut rocks_batch = rocksdb::WriteBatch::default();
+        for op in batch.0 {
+            match op {
+                BatchOp::Put(CF_DEFAULT, key, value) => rocks_batch.put(key, value),
+                BatchOp::Put(cf, key, value) => {
+                    rocks_batch.put_cf(self.db.cf_handle(cf).unwrap(), key, value)
+                }
+                BatchOp::Delete(CF_DEFAULT, key) => rocks_batch.delete(key),
+                BatchOp::Delete(cf, key) => {
+                    rocks_batch.delete_cf(self.db.cf_handle(cf).unwrap(), key)
+                }
+                BatchOp::DeleteRange(cf, from, to) => {
+                    rocks_batch.delete_range_cf(self.db.cf_handle(cf).unwrap(), from, to)
+                }
+            }
+        }
+        self.db.write(rocks_batch)?;
+        Ok(())
+    }
+}
+
+fn to_db_options(opts: IndexerOptions) -> Options {
+    let mut db_opts = Options::default();
+    if let Some(size) = opts.block_cache_size {
+        let mut table_opts = BlockBasedOptions::default();
+        table_opts.set_lru_cache(size);
+        db_opts.set_block_based_table_factory(&table_opts);
+    }
+    if !opts.compression {
+        db_opts.set_compression_type(DBCompressionType::None);
+    }
+    db_opts
+}
+
+type MemColumnFamily = BTreeMap<Box<[u8]>, Box<[u8]>>;
+
+/// Stores the index in memory, the index is lost when the backend is dropped.
+#[derive(Debug, Default)]
+pub struct MemBackend {
+    col_families: RwLock<HashMap<String, MemColumnFamily>>,
+}
+
+impl IndexBackend for MemBackend {
+    fn get(&self, cf: &str, key: &[u8]) -> Result<Option<Vec<u8>>, IndexerError> {
+        let col_families = self.col_families.read();
+        Ok(col_families
+            .get(cf)
+            .and_then(|cf| cf.get(key))
+            .map(|value| value.to_vec()))
+    }
+
+    fn multi_get(&self, cf: &str, keys: &[&[u8]]) -> Result<Vec<Option<Vec<u8>>>, IndexerError> {
+        let col_families = self.col_families.read();
+        let cf = col_families.get(cf);
+        Ok(keys
+            .iter()
+            .map(|key| cf.and_then(|cf| cf.get(*key)).map(|value| value.to_vec()))
+            .collect())
+    }
+
+    fn iter(&self, cf: &str, mode: IterMode<'_>) -> KvIter<'_> {
+        let (next, reverse) = match mode {
+            IterMode::Start => (Bound::Unbounded, false),
+            IterMode::End => (Bound::Unbounded, true),
+            IterMode::From(key) => (Bound::Included(key.into()), false),
+        };
+        Box::new(MemIter {
+            backend: self,
+            cf: cf.to_owned(),
+            next,
+            reverse,
+        })
+    }
+
+    fn write(&self, batch: Batch) -> Result<(), IndexerError> {
+        let mut col_families = self.col_families.write();
+        for op in batch.0 {
+            match op {
+                BatchOp::Put(cf, key, value) => {
+                    let cf = col_families.entry(cf.to_owned()).or_default();
+                    cf.insert(key.into(), value.into());
+                }
+                BatchOp::Delete(cf, key) => {
+                    if let Some(cf) = col_families.get_mut(cf) {
+                        cf.remove(key.as_slice());
+                    }
+                }
+                BatchOp::DeleteRange(cf, from, to) => {
+                    if let Some(cf) = col_families.get_mut(cf) {
+                        let keys: Vec<_> = cf
+                            .range::<[u8], _>((
+                                Bound::Included(from.as_slice()),
+                                Bound::Excluded(to.as_slice()),
+                            ))
+                            .map(|(k, _)| k.clone())
+                            .collect();
+                        for key in keys {
+                            cf.remove(&key);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Iterates over a column family of a `MemBackend`. Each entry is looked up when it is requested
+/// so the lock is not held while iterating, which allows the index to be written in between.
+struct MemIter<'a> {
+    backend: &'a MemBackend,
+    cf: String,
+    /// Bound of the next key to return, which is the upper bound when iterating in reverse.
+    next: Bound<Box<[u8]>>,
+    reverse: bool,
+}
+
+impl Iterator for MemIter<'_> {
+    type Item = (Box<[u8]>, Box<[u8]>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let col_families = self.backend.col_families.read();
+        let cf = col_families.get(&self.cf)?;
+        let bound = match &self.next {
+            Bound::Included(key) => Bound::Included(key.as_ref()),
+            Bound::Excluded(key) => Bound::Excluded(key.as_ref()),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        let (key, value) = if self.reverse {
+            cf.range::<[u8], _>((Bound::Unbounded, bound)).next_back()?
+        } else {
+            cf.range::<[u8], _>((bound, Bound::Unbounded)).next()?
+        };
+        self.next = Bound::Excluded(key.clone());
+        Some((key.clone(), value.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mem_backend_iter() {
+        let backend = MemBackend::default();
+        let mut batch = Batch::default();
+        for i in 0u8..10 {
+            batch.put("cf", [i], [i * 2]);
+        }
+        backend.write(batch).unwrap();
+
+        let keys = |mode| {
+            backend
+                .iter("cf", mode)
+                .map(|(k, _)| k[0])
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(keys(IterMode::Start), (0..10).collect::<Vec<_>>());
+        assert_eq!(keys(IterMode::End), (0..10).rev().collect::<Vec<_>>());
+        assert_eq!(keys(IterMode::From(&[7])), vec![7, 8, 9]);
+        assert_eq!(backend.iter("missing", IterMode::Start).count(), 0);
+
+        // Entries can be deleted while iterating
+        for (key, value) in backend.iter("cf", IterMode::Start) {
+            assert_eq!(value[0], key[0] * 2);
+            let mut batch = Batch::default();
+            batch.delete("cf", key);
+            backend.write(batch).unwrap();
+        }
+        assert_eq!(backend.iter("cf", IterMode::Start).count(), 0);
+    }
+}
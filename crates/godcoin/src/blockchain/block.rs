@@ -1,12 +1,12 @@
 use crate::{
-    account::AccountId,
+    account::{AccountId, Permissions, MAX_PERM_KEYS},
     asset::Asset,
-    blockchain::Receipt,
-    crypto::{double_sha256, Digest, DoubleSha256, KeyPair, SigPair},
+    blockchain::{BlockErr, Receipt},
+    crypto::{double_sha256, Digest, DoubleSha256, KeyPair, PublicKey, SigPair},
     serializer::*,
-    tx::TxVariant,
+    tx::{TxId, TxVariant},
 };
-use std::{collections::BTreeSet, io::Cursor, ops::Deref, sync::Arc};
+use std::{collections::BTreeSet, io::Cursor, ops::Deref, slice, sync::Arc};
 
 pub type BlockFilter = BTreeSet<AccountId>;
 
@@ -19,6 +19,8 @@ pub enum FilteredBlock {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Block {
     V0(BlockV0),
+    /// Signed by a set of minters rather than a single minter.
+    V1(BlockV1),
 }
 
 impl Block {
@@ -26,6 +28,7 @@ impl Block {
     pub fn header(&self) -> BlockHeader {
         match self {
             Block::V0(block) => BlockHeader::V0(block.header.clone()),
+            Block::V1(block) => BlockHeader::V1(block.header.clone()),
         }
     }
 
@@ -33,6 +36,7 @@ impl Block {
     pub fn height(&self) -> u64 {
         match self {
             Block::V0(block) => block.height,
+            Block::V1(block) => block.height,
         }
     }
 
@@ -40,6 +44,7 @@ impl Block {
     pub fn timestamp(&self) -> u64 {
         match self {
             Block::V0(block) => block.timestamp,
+            Block::V1(block) => block.timestamp,
         }
     }
 
@@ -47,6 +52,7 @@ impl Block {
     pub fn rewards(&self) -> Asset {
         match self {
             Block::V0(block) => block.rewards,
+            Block::V1(block) => block.rewards,
         }
     }
 
@@ -54,47 +60,138 @@ impl Block {
     pub fn receipts(&self) -> &[Receipt] {
         match self {
             Block::V0(block) => &block.receipts,
+            Block::V1(block) => &block.receipts,
         }
     }
 
+    /// Returns the minter signature of the block. Version 1 blocks return their first signature.
     #[inline]
     pub fn signer(&self) -> Option<&SigPair> {
         match self {
             Block::V0(block) => block.signer.as_ref(),
+            Block::V1(block) => block.signers.first(),
         }
     }
 
+    #[inline]
+    pub fn signers(&self) -> &[SigPair] {
+        match self {
+            Block::V0(block) => match &block.signer {
+                Some(signer) => slice::from_ref(signer),
+                None => &[],
+            },
+            Block::V1(block) => &block.signers,
+        }
+    }
+
+    /// Signs the block header. Version 0 blocks replace their signature while version 1 blocks
+    /// append the signature so every minter in the set can sign.
     pub fn sign(&mut self, key_pair: &KeyPair) {
         let buf = self.calc_header_hash();
         match self {
             Block::V0(block) => {
                 block.signer = Some(key_pair.sign(buf.as_ref()));
             }
+            Block::V1(block) => {
+                block.signers.push(key_pair.sign(buf.as_ref()));
+            }
         }
     }
 
     pub fn verify_previous_hash(&self, prev_block: &Self) -> bool {
         let cur_prev_hash = match self {
             Block::V0(block) => &block.previous_hash,
+            Block::V1(block) => &block.previous_hash,
         };
         cur_prev_hash == &prev_block.calc_header_hash()
     }
 
     pub fn verify_receipt_root(&self) -> bool {
-        match self {
-            Block::V0(block) => {
-                let digest = calc_receipt_root(&block.receipts);
-                block.receipt_root == digest
-            }
+        let (receipts, receipt_root) = match self {
+            Block::V0(block) => (&block.receipts, &block.receipt_root),
+            Block::V1(block) => (&block.receipts, &block.receipt_root),
+        };
+        receipt_root == &calc_receipt_root(receipts)
+    }
+
+    /// Creates a proof that the transaction at `tx_index` is included in the block. Returns `None`
+    /// when the index is out of bounds.
+    pub fn inclusion_proof(&self, tx_index: usize) -> Option<InclusionProof> {
+        let receipts = self.receipts();
+        let receipt = receipts.get(tx_index)?.clone();
+        let mut preceding = Vec::with_capacity(4096);
+        for r in &receipts[..tx_index] {
+            r.serialize(&mut preceding);
+        }
+        let mut following = Vec::with_capacity(4096);
+        for r in &receipts[tx_index + 1..] {
+            r.serialize(&mut following);
         }
+        Some(InclusionProof {
+            receipt,
+            preceding,
+            following,
+        })
     }
 
     pub fn calc_header_hash(&self) -> Digest {
         match self {
             Block::V0(block) => block.calc_header_hash(),
+            Block::V1(block) => block.calc_header_hash(),
         }
     }
 
+    /// Verifies the block height, receipt root, previous hash, and that the block is signed by the
+    /// `minter` against the `prev_block`. Transactions are not executed.
+    pub fn verify_structure(&self, prev_block: &Self, minter: &PublicKey) -> Result<(), BlockErr> {
+        let minters = Permissions {
+            threshold: 1,
+            keys: vec![minter.clone()],
+        };
+        self.verify_structure_with_minters(prev_block, &minters)
+    }
+
+    /// Same as `verify_structure` except the block must be signed by enough `minters` to reach
+    /// their threshold. The chain itself only verifies blocks against the single minter key of
+    /// the owner transaction, which cannot carry a minter set.
+    pub fn verify_structure_with_minters(
+        &self,
+        prev_block: &Self,
+        minters: &Permissions,
+    ) -> Result<(), BlockErr> {
+        if prev_block.height() + 1 != self.height() {
+            return Err(BlockErr::InvalidBlockHeight);
+        } else if !self.verify_receipt_root() {
+            return Err(BlockErr::InvalidReceiptRoot);
+        } else if !self.verify_previous_hash(prev_block) {
+            return Err(BlockErr::InvalidPrevHash);
+        }
+
+        self.verify_signers(minters)
+    }
+
+    /// Verifies every signature is from a distinct key in the `minters` set and that enough
+    /// minters signed the block to reach the threshold.
+    pub fn verify_signers(&self, minters: &Permissions) -> Result<(), BlockErr> {
+        let signers = self.signers();
+        if signers.is_empty() {
+            return Err(BlockErr::InvalidSignature);
+        }
+        for (i, pair) in signers.iter().enumerate() {
+            // The permissions threshold counts each signature, so a minter must not be able to
+            // sign more than once.
+            if !minters.keys.contains(&pair.pub_key)
+                || signers[..i].iter().any(|p| p.pub_key == pair.pub_key)
+            {
+                return Err(BlockErr::InvalidSignature);
+            }
+        }
+
+        minters
+            .verify(self.calc_header_hash().as_ref(), signers)
+            .map_err(|_| BlockErr::InvalidSignature)
+    }
+
     pub fn serialize(&self, buf: &mut Vec<u8>) {
         match self {
             Block::V0(block) => {
@@ -111,6 +208,22 @@ impl Block {
                     r.serialize(buf)
                 }
             }
+            Block::V1(block) => {
+                assert!(
+                    !block.signers.is_empty(),
+                    "block must be signed to serialize"
+                );
+                block.header.serialize(buf);
+                buf.push_varint(block.signers.len() as u64);
+                for signer in &block.signers {
+                    buf.push_sig_pair(signer);
+                }
+                buf.push_asset(block.rewards);
+                buf.push_u32(block.receipts.len() as u32);
+                for r in &block.receipts {
+                    r.serialize(buf)
+                }
+            }
         }
     }
 
@@ -120,12 +233,7 @@ impl Block {
             BlockHeader::V0(header) => {
                 let signer = Some(cur.take_sig_pair().ok()?);
                 let rewards = cur.take_asset().ok()?;
-
-                let len = cur.take_u32().ok()?;
-                let mut receipts = Vec::with_capacity(len as usize);
-                for _ in 0..len {
-                    receipts.push(Receipt::deserialize(cur)?);
-                }
+                let receipts = Self::deserialize_receipts(cur)?;
 
                 Some(Block::V0(BlockV0 {
                     header,
@@ -134,19 +242,92 @@ impl Block {
                     receipts,
                 }))
             }
+            BlockHeader::V1(header) => {
+                let signer_len = cur.take_varint().ok()?;
+                if signer_len == 0 || signer_len > u64::from(MAX_PERM_KEYS) {
+                    return None;
+                }
+                let mut signers = Vec::with_capacity(signer_len as usize);
+                for _ in 0..signer_len {
+                    signers.push(cur.take_sig_pair().ok()?);
+                }
+                let rewards = cur.take_asset().ok()?;
+                let receipts = Self::deserialize_receipts(cur)?;
+
+                Some(Block::V1(BlockV1 {
+                    header,
+                    signers,
+                    rewards,
+                    receipts,
+                }))
+            }
         }
     }
+
+    fn deserialize_receipts(cur: &mut Cursor<&[u8]>) -> Option<Vec<Receipt>> {
+        let len = cur.take_u32().ok()?;
+        let mut receipts = Vec::with_capacity(len as usize);
+        for _ in 0..len {
+            receipts.push(Receipt::deserialize(cur)?);
+        }
+        Some(receipts)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum BlockHeader {
     V0(BlockHeaderV0),
+    V1(BlockHeaderV1),
 }
 
 impl BlockHeader {
+    #[inline]
+    pub fn previous_hash(&self) -> &Digest {
+        match self {
+            BlockHeader::V0(header) => &header.previous_hash,
+            BlockHeader::V1(header) => &header.previous_hash,
+        }
+    }
+
+    #[inline]
+    pub fn height(&self) -> u64 {
+        match self {
+            BlockHeader::V0(header) => header.height,
+            BlockHeader::V1(header) => header.height,
+        }
+    }
+
+    #[inline]
+    pub fn timestamp(&self) -> u64 {
+        match self {
+            BlockHeader::V0(header) => header.timestamp,
+            BlockHeader::V1(header) => header.timestamp,
+        }
+    }
+
+    /// Returns the hash of the block receipts. An `InclusionProof` verifies a receipt is included in
+    /// the block against this hash without the block body.
+    #[inline]
+    pub fn receipt_root(&self) -> &Digest {
+        match self {
+            BlockHeader::V0(header) => &header.receipt_root,
+            BlockHeader::V1(header) => &header.receipt_root,
+        }
+    }
+
+    /// Calculates the hash of the header. This is the same hash the minter signs and does not
+    /// require the block body.
+    pub fn calc_hash(&self) -> Digest {
+        match self {
+            BlockHeader::V0(header) => header.calc_hash(),
+            BlockHeader::V1(header) => header.calc_hash(),
+        }
+    }
+
     pub fn serialize(&self, buf: &mut Vec<u8>) {
         match self {
             BlockHeader::V0(header) => header.serialize(buf),
+            BlockHeader::V1(header) => header.serialize(buf),
         }
     }
 
@@ -154,6 +335,7 @@ impl BlockHeader {
         let header_ver = cur.take_u16().ok()?;
         match header_ver {
             0x00 => Some(BlockHeader::V0(BlockHeaderV0::deserialize(cur)?)),
+            0x01 => Some(BlockHeader::V1(BlockHeaderV1::deserialize(cur)?)),
             _ => None,
         }
     }
@@ -168,6 +350,12 @@ pub struct BlockHeaderV0 {
 }
 
 impl BlockHeaderV0 {
+    pub fn calc_hash(&self) -> Digest {
+        let mut buf = Vec::with_capacity(1024);
+        self.serialize(&mut buf);
+        double_sha256(&buf)
+    }
+
     pub(self) fn serialize(&self, buf: &mut Vec<u8>) {
         // Header version (2 bytes)
         buf.push_u16(0x00);
@@ -194,6 +382,49 @@ impl BlockHeaderV0 {
     }
 }
 
+/// Holds the same fields as a version 0 header. The version is part of the header hash so a
+/// signature over one version is never valid for the other.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockHeaderV1 {
+    pub previous_hash: Digest,
+    pub height: u64,
+    pub timestamp: u64,
+    pub receipt_root: Digest,
+}
+
+impl BlockHeaderV1 {
+    pub fn calc_hash(&self) -> Digest {
+        let mut buf = Vec::with_capacity(1024);
+        self.serialize(&mut buf);
+        double_sha256(&buf)
+    }
+
+    pub(self) fn serialize(&self, buf: &mut Vec<u8>) {
+        // Header version (2 bytes)
+        buf.push_u16(0x01);
+
+        buf.push_digest(&self.previous_hash);
+        buf.push_u64(self.height);
+        buf.push_u64(self.timestamp);
+        buf.push_digest(&self.receipt_root);
+    }
+
+    pub(self) fn deserialize(cur: &mut Cursor<&[u8]>) -> Option<Self> {
+        // We expect the version to already be deserialized here
+
+        let previous_hash = cur.take_digest().ok()?;
+        let height = cur.take_u64().ok()?;
+        let timestamp = cur.take_u64().ok()?;
+        let receipt_root = cur.take_digest().ok()?;
+        Some(Self {
+            previous_hash,
+            height,
+            timestamp,
+            receipt_root,
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct BlockV0 {
     pub header: BlockHeaderV0,
@@ -207,12 +438,9 @@ impl BlockV0 {
         let previous_hash = self.calc_header_hash();
         let height = self.header.height + 1;
         let receipt_root = calc_receipt_root(&receipts);
-        let timestamp = crate::get_epoch_time();
-        let rewards = receipts
-            .iter()
-            .fold(Asset::default(), |acc, receipt| match &receipt.tx {
-                TxVariant::V0(tx) => acc.checked_add(tx.fee).unwrap(),
-            });
+        // Never stamp a child before its parent, even if the local clock went backwards
+        let timestamp = crate::get_epoch_time().max(self.header.timestamp);
+        let rewards = calc_rewards(&receipts);
         Block::V0(BlockV0 {
             header: BlockHeaderV0 {
                 previous_hash,
@@ -226,10 +454,9 @@ impl BlockV0 {
         })
     }
 
+    #[inline]
     pub fn calc_header_hash(&self) -> Digest {
-        let mut buf = Vec::with_capacity(1024);
-        self.header.serialize(&mut buf);
-        double_sha256(&buf)
+        self.header.calc_hash()
     }
 }
 
@@ -241,6 +468,49 @@ impl Deref for BlockV0 {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockV1 {
+    pub header: BlockHeaderV1,
+    pub signers: Vec<SigPair>,
+    pub rewards: Asset,
+    pub receipts: Vec<Receipt>,
+}
+
+impl BlockV1 {
+    pub fn new_child(&self, receipts: Vec<Receipt>) -> Block {
+        let previous_hash = self.calc_header_hash();
+        let height = self.header.height + 1;
+        let receipt_root = calc_receipt_root(&receipts);
+        // Never stamp a child before its parent, even if the local clock went backwards
+        let timestamp = crate::get_epoch_time().max(self.header.timestamp);
+        let rewards = calc_rewards(&receipts);
+        Block::V1(BlockV1 {
+            header: BlockHeaderV1 {
+                previous_hash,
+                height,
+                timestamp,
+                receipt_root,
+            },
+            signers: vec![],
+            rewards,
+            receipts,
+        })
+    }
+
+    #[inline]
+    pub fn calc_header_hash(&self) -> Digest {
+        self.header.calc_hash()
+    }
+}
+
+impl Deref for BlockV1 {
+    type Target = BlockHeaderV1;
+
+    fn deref(&self) -> &BlockHeaderV1 {
+        &self.header
+    }
+}
+
 pub fn calc_receipt_root(receipts: &[Receipt]) -> Digest {
     let mut hasher = DoubleSha256::new();
     let mut buf = Vec::with_capacity(4096);
@@ -252,10 +522,56 @@ pub fn calc_receipt_root(receipts: &[Receipt]) -> Digest {
     hasher.finalize()
 }
 
+/// Proves that a receipt is included in a block. The receipt root hashes every serialized receipt
+/// of the block in order, so the proof carries the serialized receipts surrounding the receipt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InclusionProof {
+    pub receipt: Receipt,
+    /// Serialized receipts that precede the receipt in the block.
+    pub preceding: Vec<u8>,
+    /// Serialized receipts that follow the receipt in the block.
+    pub following: Vec<u8>,
+}
+
+impl InclusionProof {
+    /// Verifies the proof is for the transaction `txid` and that it hashes to the receipt `root`
+    /// of a block header.
+    pub fn verify(&self, txid: &TxId, root: &Digest) -> bool {
+        if &self.receipt.tx.calc_txid() != txid {
+            return false;
+        }
+
+        // The preceding bytes must consist of whole receipts, otherwise the boundary of the
+        // proven receipt could be shifted into the middle of another receipt.
+        let mut cur = Cursor::<&[u8]>::new(&self.preceding);
+        while cur.position() < self.preceding.len() as u64 {
+            if Receipt::deserialize(&mut cur).is_none() {
+                return false;
+            }
+        }
+
+        let mut hasher = DoubleSha256::new();
+        let mut buf = Vec::with_capacity(4096);
+        self.receipt.serialize(&mut buf);
+        hasher.update(&self.preceding);
+        hasher.update(&buf);
+        hasher.update(&self.following);
+        &hasher.finalize() == root
+    }
+}
+
+fn calc_rewards(receipts: &[Receipt]) -> Asset {
+    receipts
+        .iter()
+        .fold(Asset::default(), |acc, receipt| match &receipt.tx {
+            TxVariant::V0(tx) => acc.checked_add(tx.fee).unwrap(),
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{asset::Asset, crypto::KeyPair, tx::*};
+    use crate::{asset::Asset, blockchain::LogEntry, crypto::KeyPair, tx::*};
 
     #[test]
     fn serialize_block_v0() {
@@ -299,6 +615,42 @@ mod tests {
         assert_eq!(block, dec);
     }
 
+    #[test]
+    fn serialize_header() {
+        let keys = KeyPair::gen();
+        let mut block = Block::V0(BlockV0 {
+            header: BlockHeaderV0 {
+                previous_hash: Digest::from_slice(&[0u8; 32]).unwrap(),
+                height: 123,
+                timestamp: 1532992800,
+                receipt_root: calc_receipt_root(&[]),
+            },
+            signer: None,
+            rewards: Asset::default(),
+            receipts: vec![],
+        });
+        block.sign(&keys);
+
+        let header = block.header();
+        assert_eq!(header.calc_hash(), block.calc_header_hash());
+        assert!(block.signer().unwrap().verify(header.calc_hash().as_ref()));
+        assert_eq!(
+            header.previous_hash(),
+            &Digest::from_slice(&[0u8; 32]).unwrap()
+        );
+        assert_eq!(header.height(), block.height());
+        assert_eq!(header.timestamp(), block.timestamp());
+        assert_eq!(header.receipt_root(), &calc_receipt_root(&[]));
+
+        let mut buf = Vec::new();
+        header.serialize(&mut buf);
+        let mut cur = Cursor::<&[u8]>::new(&buf);
+        let dec = BlockHeader::deserialize(&mut cur).unwrap();
+        assert_eq!(cur.position(), buf.len() as u64);
+        assert_eq!(header, dec);
+        assert_eq!(dec.calc_hash(), block.calc_header_hash());
+    }
+
     #[test]
     fn receipt_root() {
         let mut block = Block::V0(BlockV0 {
@@ -318,11 +670,63 @@ mod tests {
             Block::V0(block) => {
                 block.header.receipt_root = Digest::from_slice(&[0; 32]).unwrap();
             }
+            Block::V1(_) => unreachable!(),
         }
 
         assert!(!block.verify_receipt_root());
     }
 
+    #[test]
+    fn receipt_root_by_receipt_count() {
+        let receipts = (0..3)
+            .map(|nonce| Receipt {
+                tx: TxVariant::V0(TxVariantV0::TransferTx(TransferTx {
+                    base: Tx {
+                        nonce,
+                        expiry: 1234567890,
+                        fee: Asset::default(),
+                        signature_pairs: Vec::new(),
+                    },
+                    from: 10,
+                    call_fn: 0,
+                    args: vec![],
+                    amount: Asset::default(),
+                    memo: vec![],
+                })),
+                log: vec![],
+            })
+            .collect::<Vec<_>>();
+
+        let mut roots = Vec::new();
+        for count in 0..=receipts.len() {
+            let receipts = receipts[0..count].to_vec();
+            let receipt_root = calc_receipt_root(&receipts);
+            assert_eq!(receipt_root, calc_receipt_root(&receipts));
+            assert!(!roots.contains(&receipt_root));
+            roots.push(receipt_root.clone());
+
+            let block = Block::V0(BlockV0 {
+                header: BlockHeaderV0 {
+                    previous_hash: Digest::from_slice(&[0; 32]).unwrap(),
+                    height: 0,
+                    timestamp: 0,
+                    receipt_root,
+                },
+                signer: None,
+                rewards: Asset::default(),
+                receipts,
+            });
+            assert!(block.verify_receipt_root());
+        }
+
+        // An empty block has the root of the hash of no data
+        assert_eq!(roots[0], double_sha256(&[]));
+
+        // The order of the receipts is part of the root
+        let reversed = receipts.iter().rev().cloned().collect::<Vec<_>>();
+        assert_ne!(calc_receipt_root(&reversed), roots[3]);
+    }
+
     #[test]
     fn previous_hash() {
         let block_0 = Block::V0(BlockV0 {
@@ -364,4 +768,260 @@ mod tests {
         assert!(block_1.verify_previous_hash(&block_0));
         assert!(!block_1_invalid.verify_previous_hash(&block_0));
     }
+
+    #[test]
+    fn verify_structure() {
+        let minter = KeyPair::gen();
+        let mut block_0 = Block::V0(BlockV0 {
+            header: BlockHeaderV0 {
+                previous_hash: Digest::from_slice(&[0; 32]).unwrap(),
+                height: 0,
+                timestamp: 0,
+                receipt_root: calc_receipt_root(&[]),
+            },
+            signer: None,
+            rewards: Asset::default(),
+            receipts: vec![],
+        });
+        block_0.sign(&minter);
+
+        let block_1 = match &block_0 {
+            Block::V0(block) => block.new_child(vec![]),
+            Block::V1(block) => block.new_child(vec![]),
+        };
+
+        let modify_and_sign = |f: &dyn Fn(&mut BlockV0), key: &KeyPair| {
+            let mut block = block_1.clone();
+            match &mut block {
+                Block::V0(block) => f(block),
+                Block::V1(_) => unreachable!(),
+            }
+            block.sign(key);
+            block
+        };
+
+        let block = modify_and_sign(&|_| {}, &minter);
+        assert_eq!(block.verify_structure(&block_0, &minter.0), Ok(()));
+
+        let block = modify_and_sign(&|b| b.header.height = 2, &minter);
+        assert_eq!(
+            block.verify_structure(&block_0, &minter.0),
+            Err(BlockErr::InvalidBlockHeight)
+        );
+
+        let block = modify_and_sign(
+            &|b| b.header.receipt_root = Digest::from_slice(&[0; 32]).unwrap(),
+            &minter,
+        );
+        assert_eq!(
+            block.verify_structure(&block_0, &minter.0),
+            Err(BlockErr::InvalidReceiptRoot)
+        );
+
+        let block = modify_and_sign(
+            &|b| b.header.previous_hash = Digest::from_slice(&[0; 32]).unwrap(),
+            &minter,
+        );
+        assert_eq!(
+            block.verify_structure(&block_0, &minter.0),
+            Err(BlockErr::InvalidPrevHash)
+        );
+
+        // Missing signature
+        assert_eq!(
+            block_1.verify_structure(&block_0, &minter.0),
+            Err(BlockErr::InvalidSignature)
+        );
+
+        // Signed by a key that isn't the minter
+        let block = modify_and_sign(&|_| {}, &KeyPair::gen());
+        assert_eq!(
+            block.verify_structure(&block_0, &minter.0),
+            Err(BlockErr::InvalidSignature)
+        );
+
+        // Header modified after signing
+        let mut block = modify_and_sign(&|_| {}, &minter);
+        match &mut block {
+            Block::V0(block) => block.header.timestamp += 1,
+            Block::V1(_) => unreachable!(),
+        }
+        assert_eq!(
+            block.verify_structure(&block_0, &minter.0),
+            Err(BlockErr::InvalidSignature)
+        );
+    }
+
+    fn create_block_v1(height: u64, previous_hash: Digest) -> Block {
+        Block::V1(BlockV1 {
+            header: BlockHeaderV1 {
+                previous_hash,
+                height,
+                timestamp: 1532992800,
+                receipt_root: calc_receipt_root(&[]),
+            },
+            signers: vec![],
+            rewards: Asset::default(),
+            receipts: vec![],
+        })
+    }
+
+    #[test]
+    fn serialize_block_v1() {
+        let mut block = create_block_v1(123, Digest::from_slice(&[0; 32]).unwrap());
+        let keys = (0..3).map(|_| KeyPair::gen()).collect::<Vec<_>>();
+        for key in &keys {
+            block.sign(key);
+        }
+        assert_eq!(block.signers().len(), 3);
+        assert_eq!(block.signer(), Some(&block.signers()[0]));
+
+        let mut buf = Vec::new();
+        block.serialize(&mut buf);
+        let mut cur = Cursor::<&[u8]>::new(&buf);
+        let dec = Block::deserialize(&mut cur).unwrap();
+        assert_eq!(cur.position(), buf.len() as u64);
+        assert_eq!(block, dec);
+
+        let header = block.header();
+        let mut buf = Vec::new();
+        header.serialize(&mut buf);
+        let mut cur = Cursor::<&[u8]>::new(&buf);
+        assert_eq!(BlockHeader::deserialize(&mut cur).unwrap(), header);
+        assert_eq!(header.calc_hash(), block.calc_header_hash());
+
+        // The header version is part of the signed hash
+        let header_v0 = BlockHeader::V0(BlockHeaderV0 {
+            previous_hash: Digest::from_slice(&[0; 32]).unwrap(),
+            height: 123,
+            timestamp: 1532992800,
+            receipt_root: calc_receipt_root(&[]),
+        });
+        assert_ne!(header_v0.calc_hash(), header.calc_hash());
+    }
+
+    #[test]
+    fn verify_minter_set() {
+        let keys = (0..3).map(|_| KeyPair::gen()).collect::<Vec<_>>();
+        let minters = Permissions {
+            threshold: 2,
+            keys: keys.iter().map(|k| k.0.clone()).collect(),
+        };
+
+        let mut block_0 = create_block_v1(0, Digest::from_slice(&[0; 32]).unwrap());
+        block_0.sign(&keys[0]);
+        block_0.sign(&keys[1]);
+        assert_eq!(block_0.verify_signers(&minters), Ok(()));
+
+        let create_child = |signers: &[&KeyPair]| {
+            let mut block = create_block_v1(1, block_0.calc_header_hash());
+            for key in signers {
+                block.sign(key);
+            }
+            block
+        };
+
+        let block = create_child(&[&keys[0], &keys[2]]);
+        assert_eq!(
+            block.verify_structure_with_minters(&block_0, &minters),
+            Ok(())
+        );
+
+        let block = create_child(&[&keys[1]]);
+        assert_eq!(
+            block.verify_structure_with_minters(&block_0, &minters),
+            Err(BlockErr::InvalidSignature)
+        );
+
+        // A minter signing twice does not reach the threshold
+        let block = create_child(&[&keys[1], &keys[1]]);
+        assert_eq!(
+            block.verify_structure_with_minters(&block_0, &minters),
+            Err(BlockErr::InvalidSignature)
+        );
+
+        // Signatures from keys outside of the minter set are rejected
+        let block = create_child(&[&keys[0], &keys[1], &KeyPair::gen()]);
+        assert_eq!(
+            block.verify_structure_with_minters(&block_0, &minters),
+            Err(BlockErr::InvalidSignature)
+        );
+
+        // Signatures must be over the block header
+        let mut block = create_child(&[&keys[0], &keys[1]]);
+        match &mut block {
+            Block::V1(block) => block.header.timestamp += 1,
+            Block::V0(_) => unreachable!(),
+        }
+        assert_eq!(
+            block.verify_structure_with_minters(&block_0, &minters),
+            Err(BlockErr::InvalidSignature)
+        );
+
+        let block = create_child(&[]);
+        assert_eq!(
+            block.verify_structure_with_minters(&block_0, &minters),
+            Err(BlockErr::InvalidSignature)
+        );
+
+        // A single minter verifies as a one of one minter set
+        let block = create_child(&[&keys[0]]);
+        assert_eq!(block.verify_structure(&block_0, &keys[0].0), Ok(()));
+        assert_eq!(
+            block.verify_structure(&block_0, &keys[1].0),
+            Err(BlockErr::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn receipt_inclusion_proofs() {
+        let receipts = (0..4)
+            .map(|i| Receipt {
+                tx: TxVariant::V0(TxVariantV0::TransferTx(TransferTx {
+                    base: Tx {
+                        nonce: i,
+                        expiry: 1234567890,
+                        fee: Asset::default(),
+                        signature_pairs: Vec::new(),
+                    },
+                    from: 10,
+                    call_fn: 0,
+                    args: vec![],
+                    amount: Asset::new(i64::from(i)),
+                    memo: vec![],
+                })),
+                log: vec![LogEntry::Transfer(11, Asset::new(i64::from(i)))],
+            })
+            .collect::<Vec<_>>();
+        let block = Block::V0(BlockV0 {
+            header: BlockHeaderV0 {
+                previous_hash: Digest::from_slice(&[0u8; 32]).unwrap(),
+                height: 1,
+                timestamp: 1532992800,
+                receipt_root: calc_receipt_root(&receipts),
+            },
+            signer: None,
+            rewards: Asset::default(),
+            receipts,
+        });
+        let header = block.header();
+        let root = header.receipt_root();
+
+        for (i, r) in block.receipts().iter().enumerate() {
+            let proof = block.inclusion_proof(i).unwrap();
+            assert_eq!(proof.receipt, *r);
+            assert!(proof.verify(&r.tx.calc_txid(), root));
+        }
+        assert!(block.inclusion_proof(4).is_none());
+
+        let proof = block.inclusion_proof(1).unwrap();
+        let other_txid = block.receipts()[2].tx.calc_txid();
+        assert!(!proof.verify(&other_txid, root));
+        let txid = block.receipts()[1].tx.calc_txid();
+        assert!(!proof.verify(&txid, &Digest::from_slice(&[0u8; 32]).unwrap()));
+
+        let mut truncated = proof.clone();
+        truncated.preceding.pop();
+        assert!(!truncated.verify(&txid, root));
+    }
 }
@@ -4,16 +4,48 @@ use crate::{
 };
 use std::{
     convert::TryFrom,
+    error::Error,
+    fmt::{self, Display},
     io::{self, Cursor},
 };
 
+/// A `ChainParams` value the chain cannot operate with.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ChainParamsErr {
+    ZeroNetworkFeeWindow,
+}
+
+impl Error for ChainParamsErr {}
+
+impl Display for ChainParamsErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let desc = match self {
+            ChainParamsErr::ZeroNetworkFeeWindow => "network fee average window must be at least 1",
+        };
+        write!(f, "{}", desc)
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum BlockErr {
     InvalidBlockHeight,
     InvalidReceiptRoot,
     InvalidSignature,
     InvalidPrevHash,
-    Tx(TxErr),
+    /// The block timestamp is earlier than the timestamp of the previous block.
+    InvalidTimestamp,
+    /// The block timestamp is further ahead of the local time than the allowed clock skew.
+    TimestampTooFarAhead,
+    /// The sum of the serialized transaction sizes exceeds the maximum block byte size.
+    BlockTooLarge,
+    /// The block at height 0 is not a genesis block with a zeroed previous hash, a valid minter
+    /// signature, and the owner transaction.
+    InvalidGenesis,
+    /// A transaction failed to execute where `index` is the position of the receipt in the block.
+    Tx {
+        index: usize,
+        err: TxErr,
+    },
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -30,6 +62,9 @@ pub enum TxErr {
     TxProhibited,
     TxExpired,
     TxDupe,
+    /// The nonce of a transfer is not greater than the nonce of the last transfer sent from the
+    /// same account.
+    NonceTooLow,
 }
 
 impl TxErr {
@@ -51,6 +86,7 @@ impl TxErr {
             TxErr::TxProhibited => buf.push(0x09),
             TxErr::TxExpired => buf.push(0x0A),
             TxErr::TxDupe => buf.push(0x0B),
+            TxErr::NonceTooLow => buf.push(0x0C),
         }
     }
 
@@ -78,6 +114,7 @@ impl TxErr {
             0x09 => TxErr::TxProhibited,
             0x0A => TxErr::TxExpired,
             0x0B => TxErr::TxDupe,
+            0x0C => TxErr::NonceTooLow,
             _ => {
                 return Err(io::Error::new(
                     io::ErrorKind::InvalidData,
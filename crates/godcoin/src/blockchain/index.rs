@@ -1,54 +1,122 @@
-use rocksdb::{ColumnFamilyDescriptor, DBRecoveryMode, IteratorMode, Options, DB};
 use std::{
     collections::{hash_map::Entry, HashMap},
     convert::TryInto,
+    error::Error,
+    fmt::{self, Display},
     io::Cursor,
     mem,
+    ops::RangeInclusive,
     path::Path,
     sync::Arc,
+    thread,
 };
 
+use super::backend::{
+    Batch, BatchOp, IndexBackend, IterMode, MemBackend, RocksBackend, CF_DEFAULT,
+};
 use crate::{
     account::{Account, AccountId},
     asset::Asset,
+    constants::MAX_ROLLBACK_DEPTH,
+    script::{Script, ScriptHash},
     serializer::*,
     tx::{TxId, TxVariant, TxVariantV0},
 };
 
+pub use rocksdb::DBRecoveryMode;
+
 const CF_BLOCK_BYTE_POS: &str = "block_byte_pos";
 const CF_ACCOUNT: &str = "account";
 const CF_TX_EXPIRY: &str = "tx_expiry";
+/// Secondary index of `CF_TX_EXPIRY` where the keys are the expiry (big endian) followed by the
+/// transaction id. This allows purging expired transactions without scanning the full history.
+const CF_TX_EXPIRY_TIME: &str = "tx_expiry_time";
+/// Maps a transaction id to the height (big endian) of the block that includes it followed by the
+/// index (big endian) of its receipt in the block.
+const CF_TX_LOCATION: &str = "tx_location";
+/// Maps a block height (big endian) to the token supply after that block has been indexed.
+const CF_SUPPLY_CHECKPOINT: &str = "supply_checkpoint";
+/// Maps an account id (big endian) to the nonce (big endian) of the last transfer sent from the
+/// account.
+const CF_ACCOUNT_NONCE: &str = "account_nonce";
+/// Maps a block height (big endian) to the previous values of every key written when the block was
+/// indexed, which allows the block to be reverted.
+const CF_BLOCK_UNDO: &str = "block_undo";
+/// Maps a script hash to the byte code of a script used by an indexed account.
+const CF_SCRIPT: &str = "script";
+
+const COL_FAMILIES: [&str; 9] = [
+    CF_BLOCK_BYTE_POS,
+    CF_ACCOUNT,
+    CF_TX_EXPIRY,
+    CF_TX_EXPIRY_TIME,
+    CF_TX_LOCATION,
+    CF_SUPPLY_CHECKPOINT,
+    CF_ACCOUNT_NONCE,
+    CF_BLOCK_UNDO,
+    CF_SCRIPT,
+];
 
 const KEY_NET_OWNER: &[u8] = b"network_owner";
 const KEY_CHAIN_HEIGHT: &[u8] = b"chain_height";
 const KEY_TOKEN_SUPPLY: &[u8] = b"token_supply";
 const KEY_INDEX_STATUS: &[u8] = b"index_status";
+const KEY_NEXT_ACCOUNT_ID: &[u8] = b"next_account_id";
 
 const TX_EXPIRY_ADJUSTMENT: u64 = 30;
 
+/// Tuning options for the index database. The default options favor consistency over throughput.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct IndexerOptions {
+    /// How the write-ahead log is recovered when the database is opened after a crash.
+    pub wal_recovery_mode: DBRecoveryMode,
+    /// Size in bytes of the LRU block cache of each column family. The RocksDB default cache is
+    /// used when `None`.
+    pub block_cache_size: Option<usize>,
+    /// Whether data is compressed with the RocksDB default compression.
+    pub compression: bool,
+}
+
+impl Default for IndexerOptions {
+    fn default() -> Self {
+        IndexerOptions {
+            wal_recovery_mode: DBRecoveryMode::AbsoluteConsistency,
+            block_cache_size: None,
+            compression: true,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Indexer {
-    db: DB,
+    db: Box<dyn IndexBackend>,
 }
 
 impl Indexer {
+    #[inline]
     pub fn new(path: &Path) -> Indexer {
-        let mut db_opts = Options::default();
-        db_opts.create_missing_column_families(true);
-        db_opts.create_if_missing(true);
-        db_opts.set_wal_recovery_mode(DBRecoveryMode::AbsoluteConsistency);
-
-        let col_families = vec![
-            ColumnFamilyDescriptor::new(CF_BLOCK_BYTE_POS, Options::default()),
-            ColumnFamilyDescriptor::new(CF_ACCOUNT, Options::default()),
-            ColumnFamilyDescriptor::new(CF_TX_EXPIRY, Options::default()),
-        ];
-        let db = DB::open_cf_descriptors(&db_opts, path, col_families).unwrap();
+        Self::with_options(path, IndexerOptions::default())
+    }
+
+    pub fn with_options(path: &Path, opts: IndexerOptions) -> Indexer {
+        let db = RocksBackend::open(path, opts, &COL_FAMILIES);
+        Self::with_backend(Box::new(db))
+    }
+
+    /// Creates an indexer that keeps the index in memory. The index is lost when the indexer is
+    /// dropped.
+    #[inline]
+    pub fn new_in_memory() -> Indexer {
+        Self::with_backend(Box::new(MemBackend::default()))
+    }
+
+    #[inline]
+    pub fn with_backend(db: Box<dyn IndexBackend>) -> Indexer {
         Indexer { db }
     }
 
     pub fn index_status(&self) -> IndexStatus {
-        let buf_status = self.db.get_pinned(KEY_INDEX_STATUS).unwrap();
+        let buf_status = self.db.get(CF_DEFAULT, KEY_INDEX_STATUS).unwrap();
         match buf_status {
             Some(buf_status) => match buf_status[0] {
                 0 => IndexStatus::None,
@@ -66,82 +134,310 @@ impl Indexer {
             IndexStatus::Partial => 1,
             IndexStatus::Complete => 2,
         };
-        self.db.put(KEY_INDEX_STATUS, vec![status_byte]).unwrap();
+        let mut batch = Batch::default();
+        batch.put(CF_DEFAULT, KEY_INDEX_STATUS, [status_byte]);
+        self.db.write(batch).unwrap();
     }
 
-    pub fn get_block_byte_pos(&self, height: u64) -> Option<u64> {
-        let cf = self.db.cf_handle(CF_BLOCK_BYTE_POS).unwrap();
-        let buf = self.db.get_pinned_cf(cf, height.to_be_bytes()).unwrap()?;
+    /// Removes all indexed data except for the index status. This is used to start a reindex from
+    /// a clean slate since a partially applied index cannot be resumed in place.
+    pub fn clear(&self) {
+        let mut batch = Batch::default();
+        for cf in COL_FAMILIES.iter().copied() {
+            // Only the bounds of each column family are read so clearing a large index does not
+            // load every key into memory.
+            let first = self.db.iter(cf, IterMode::Start).next();
+            let last = self.db.iter(cf, IterMode::End).next();
+            if let (Some((first, _)), Some((last, _))) = (first, last) {
+                batch.delete_range(cf, &first, &last);
+                batch.delete(cf, last);
+            }
+        }
+        for key in &[
+            KEY_NET_OWNER,
+            KEY_CHAIN_HEIGHT,
+            KEY_TOKEN_SUPPLY,
+            KEY_NEXT_ACCOUNT_ID,
+        ] {
+            batch.delete(CF_DEFAULT, key);
+        }
+        self.db.write(batch).unwrap();
+    }
+
+    pub fn get_block_byte_pos(&self, height: u64) -> Result<Option<u64>, IndexerError> {
+        let buf = match self.db.get(CF_BLOCK_BYTE_POS, &height.to_be_bytes())? {
+            Some(buf) => buf,
+            None => return Ok(None),
+        };
 
-        Some(u64::from_be_bytes(buf.as_ref().try_into().unwrap()))
+        Ok(Some(u64::from_be_bytes(buf.as_slice().try_into().unwrap())))
     }
 
-    pub fn get_chain_height(&self) -> u64 {
-        match self.db.get_pinned(KEY_CHAIN_HEIGHT).unwrap() {
-            Some(buf) => u64::from_be_bytes(buf.as_ref().try_into().unwrap()),
+    pub fn get_chain_height(&self) -> Result<u64, IndexerError> {
+        Ok(match self.db.get(CF_DEFAULT, KEY_CHAIN_HEIGHT)? {
+            Some(buf) => u64::from_be_bytes(buf.as_slice().try_into().unwrap()),
             None => 0,
-        }
+        })
     }
 
-    pub fn get_owner(&self) -> Option<TxVariant> {
-        let tx_buf = self.db.get_pinned(KEY_NET_OWNER).unwrap()?;
+    pub fn get_owner(&self) -> Result<Option<TxVariant>, IndexerError> {
+        let tx_buf = match self.db.get(CF_DEFAULT, KEY_NET_OWNER)? {
+            Some(tx_buf) => tx_buf,
+            None => return Ok(None),
+        };
         let cur = &mut Cursor::<&[u8]>::new(&tx_buf);
         let tx = TxVariant::deserialize(cur).expect("Failed to deserialize owner tx");
         match tx {
             TxVariant::V0(ref var) => match var {
-                TxVariantV0::OwnerTx(_) => Some(tx),
+                TxVariantV0::OwnerTx(_) => Ok(Some(tx)),
                 _ => panic!("expected owner transaction"),
             },
         }
     }
 
-    pub fn get_account(&self, id: AccountId) -> Option<Account> {
-        let cf = self.db.cf_handle(CF_ACCOUNT).unwrap();
-        let buf = self.db.get_pinned_cf(cf, id.to_be_bytes()).unwrap()?;
+    pub fn get_account(&self, id: AccountId) -> Result<Option<Account>, IndexerError> {
+        let buf = match self.db.get(CF_ACCOUNT, &id.to_be_bytes())? {
+            Some(buf) => buf,
+            None => return Ok(None),
+        };
         let cur = &mut Cursor::<&[u8]>::new(&buf);
         let account = Account::deserialize(cur).expect("failed to deserialize indexed account");
-        Some(account)
+        Ok(Some(account))
+    }
+
+    /// Returns the account of each id in the same order as `ids`. Every account is read from the
+    /// same state of the index.
+    pub fn get_accounts(&self, ids: &[AccountId]) -> Result<Vec<Option<Account>>, IndexerError> {
+        let keys = ids.iter().map(|id| id.to_be_bytes()).collect::<Vec<_>>();
+        let keys = keys.iter().map(|key| key.as_ref()).collect::<Vec<_>>();
+        let accounts = self
+            .db
+            .multi_get(CF_ACCOUNT, &keys)?
+            .into_iter()
+            .map(|buf| {
+                let buf = buf?;
+                let cur = &mut Cursor::<&[u8]>::new(&buf);
+                Some(Account::deserialize(cur).expect("failed to deserialize indexed account"))
+            })
+            .collect();
+        Ok(accounts)
+    }
+
+    /// Returns up to `limit` accounts ordered by id starting at the `start` id inclusively.
+    pub fn get_account_range(
+        &self,
+        start: AccountId,
+        limit: usize,
+    ) -> Result<Vec<(AccountId, Account)>, IndexerError> {
+        let start = start.to_be_bytes();
+        let iter = self.db.iter(CF_ACCOUNT, IterMode::From(&start));
+
+        let mut accounts = Vec::with_capacity(limit.min(1024));
+        for (key, value) in iter.take(limit) {
+            let id = AccountId::from_be_bytes(key.as_ref().try_into().unwrap());
+            let cur = &mut Cursor::<&[u8]>::new(&value);
+            let account = Account::deserialize(cur).expect("failed to deserialize indexed account");
+            accounts.push((id, account));
+        }
+        Ok(accounts)
+    }
+
+    /// Returns the smallest account id that is not used by an indexed account.
+    pub fn get_next_account_id(&self) -> Result<AccountId, IndexerError> {
+        match self.db.get(CF_DEFAULT, KEY_NEXT_ACCOUNT_ID)? {
+            Some(buf) => Ok(AccountId::from_be_bytes(buf.as_slice().try_into().unwrap())),
+            // Indexes created before the counter was tracked derive it from the accounts
+            None => self.find_unused_account_id(0, &HashMap::new()),
+        }
+    }
+
+    /// Returns the smallest account id starting at `start` that is neither indexed nor in
+    /// `pending`.
+    fn find_unused_account_id(
+        &self,
+        start: AccountId,
+        pending: &HashMap<AccountId, Account>,
+    ) -> Result<AccountId, IndexerError> {
+        let mut indexed = self
+            .db
+            .iter(CF_ACCOUNT, IterMode::From(&start.to_be_bytes()))
+            .map(|(key, _)| AccountId::from_be_bytes(key.as_ref().try_into().unwrap()))
+            .peekable();
+        let mut id = start;
+        loop {
+            // Indexed ids are sorted, so the id is in use when it is the next indexed id
+            let is_indexed = indexed.peek() == Some(&id);
+            if is_indexed {
+                indexed.next();
+            }
+            let is_used = is_indexed || pending.contains_key(&id);
+            if !is_used || id == AccountId::MAX {
+                return Ok(id);
+            }
+            id += 1;
+        }
     }
 
     pub fn account_exists(&self, id: AccountId) -> bool {
-        let cf = self.db.cf_handle(CF_ACCOUNT).unwrap();
-        let acc_buf_opt = self.db.get_pinned_cf(cf, id.to_be_bytes()).unwrap();
+        let acc_buf_opt = self.db.get(CF_ACCOUNT, &id.to_be_bytes()).unwrap();
         acc_buf_opt.is_some()
     }
 
-    pub fn get_token_supply(&self) -> Asset {
-        let supply_buf = self.db.get_pinned(KEY_TOKEN_SUPPLY).unwrap();
-        match supply_buf {
+    pub fn get_token_supply(&self) -> Result<Asset, IndexerError> {
+        let supply_buf = self.db.get(CF_DEFAULT, KEY_TOKEN_SUPPLY)?;
+        Ok(match supply_buf {
             Some(supply_buf) => {
                 let cur = &mut Cursor::<&[u8]>::new(&supply_buf);
                 cur.take_asset().unwrap()
             }
             None => Asset::default(),
+        })
+    }
+
+    /// Returns the block height and receipt index of an included transaction.
+    pub fn get_tx_location(&self, id: &TxId) -> Result<Option<(u64, u32)>, IndexerError> {
+        let buf = match self.db.get(CF_TX_LOCATION, id.as_ref())? {
+            Some(buf) => buf,
+            None => return Ok(None),
+        };
+        let height = u64::from_be_bytes(buf[0..8].try_into().unwrap());
+        let index = u32::from_be_bytes(buf[8..12].try_into().unwrap());
+        Ok(Some((height, index)))
+    }
+
+    /// Returns the token supply recorded after the block at `height` was indexed, if a checkpoint
+    /// was written for that height.
+    pub fn get_supply_checkpoint(&self, height: u64) -> Result<Option<Asset>, IndexerError> {
+        Ok(
+            match self.db.get(CF_SUPPLY_CHECKPOINT, &height.to_be_bytes())? {
+                Some(buf) => {
+                    let cur = &mut Cursor::<&[u8]>::new(&buf);
+                    Some(cur.take_asset().unwrap())
+                }
+                None => None,
+            },
+        )
+    }
+
+    /// Returns the nonce of the last indexed transfer sent from the account.
+    pub fn get_account_nonce(&self, id: AccountId) -> Result<Option<u32>, IndexerError> {
+        let buf = self.db.get(CF_ACCOUNT_NONCE, &id.to_be_bytes())?;
+        Ok(buf.map(|buf| u32::from_be_bytes(buf.as_slice().try_into().unwrap())))
+    }
+
+    /// Returns the script with the provided hash if it is or was used by an indexed account.
+    pub fn get_script(&self, hash: &ScriptHash) -> Result<Option<Script>, IndexerError> {
+        Ok(self.db.get(CF_SCRIPT, hash.as_ref())?.map(Script::new))
+    }
+
+    /// Returns whether the changes of the block at `height` can be reverted with `revert_block`.
+    pub fn has_undo_data(&self, height: u64) -> Result<bool, IndexerError> {
+        Ok(self.db.get(CF_BLOCK_UNDO, &height.to_be_bytes())?.is_some())
+    }
+
+    /// Restores every key written when the block at `height` was indexed to its previous value.
+    /// Blocks must be reverted in order starting from the chain head.
+    pub fn revert_block(&self, height: u64) -> Result<(), IndexerError> {
+        self.revert_blocks(height..=height, &[])
+    }
+
+    /// Reverts every block in `heights` starting from the highest in a single write, the highest
+    /// height must be the chain head. Nothing is reverted when any of the blocks has no undo
+    /// data. The expiry of each transaction in `removed_txs` is deleted so the transactions are
+    /// no longer considered duplicates.
+    pub fn revert_blocks(
+        &self,
+        heights: RangeInclusive<u64>,
+        removed_txs: &[(TxId, u64)],
+    ) -> Result<(), IndexerError> {
+        let mut batch = Batch::default();
+        for height in heights.rev() {
+            self.push_undo_ops(&mut batch, height)?;
+        }
+        for (id, expiry) in removed_txs {
+            let expiry = expiry.to_be_bytes();
+            batch.delete(CF_TX_EXPIRY, id);
+            batch.delete(CF_TX_EXPIRY_TIME, [&expiry, id.as_ref()].concat());
         }
+        self.db.write(batch)
+    }
+
+    fn push_undo_ops(&self, batch: &mut Batch, height: u64) -> Result<(), IndexerError> {
+        let height_key = height.to_be_bytes();
+        let buf = match self.db.get(CF_BLOCK_UNDO, &height_key)? {
+            Some(buf) => buf,
+            None => return Err(IndexerError::MissingUndoData(height)),
+        };
+
+        let mut entries = Vec::new();
+        let cur = &mut Cursor::<&[u8]>::new(&buf);
+        while (cur.position() as usize) < buf.len() {
+            const ERR_MSG: &str = "failed to deserialize undo data";
+            let cf = cur.take_bytes().expect(ERR_MSG);
+            let key = cur.take_bytes().expect(ERR_MSG);
+            let value = cur.take_option(|cur| cur.take_bytes()).expect(ERR_MSG);
+            entries.push((cf, key, value));
+        }
+
+        // Apply in reverse so a key written more than once ends with its oldest value
+        for (cf, key, value) in entries.into_iter().rev() {
+            let cf = [CF_DEFAULT]
+                .iter()
+                .chain(COL_FAMILIES.iter())
+                .find(|name| name.as_bytes() == cf.as_slice())
+                .expect("unknown column family in undo data");
+            match value {
+                Some(value) => batch.put(cf, key, value),
+                None => batch.delete(cf, key),
+            }
+        }
+        batch.delete(CF_BLOCK_UNDO, height_key);
+        Ok(())
+    }
+
+    /// Serializes the current value of every key the `batch` writes to.
+    fn build_undo_data(&self, batch: &Batch) -> Result<Vec<u8>, IndexerError> {
+        let mut buf = Vec::new();
+        for op in batch.ops() {
+            match op {
+                BatchOp::Put(cf, key, _) => {
+                    buf.push_bytes(cf.as_bytes());
+                    buf.push_bytes(key);
+                    buf.push_option(self.db.get(cf, key)?, |buf, value| buf.push_bytes(&value));
+                }
+                _ => panic!("undo data is only recorded for put operations"),
+            }
+        }
+        Ok(buf)
     }
 
     pub fn has_txid(&self, id: &TxId) -> bool {
-        let cf = self.db.cf_handle(CF_TX_EXPIRY).unwrap();
-        self.db.get_cf(cf, id).unwrap().is_some()
+        self.db.get(CF_TX_EXPIRY, id.as_ref()).unwrap().is_some()
     }
 
     pub fn insert_txid(&self, id: &TxId, expiry: u64) {
-        let cf = self.db.cf_handle(CF_TX_EXPIRY).unwrap();
-        self.db.put_cf(cf, id, expiry.to_be_bytes()).unwrap();
+        let expiry = expiry.to_be_bytes();
+
+        let mut batch = Batch::default();
+        batch.put(CF_TX_EXPIRY, id, expiry);
+        batch.put(CF_TX_EXPIRY_TIME, [&expiry, id.as_ref()].concat(), [0u8; 0]);
+        self.db.write(batch).unwrap();
     }
 
     pub fn purge_expired_txids(&self) {
-        let cf = self.db.cf_handle(CF_TX_EXPIRY).unwrap();
         // Pretend to be slightly in the past in case system time adjusts in the future.
         let current_time = crate::get_epoch_time() - TX_EXPIRY_ADJUSTMENT;
+        // Any key prefixed with an expiry lower than the current time sorts before the current time
+        let end_key = current_time.to_be_bytes();
 
-        let mut batch = rocksdb::WriteBatch::default();
-        for (key, value) in self.db.iterator_cf(cf, IteratorMode::Start) {
-            let expiry = u64::from_be_bytes(value.as_ref().try_into().unwrap());
-            if expiry < current_time {
-                batch.delete_cf(cf, key);
+        let mut batch = Batch::default();
+        for (key, _) in self.db.iter(CF_TX_EXPIRY_TIME, IterMode::Start) {
+            if key.as_ref() >= &end_key[..] {
+                break;
             }
+            batch.delete(CF_TX_EXPIRY, &key[end_key.len()..]);
         }
+        batch.delete_range(CF_TX_EXPIRY_TIME, &[0u8; 8], &end_key);
         self.db.write(batch).unwrap();
     }
 }
@@ -152,7 +448,12 @@ pub struct WriteBatch {
     chain_height: Option<u64>,
     owner: Option<TxVariant>,
     accounts: HashMap<AccountId, Account>,
+    scripts: HashMap<ScriptHash, Script>,
     token_supply: Option<Asset>,
+    tx_locations: Vec<(TxId, u64, u32)>,
+    supply_checkpoints: Vec<(u64, Asset)>,
+    account_nonces: HashMap<AccountId, u32>,
+    undo_height: Option<u64>,
 }
 
 impl WriteBatch {
@@ -163,55 +464,138 @@ impl WriteBatch {
             chain_height: None,
             owner: None,
             accounts: HashMap::with_capacity(64),
+            scripts: HashMap::new(),
             token_supply: None,
+            tx_locations: Vec::new(),
+            supply_checkpoints: Vec::new(),
+            account_nonces: HashMap::new(),
+            undo_height: None,
         }
     }
 
-    pub fn commit(self) {
-        let mut batch = rocksdb::WriteBatch::default();
+    pub fn commit(mut self) -> Result<(), IndexerError> {
+        let mut batch = Batch::default();
 
-        {
-            let cf = self.indexer.db.cf_handle(CF_BLOCK_BYTE_POS).unwrap();
-            for (height, pos) in self.block_byte_pos {
-                let height = height.to_be_bytes();
-                let pos = pos.to_be_bytes();
-                batch.put_cf(cf, &height, &pos);
-            }
+        for (height, pos) in mem::take(&mut self.block_byte_pos) {
+            batch.put(CF_BLOCK_BYTE_POS, height.to_be_bytes(), pos.to_be_bytes());
         }
 
-        if let Some(height) = self.chain_height {
-            batch.put(KEY_CHAIN_HEIGHT, height.to_be_bytes());
+        if let Some(height) = self.chain_height.take() {
+            batch.put(CF_DEFAULT, KEY_CHAIN_HEIGHT, height.to_be_bytes());
         }
 
-        if let Some(owner) = self.owner {
+        if let Some(owner) = self.owner.take() {
             let val = {
-                let mut buf = Vec::with_capacity(4096);
+                let mut buf = Vec::with_capacity(owner.byte_size());
                 owner.serialize(&mut buf);
                 buf
             };
-            batch.put(KEY_NET_OWNER, &val);
+            batch.put(CF_DEFAULT, KEY_NET_OWNER, &val);
         }
 
-        if let Some(token_supply) = self.token_supply {
+        if let Some(token_supply) = self.token_supply.take() {
             let val = {
                 let mut buf = Vec::with_capacity(mem::size_of::<Asset>());
                 buf.push_asset(token_supply);
                 buf
             };
-            batch.put(KEY_TOKEN_SUPPLY, &val);
+            batch.put(CF_DEFAULT, KEY_TOKEN_SUPPLY, &val);
+        }
+
+        if !self.accounts.is_empty() {
+            let counter = self.indexer.db.get(CF_DEFAULT, KEY_NEXT_ACCOUNT_ID)?;
+            let next_id = match &counter {
+                Some(buf) => AccountId::from_be_bytes(buf.as_slice().try_into().unwrap()),
+                None => 0,
+            };
+            // The counter only moves when the id it points to is taken, which also stores the
+            // counter of indexes that did not track it yet
+            if counter.is_none() || self.accounts.contains_key(&next_id) {
+                let next_id = self
+                    .indexer
+                    .find_unused_account_id(next_id, &self.accounts)?;
+                batch.put(CF_DEFAULT, KEY_NEXT_ACCOUNT_ID, next_id.to_be_bytes());
+            }
         }
 
         {
-            let cf = self.indexer.db.cf_handle(CF_ACCOUNT).unwrap();
             let mut buf = Vec::with_capacity(mem::size_of::<Account>());
-            for (id, account) in self.accounts {
+            for (id, account) in mem::take(&mut self.accounts) {
                 account.serialize(&mut buf);
-                batch.put_cf(cf, id.to_be_bytes(), &buf);
+                batch.put(CF_ACCOUNT, id.to_be_bytes(), &buf);
+                buf.clear();
+            }
+        }
+
+        // Scripts are looked up by their hash, so a script that is already indexed is unchanged
+        // and replaced scripts stay indexed.
+        for (hash, script) in mem::take(&mut self.scripts) {
+            if self.indexer.db.get(CF_SCRIPT, hash.as_ref())?.is_none() {
+                batch.put(CF_SCRIPT, hash, &script);
+            }
+        }
+
+        for (id, height, index) in mem::take(&mut self.tx_locations) {
+            let loc = [height.to_be_bytes().as_ref(), index.to_be_bytes().as_ref()].concat();
+            batch.put(CF_TX_LOCATION, id, loc);
+        }
+
+        {
+            let mut buf = Vec::with_capacity(mem::size_of::<Asset>());
+            for (height, supply) in mem::take(&mut self.supply_checkpoints) {
+                buf.push_asset(supply);
+                batch.put(CF_SUPPLY_CHECKPOINT, height.to_be_bytes(), &buf);
                 buf.clear();
             }
         }
 
-        self.indexer.db.write(batch).unwrap();
+        for (id, nonce) in mem::take(&mut self.account_nonces) {
+            batch.put(CF_ACCOUNT_NONCE, id.to_be_bytes(), nonce.to_be_bytes());
+        }
+
+        if let Some(height) = self.undo_height.take() {
+            let undo = self.indexer.build_undo_data(&batch)?;
+            batch.put(CF_BLOCK_UNDO, height.to_be_bytes(), undo);
+            if let Some(expired) = height.checked_sub(MAX_ROLLBACK_DEPTH) {
+                batch.delete(CF_BLOCK_UNDO, expired.to_be_bytes());
+            }
+        }
+
+        self.indexer.db.write(batch)
+    }
+
+    /// Discards all pending changes without writing them to the index. Every batch must either be
+    /// committed or aborted, otherwise dropping a batch with pending changes is considered a bug.
+    pub fn abort(mut self) {
+        self.block_byte_pos.clear();
+        self.chain_height = None;
+        self.owner = None;
+        self.accounts.clear();
+        self.scripts.clear();
+        self.token_supply = None;
+        self.tx_locations.clear();
+        self.supply_checkpoints.clear();
+        self.account_nonces.clear();
+        self.undo_height = None;
+    }
+
+    fn has_pending(&self) -> bool {
+        !self.block_byte_pos.is_empty()
+            || self.chain_height.is_some()
+            || self.owner.is_some()
+            || !self.accounts.is_empty()
+            || !self.scripts.is_empty()
+            || self.token_supply.is_some()
+            || !self.tx_locations.is_empty()
+            || !self.supply_checkpoints.is_empty()
+            || !self.account_nonces.is_empty()
+    }
+
+    /// Records the previous values of every key written by this batch when it is committed, so the
+    /// block at `height` can be reverted with `Indexer::revert_block`. Undo data is only kept for
+    /// the last `MAX_ROLLBACK_DEPTH` blocks.
+    pub fn record_undo(&mut self, height: u64) {
+        self.undo_height = Some(height);
     }
 
     pub fn set_block_byte_pos(&mut self, height: u64, pos: u64) {
@@ -246,7 +630,12 @@ impl WriteBatch {
                 *token_supply = token_supply.checked_add(amount).unwrap();
             }
             None => {
-                let amt = self.indexer.get_token_supply().checked_add(amount).unwrap();
+                let amt = self
+                    .indexer
+                    .get_token_supply()
+                    .expect("failed to read token supply")
+                    .checked_add(amount)
+                    .unwrap();
                 self.token_supply = Some(amt);
             }
         }
@@ -262,19 +651,65 @@ impl WriteBatch {
         acc.balance = acc.balance.checked_sub(amount).unwrap();
     }
 
-    #[inline]
+    /// Records the block height and receipt index where the transaction was included.
+    pub fn set_tx_location(&mut self, id: TxId, height: u64, index: u32) {
+        self.tx_locations.push((id, height, index));
+    }
+
+    /// Records the token supply including all changes made in this batch so far as the supply at
+    /// `height`.
+    pub fn set_supply_checkpoint(&mut self, height: u64) {
+        let supply = match self.token_supply {
+            Some(supply) => supply,
+            None => self
+                .indexer
+                .get_token_supply()
+                .expect("failed to read token supply"),
+        };
+        self.supply_checkpoints.push((height, supply));
+    }
+
+    /// Records the nonce of the last transfer sent from the account.
+    pub fn set_account_nonce(&mut self, id: AccountId, nonce: u32) {
+        self.account_nonces.insert(id, nonce);
+    }
+
     pub fn insert_or_update_account(&mut self, account: Account) {
+        self.scripts
+            .insert(account.script.hash(), account.script.clone());
         self.accounts.insert(account.id, account);
     }
 
+    /// Replaces the script of the account.
+    pub fn set_account_script(&mut self, id: AccountId, script: Script) {
+        self.scripts.insert(script.hash(), script.clone());
+        self.get_account_mut(id).script = script;
+    }
+
     pub fn get_account_mut(&mut self, id: AccountId) -> &mut Account {
         match self.accounts.entry(id) {
             Entry::Occupied(entry) => entry.into_mut(),
-            Entry::Vacant(entry) => entry.insert(self.indexer.get_account(id).unwrap()),
+            Entry::Vacant(entry) => {
+                let acc = self
+                    .indexer
+                    .get_account(id)
+                    .expect("failed to read account")
+                    .unwrap();
+                entry.insert(acc)
+            }
         }
     }
 }
 
+impl Drop for WriteBatch {
+    fn drop(&mut self) {
+        debug_assert!(
+            !self.has_pending() || thread::panicking(),
+            "write batch with pending changes dropped without commit or abort"
+        );
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum IndexStatus {
     None,
@@ -282,10 +717,40 @@ pub enum IndexStatus {
     Complete,
 }
 
+#[derive(Debug)]
+pub enum IndexerError {
+    Db(rocksdb::Error),
+    /// The block at the height has no undo data and cannot be reverted.
+    MissingUndoData(u64),
+}
+
+impl Error for IndexerError {}
+
+impl Display for IndexerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IndexerError::Db(e) => write!(f, "database error: {}", e),
+            IndexerError::MissingUndoData(height) => {
+                write!(f, "missing undo data for block {}", height)
+            }
+        }
+    }
+}
+
+impl From<rocksdb::Error> for IndexerError {
+    fn from(e: rocksdb::Error) -> Self {
+        IndexerError::Db(e)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::crypto::Digest;
+    use crate::{
+        account::Permissions,
+        crypto::{Digest, KeyPair},
+    };
+    use rocksdb::{Options, DB};
     use sodiumoxide::randombytes;
     use std::{env, fs, panic};
 
@@ -294,20 +759,272 @@ mod tests {
         run_test(|indexer| {
             let mut batch = WriteBatch::new(Arc::clone(&indexer));
             batch.set_block_byte_pos(1, 327);
-            batch.commit();
-            assert!(indexer.get_block_byte_pos(0).is_none());
-            assert_eq!(indexer.get_block_byte_pos(1).unwrap(), 327);
+            batch.commit().unwrap();
+            assert!(indexer.get_block_byte_pos(0).unwrap().is_none());
+            assert_eq!(indexer.get_block_byte_pos(1).unwrap(), Some(327));
         });
     }
 
     #[test]
     fn get_chain_height() {
         run_test(|indexer| {
-            assert_eq!(indexer.get_chain_height(), 0);
+            assert_eq!(indexer.get_chain_height().unwrap(), 0);
             let mut batch = WriteBatch::new(Arc::clone(&indexer));
             batch.set_chain_height(42);
-            batch.commit();
-            assert_eq!(indexer.get_chain_height(), 42);
+            batch.commit().unwrap();
+            assert_eq!(indexer.get_chain_height().unwrap(), 42);
+        });
+    }
+
+    #[test]
+    fn abort_discards_changes() {
+        run_test(|indexer| {
+            let acc = Account::create_default(
+                1,
+                Permissions {
+                    threshold: 1,
+                    keys: vec![KeyPair::gen().0],
+                },
+            );
+
+            let mut batch = WriteBatch::new(Arc::clone(&indexer));
+            batch.set_block_byte_pos(1, 327);
+            batch.set_chain_height(1);
+            batch.add_token_supply(Asset::new(100));
+            batch.insert_or_update_account(acc);
+            batch.abort();
+
+            assert!(indexer.get_block_byte_pos(1).unwrap().is_none());
+            assert_eq!(indexer.get_chain_height().unwrap(), 0);
+            assert_eq!(indexer.get_token_supply().unwrap(), Asset::default());
+            assert!(indexer.get_account(1).unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn commit_error_is_returned() {
+        run_test_in_dir(|dir| {
+            drop(Indexer::new(dir));
+            let db =
+                DB::open_cf_for_read_only(&Options::default(), dir, COL_FAMILIES.iter(), false)
+                    .unwrap();
+            let read_only = Arc::new(Indexer::with_backend(Box::new(RocksBackend { db })));
+
+            let mut batch = WriteBatch::new(Arc::clone(&read_only));
+            batch.set_chain_height(42);
+            match batch.commit() {
+                Err(IndexerError::Db(_)) => {}
+                res => panic!("expected a database error: {:?}", res),
+            }
+            assert_eq!(read_only.get_chain_height().unwrap(), 0);
+        });
+    }
+
+    #[test]
+    fn get_account_range() {
+        run_test(|indexer| {
+            let ids = [7, 2, 300, 5, 1];
+            let mut batch = WriteBatch::new(Arc::clone(&indexer));
+            for id in &ids {
+                let acc = Account::create_default(
+                    *id,
+                    Permissions {
+                        threshold: 1,
+                        keys: vec![KeyPair::gen().0],
+                    },
+                );
+                batch.insert_or_update_account(acc);
+            }
+            batch.commit().unwrap();
+
+            let get_ids = |start, limit| -> Vec<AccountId> {
+                indexer
+                    .get_account_range(start, limit)
+                    .unwrap()
+                    .into_iter()
+                    .map(|(id, acc)| {
+                        assert_eq!(id, acc.id);
+                        id
+                    })
+                    .collect()
+            };
+
+            assert_eq!(get_ids(0, 100), vec![1, 2, 5, 7, 300]);
+            assert_eq!(get_ids(0, 2), vec![1, 2]);
+            assert_eq!(get_ids(3, 2), vec![5, 7]);
+            assert_eq!(get_ids(8, 2), vec![300]);
+            assert_eq!(get_ids(301, 2), vec![]);
+            assert_eq!(get_ids(0, 0), vec![]);
+        });
+    }
+
+    #[test]
+    fn get_accounts() {
+        run_test(|indexer| {
+            let mut batch = WriteBatch::new(Arc::clone(&indexer));
+            for id in &[3, 9] {
+                let acc = Account::create_default(
+                    *id,
+                    Permissions {
+                        threshold: 1,
+                        keys: vec![KeyPair::gen().0],
+                    },
+                );
+                batch.insert_or_update_account(acc);
+            }
+            batch.commit().unwrap();
+
+            let ids = indexer
+                .get_accounts(&[9, 4, 3, 9])
+                .unwrap()
+                .into_iter()
+                .map(|acc| acc.map(|acc| acc.id))
+                .collect::<Vec<_>>();
+            assert_eq!(ids, vec![Some(9), None, Some(3), Some(9)]);
+            assert_eq!(indexer.get_accounts(&[]).unwrap(), vec![]);
+        });
+    }
+
+    #[test]
+    fn next_account_id_is_smallest_unused_id() {
+        run_test(|indexer| {
+            assert_eq!(indexer.get_next_account_id().unwrap(), 0);
+
+            let insert = |id, height| {
+                let mut batch = WriteBatch::new(Arc::clone(&indexer));
+                batch.record_undo(height);
+                let acc = Account::create_default(
+                    id,
+                    Permissions {
+                        threshold: 1,
+                        keys: vec![KeyPair::gen().0],
+                    },
+                );
+                batch.insert_or_update_account(acc);
+                batch.commit().unwrap();
+            };
+
+            insert(0, 1);
+            assert_eq!(indexer.get_next_account_id().unwrap(), 1);
+            insert(2, 2);
+            assert_eq!(indexer.get_next_account_id().unwrap(), 1);
+            insert(3, 3);
+            assert_eq!(indexer.get_next_account_id().unwrap(), 1);
+            insert(1, 4);
+            assert_eq!(indexer.get_next_account_id().unwrap(), 4);
+
+            // Removing a middle account frees its id
+            indexer.revert_block(4).unwrap();
+            assert!(!indexer.account_exists(1));
+            assert_eq!(indexer.get_next_account_id().unwrap(), 1);
+
+            // Indexes without a tracked counter derive it from the accounts
+            let mut batch = Batch::default();
+            batch.delete(CF_DEFAULT, KEY_NEXT_ACCOUNT_ID);
+            indexer.db.write(batch).unwrap();
+            assert_eq!(indexer.get_next_account_id().unwrap(), 1);
+            insert(1, 4);
+            assert_eq!(indexer.get_next_account_id().unwrap(), 4);
+        });
+    }
+
+    #[test]
+    fn tx_locations() {
+        run_test(|indexer| {
+            let id_a = TxId::from_digest(Digest::from_slice(&[1u8; 32]).unwrap());
+            let id_b = TxId::from_digest(Digest::from_slice(&[2u8; 32]).unwrap());
+            assert_eq!(indexer.get_tx_location(&id_a).unwrap(), None);
+
+            let mut batch = WriteBatch::new(Arc::clone(&indexer));
+            batch.set_tx_location(id_a.clone(), 5, 0);
+            batch.set_tx_location(id_b.clone(), u64::MAX, u32::MAX);
+            assert_eq!(indexer.get_tx_location(&id_a).unwrap(), None);
+            batch.commit().unwrap();
+
+            assert_eq!(indexer.get_tx_location(&id_a).unwrap(), Some((5, 0)));
+            assert_eq!(
+                indexer.get_tx_location(&id_b).unwrap(),
+                Some((u64::MAX, u32::MAX))
+            );
+        });
+    }
+
+    #[test]
+    fn scripts_are_reverted() {
+        run_test(|indexer| {
+            let acc = Account::create_default(
+                1,
+                Permissions {
+                    threshold: 1,
+                    keys: vec![KeyPair::gen().0],
+                },
+            );
+            let old_script = acc.script.clone();
+            let new_script = Script::new(vec![0x01, 0x02, 0x03]);
+
+            let mut batch = WriteBatch::new(Arc::clone(&indexer));
+            batch.record_undo(1);
+            batch.insert_or_update_account(acc);
+            batch.commit().unwrap();
+            assert_eq!(
+                indexer.get_script(&old_script.hash()).unwrap(),
+                Some(old_script.clone())
+            );
+
+            let mut batch = WriteBatch::new(Arc::clone(&indexer));
+            batch.record_undo(2);
+            batch.add_bal(1, Asset::new(10));
+            batch.commit().unwrap();
+
+            let mut batch = WriteBatch::new(Arc::clone(&indexer));
+            batch.record_undo(3);
+            batch.set_account_script(1, new_script.clone());
+            batch.commit().unwrap();
+            let acc = indexer.get_account(1).unwrap().unwrap();
+            assert_eq!(acc.script, new_script);
+            assert_eq!(
+                indexer.get_script(&new_script.hash()).unwrap(),
+                Some(new_script.clone())
+            );
+            // The replaced script can still be looked up
+            assert_eq!(
+                indexer.get_script(&old_script.hash()).unwrap(),
+                Some(old_script.clone())
+            );
+
+            indexer.revert_block(3).unwrap();
+            let acc = indexer.get_account(1).unwrap().unwrap();
+            assert_eq!(acc.script, old_script);
+            assert_eq!(indexer.get_script(&new_script.hash()).unwrap(), None);
+
+            // Reverting a block that did not change the script keeps it indexed
+            indexer.revert_block(2).unwrap();
+            assert_eq!(
+                indexer.get_script(&old_script.hash()).unwrap(),
+                Some(old_script.clone())
+            );
+
+            indexer.revert_block(1).unwrap();
+            assert_eq!(indexer.get_account(1).unwrap(), None);
+            assert_eq!(indexer.get_script(&old_script.hash()).unwrap(), None);
+        });
+    }
+
+    #[test]
+    fn account_nonces() {
+        run_test(|indexer| {
+            assert_eq!(indexer.get_account_nonce(1).unwrap(), None);
+
+            let mut batch = WriteBatch::new(Arc::clone(&indexer));
+            batch.set_account_nonce(1, 5);
+            batch.set_account_nonce(2, u32::MAX);
+            batch.set_account_nonce(1, 7);
+            assert_eq!(indexer.get_account_nonce(1).unwrap(), None);
+            batch.commit().unwrap();
+
+            assert_eq!(indexer.get_account_nonce(1).unwrap(), Some(7));
+            assert_eq!(indexer.get_account_nonce(2).unwrap(), Some(u32::MAX));
+            assert_eq!(indexer.get_account_nonce(3).unwrap(), None);
         });
     }
 
@@ -320,8 +1037,9 @@ mod tests {
             indexer.insert_txid(&id, expiry);
             assert!(indexer.has_txid(&id));
 
-            let cf = indexer.db.cf_handle(CF_TX_EXPIRY).unwrap();
-            indexer.db.delete_cf(cf, &id).unwrap();
+            let mut batch = Batch::default();
+            batch.delete(CF_TX_EXPIRY, &id);
+            indexer.db.write(batch).unwrap();
             assert!(!indexer.has_txid(&id));
 
             indexer.insert_txid(&id, expiry - TX_EXPIRY_ADJUSTMENT + 1);
@@ -329,8 +1047,9 @@ mod tests {
             // The transaction has expired, but we give additional time before purging it.
             assert!(indexer.has_txid(&id));
 
-            let cf = indexer.db.cf_handle(CF_TX_EXPIRY).unwrap();
-            indexer.db.delete_cf(cf, &id).unwrap();
+            let mut batch = Batch::default();
+            batch.delete(CF_TX_EXPIRY, &id);
+            indexer.db.write(batch).unwrap();
             assert!(!indexer.has_txid(&id));
             indexer.insert_txid(&id, expiry - TX_EXPIRY_ADJUSTMENT - 1);
             assert!(indexer.has_txid(&id));
@@ -340,9 +1059,97 @@ mod tests {
         });
     }
 
+    #[test]
+    fn purge_only_expired_txids() {
+        run_test(|indexer| {
+            let current_time = crate::get_epoch_time() - TX_EXPIRY_ADJUSTMENT;
+            let ids = (0..1000u64)
+                .map(|i| {
+                    let mut buf = [0u8; 32];
+                    buf[..8].copy_from_slice(&i.to_be_bytes());
+                    let id = TxId::from_digest(Digest::from_slice(&buf).unwrap());
+                    // Half of the transactions have expired with varying expiry times
+                    let expiry = if i % 2 == 0 {
+                        current_time - 1 - (i * 7) % 500
+                    } else {
+                        current_time + 1 + (i * 7) % 500
+                    };
+                    indexer.insert_txid(&id, expiry);
+                    (i, id)
+                })
+                .collect::<Vec<_>>();
+
+            indexer.purge_expired_txids();
+            for (i, id) in &ids {
+                assert_eq!(indexer.has_txid(id), i % 2 != 0, "txid {}", i);
+            }
+
+            let remaining = indexer.db.iter(CF_TX_EXPIRY_TIME, IterMode::Start).count();
+            assert_eq!(remaining, 500);
+        });
+    }
+
+    #[test]
+    fn clear_keeps_index_status() {
+        run_test(|indexer| {
+            let mut batch = WriteBatch::new(Arc::clone(&indexer));
+            batch.set_chain_height(42);
+            for height in 0..100 {
+                batch.set_block_byte_pos(height, height * 100);
+            }
+            batch.set_account_nonce(1, 5);
+            batch.commit().unwrap();
+            let id = TxId::from_digest(Digest::from_slice(&[0u8; 32]).unwrap());
+            indexer.insert_txid(&id, crate::get_epoch_time());
+            indexer.set_index_status(IndexStatus::Partial);
+
+            indexer.clear();
+            for cf in COL_FAMILIES.iter().copied() {
+                assert_eq!(indexer.db.iter(cf, IterMode::Start).count(), 0, "{}", cf);
+            }
+            assert_eq!(indexer.get_chain_height().unwrap(), 0);
+            assert!(!indexer.has_txid(&id));
+            assert_eq!(indexer.index_status(), IndexStatus::Partial);
+        });
+    }
+
+    #[test]
+    fn custom_options() {
+        let opts = IndexerOptions {
+            wal_recovery_mode: DBRecoveryMode::PointInTime,
+            block_cache_size: Some(64 * 1024),
+            compression: false,
+        };
+        run_test_with_options(opts, |indexer| {
+            let mut batch = WriteBatch::new(Arc::clone(&indexer));
+            batch.set_chain_height(42);
+            batch.set_block_byte_pos(42, 327);
+            batch.commit().unwrap();
+            assert_eq!(indexer.get_chain_height().unwrap(), 42);
+            assert_eq!(indexer.get_block_byte_pos(42).unwrap(), Some(327));
+        });
+    }
+
     fn run_test<F>(func: F)
     where
         F: FnOnce(Arc<Indexer>) -> () + panic::UnwindSafe,
+    {
+        run_test_with_options(IndexerOptions::default(), func);
+    }
+
+    fn run_test_with_options<F>(opts: IndexerOptions, func: F)
+    where
+        F: FnOnce(Arc<Indexer>) + panic::UnwindSafe,
+    {
+        run_test_in_dir(|dir| {
+            let indexer = Indexer::with_options(dir, opts);
+            func(Arc::new(indexer));
+        });
+    }
+
+    fn run_test_in_dir<F>(func: F)
+    where
+        F: FnOnce(&Path) + panic::UnwindSafe,
     {
         let mut tmp_dir = env::temp_dir();
         {
@@ -354,10 +1161,7 @@ mod tests {
         }
         fs::create_dir(&tmp_dir).expect(&format!("Could not create temp dir {:?}", &tmp_dir));
 
-        let result = panic::catch_unwind(|| {
-            let indexer = Indexer::new(&tmp_dir);
-            func(Arc::new(indexer));
-        });
+        let result = panic::catch_unwind(|| func(&tmp_dir));
 
         fs::remove_dir_all(&tmp_dir).expect("Failed to rm dir");
         assert!(result.is_ok());
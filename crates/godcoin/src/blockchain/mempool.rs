@@ -0,0 +1,224 @@
+use crate::{
+    constants::TX_MAX_EXPIRY_TIME,
+    tx::{TxId, TxPrecompData},
+};
+use std::{cmp::Ordering, collections::HashMap};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MempoolErr {
+    TxDupe,
+    TxExpired,
+    /// The pool is full and the transaction does not pay a higher fee per byte than the lowest
+    /// paying transaction in the pool.
+    PoolFull,
+}
+
+#[derive(Debug)]
+struct Entry {
+    data: TxPrecompData<'static>,
+    byte_size: usize,
+}
+
+impl Entry {
+    fn new(data: TxPrecompData<'static>) -> Self {
+        let byte_size = data.tx().byte_size();
+        Self { data, byte_size }
+    }
+
+    /// Compares the fee per byte of both entries without losing precision to division.
+    fn cmp_fee_rate(&self, other: &Entry) -> Ordering {
+        let fee = |e: &Entry| i128::from(e.data.tx().fee().amount);
+        let lhs = fee(self) * other.byte_size as i128;
+        let rhs = fee(other) * self.byte_size as i128;
+        lhs.cmp(&rhs)
+    }
+}
+
+/// Holds pending transactions ordered by their fee per byte for block inclusion. Transactions are
+/// not executed against the chain, the caller is responsible for validating them.
+#[derive(Debug)]
+pub struct Mempool {
+    txs: HashMap<TxId, Entry>,
+    max_size: usize,
+}
+
+impl Mempool {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            txs: HashMap::with_capacity(max_size),
+            max_size,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.txs.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.txs.is_empty()
+    }
+
+    #[inline]
+    pub fn contains(&self, id: &TxId) -> bool {
+        self.txs.contains_key(id)
+    }
+
+    /// Inserts the transaction into the pool. When the pool is full, the transaction with the
+    /// lowest fee per byte is evicted if the new transaction pays more.
+    pub fn insert(&mut self, data: TxPrecompData<'static>) -> Result<(), MempoolErr> {
+        let current_time = crate::get_epoch_time();
+        let expiry = data.tx().expiry();
+        if expiry <= current_time || expiry - current_time > TX_MAX_EXPIRY_TIME {
+            return Err(MempoolErr::TxExpired);
+        } else if self.txs.contains_key(data.txid()) {
+            return Err(MempoolErr::TxDupe);
+        }
+
+        let entry = Entry::new(data);
+        if self.txs.len() >= self.max_size {
+            let lowest = self
+                .txs
+                .iter()
+                .min_by(|(_, a), (_, b)| a.cmp_fee_rate(b))
+                .map(|(id, e)| (id.clone(), e.cmp_fee_rate(&entry)));
+            match lowest {
+                Some((id, Ordering::Less)) => {
+                    self.txs.remove(&id);
+                }
+                _ => return Err(MempoolErr::PoolFull),
+            }
+        }
+
+        self.txs.insert(entry.data.txid().clone(), entry);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: &TxId) -> Option<TxPrecompData<'static>> {
+        self.txs.remove(id).map(|e| e.data)
+    }
+
+    /// Removes and returns up to `n` transactions with the highest fee per byte, ordered from the
+    /// highest paying transaction.
+    pub fn take_best(&mut self, n: usize) -> Vec<TxPrecompData<'static>> {
+        let mut ids = self.txs.keys().cloned().collect::<Vec<_>>();
+        ids.sort_by(|a, b| self.txs[b].cmp_fee_rate(&self.txs[a]));
+        ids.truncate(n);
+        ids.iter()
+            .map(|id| self.txs.remove(id).unwrap().data)
+            .collect()
+    }
+
+    /// Removes every transaction that is expired at `current_time` and returns the amount removed.
+    pub fn prune_expired(&mut self, current_time: u64) -> usize {
+        let len = self.txs.len();
+        self.txs.retain(|_, e| e.data.tx().expiry() > current_time);
+        len - self.txs.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{asset::Asset, tx::*};
+
+    fn create_tx(fee: i64, memo_len: usize) -> TxPrecompData<'static> {
+        create_tx_with_expiry(fee, memo_len, crate::get_epoch_time() + 30)
+    }
+
+    fn create_tx_with_expiry(fee: i64, memo_len: usize, expiry: u64) -> TxPrecompData<'static> {
+        let nonce: u32 = {
+            let mut nonce = [0; 4];
+            sodiumoxide::randombytes::randombytes_into(&mut nonce);
+            u32::from_ne_bytes(nonce)
+        };
+        TxVariant::V0(TxVariantV0::TransferTx(TransferTx {
+            base: Tx {
+                nonce,
+                expiry,
+                fee: Asset::new(fee),
+                signature_pairs: vec![],
+            },
+            from: 0,
+            call_fn: 0,
+            args: vec![],
+            amount: Asset::default(),
+            memo: vec![0; memo_len],
+        }))
+        .precompute()
+    }
+
+    #[test]
+    fn take_best_orders_by_fee_per_byte() {
+        let mut pool = Mempool::new(10);
+        let low = create_tx(100, 0);
+        // Pays more in total but less per byte than `low`
+        let large = create_tx(200, 1000);
+        let high = create_tx(1000, 0);
+        for tx in &[&low, &large, &high] {
+            pool.insert((*tx).clone()).unwrap();
+        }
+        assert_eq!(pool.len(), 3);
+
+        let best = pool.take_best(2);
+        assert_eq!(best, vec![high, low]);
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.take_best(5), vec![large]);
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn reject_dupes() {
+        let mut pool = Mempool::new(10);
+        let tx = create_tx(100, 0);
+        pool.insert(tx.clone()).unwrap();
+        assert_eq!(pool.insert(tx.clone()), Err(MempoolErr::TxDupe));
+
+        assert_eq!(pool.remove(tx.txid()), Some(tx.clone()));
+        assert_eq!(pool.remove(tx.txid()), None);
+        assert_eq!(pool.insert(tx), Ok(()));
+    }
+
+    #[test]
+    fn evict_lowest_fee_when_full() {
+        let mut pool = Mempool::new(2);
+        let low = create_tx(100, 0);
+        let mid = create_tx(200, 0);
+        pool.insert(low.clone()).unwrap();
+        pool.insert(mid.clone()).unwrap();
+
+        // Does not pay more than the lowest paying tx
+        let lower = create_tx(100, 0);
+        assert_eq!(pool.insert(lower.clone()), Err(MempoolErr::PoolFull));
+        assert!(!pool.contains(lower.txid()));
+
+        let high = create_tx(300, 0);
+        pool.insert(high.clone()).unwrap();
+        assert_eq!(pool.len(), 2);
+        assert!(!pool.contains(low.txid()));
+        assert!(pool.contains(mid.txid()));
+        assert!(pool.contains(high.txid()));
+    }
+
+    #[test]
+    fn reject_and_prune_expired() {
+        let mut pool = Mempool::new(10);
+        let now = crate::get_epoch_time();
+
+        let tx = create_tx_with_expiry(100, 0, now - 1);
+        assert_eq!(pool.insert(tx), Err(MempoolErr::TxExpired));
+        let tx = create_tx_with_expiry(100, 0, now + TX_MAX_EXPIRY_TIME + 10);
+        assert_eq!(pool.insert(tx), Err(MempoolErr::TxExpired));
+
+        let short = create_tx_with_expiry(100, 0, now + 10);
+        let long = create_tx_with_expiry(100, 0, now + 100);
+        pool.insert(short.clone()).unwrap();
+        pool.insert(long.clone()).unwrap();
+
+        assert_eq!(pool.prune_expired(now), 0);
+        assert_eq!(pool.prune_expired(now + 10), 1);
+        assert!(!pool.contains(short.txid()));
+        assert!(pool.contains(long.txid()));
+    }
+}
@@ -1,20 +1,27 @@
 use parking_lot::Mutex;
 use std::{path::Path, sync::Arc};
-use tracing::info;
+use tracing::{debug, info, trace, warn};
 
+pub mod backend;
 pub mod block;
 pub mod error;
 pub mod index;
+pub mod mempool;
 pub mod receipt;
 pub mod skip_flags;
 pub mod store;
+pub mod subscription;
+pub mod verify;
 
 pub use self::{
     block::*,
     error::*,
-    index::{IndexStatus, Indexer, WriteBatch},
+    index::{IndexStatus, Indexer, IndexerError, IndexerOptions, WriteBatch},
+    mempool::{Mempool, MempoolErr},
     receipt::*,
-    store::{BlockStore, ReindexOpts},
+    store::{BlockStore, ReindexErr, ReindexOpts},
+    subscription::BlockReceiver,
+    verify::TxPolicy,
 };
 
 use crate::{
@@ -48,10 +55,83 @@ impl AccountInfo {
     }
 }
 
+/// Consensus parameters of the chain, every node of a chain must use the same values. The default
+/// values are the mainnet parameters, test chains may use smaller windows to reach fee adjustments
+/// with fewer blocks.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainParams {
+    /// Number of blocks without an account transaction before the account fee resets.
+    pub fee_reset_window: usize,
+    /// Number of blocks used to average the network fee, must be at least 1.
+    pub network_fee_avg_window: u64,
+    /// Maximum sum of the serialized transaction sizes in a block.
+    pub max_block_byte_size: usize,
+    /// Number of blocks between token supply checkpoints, a value of 0 disables checkpoints.
+    pub supply_checkpoint_interval: u64,
+    /// Height of the first block whose transfers must use a nonce greater than the last nonce of
+    /// the sending account.
+    pub nonce_check_height: u64,
+    pub fee_min: Asset,
+    pub fee_mult: Asset,
+    pub net_fee_mult: Asset,
+}
+
+impl Default for ChainParams {
+    fn default() -> Self {
+        ChainParams {
+            fee_reset_window: FEE_RESET_WINDOW,
+            network_fee_avg_window: NETWORK_FEE_AVG_WINDOW,
+            max_block_byte_size: MAX_BLOCK_BYTE_SIZE,
+            supply_checkpoint_interval: SUPPLY_CHECKPOINT_INTERVAL,
+            nonce_check_height: NONCE_CHECK_HEIGHT,
+            fee_min: GRAEL_FEE_MIN,
+            fee_mult: GRAEL_FEE_MULT,
+            net_fee_mult: GRAEL_FEE_NET_MULT,
+        }
+    }
+}
+
+impl ChainParams {
+    /// Checks that the parameters can be used to operate a chain.
+    pub fn validate(&self) -> Result<(), ChainParamsErr> {
+        if self.network_fee_avg_window == 0 {
+            return Err(ChainParamsErr::ZeroNetworkFeeWindow);
+        }
+        Ok(())
+    }
+}
+
+/// Options local to the node. Unlike `ChainParams`, these may differ between nodes of the same
+/// chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeOptions {
+    /// Whether newly written blocks are compressed in the block log. Compressed and uncompressed
+    /// blocks can be mixed in the same block log.
+    pub compress_blocks: bool,
+    /// Tuning options for the index database.
+    pub index_opts: IndexerOptions,
+    /// Number of seconds a block timestamp may be ahead of the local time.
+    pub max_timestamp_skew: u64,
+}
+
+impl Default for NodeOptions {
+    fn default() -> Self {
+        NodeOptions {
+            compress_blocks: false,
+            index_opts: IndexerOptions::default(),
+            max_timestamp_skew: MAX_BLOCK_TIMESTAMP_SKEW,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Blockchain {
     indexer: Arc<Indexer>,
     store: Mutex<BlockStore>,
+    params: ChainParams,
+    opts: NodeOptions,
+    subscribers: subscription::Subscribers,
+    tx_policy: Option<Box<dyn TxPolicy>>,
 }
 
 impl Blockchain {
@@ -59,15 +139,67 @@ impl Blockchain {
     /// Creates a new `Blockchain` with an associated indexer and block log based on the
     /// provided paths.
     ///
-    pub fn new(blocklog_loc: &Path, index_loc: &Path) -> Self {
-        let indexer = Arc::new(Indexer::new(index_loc));
-        let store = BlockStore::new(blocklog_loc, Arc::clone(&indexer));
+    pub fn new(
+        blocklog_loc: &Path,
+        index_loc: &Path,
+        params: ChainParams,
+        opts: NodeOptions,
+    ) -> Self {
+        if let Err(e) = params.validate() {
+            panic!("Invalid chain params: {}", e);
+        }
+        let indexer = Arc::new(Indexer::with_options(index_loc, opts.index_opts));
+        let store = BlockStore::new(blocklog_loc, Arc::clone(&indexer), opts.compress_blocks);
+        Blockchain {
+            indexer,
+            store: Mutex::new(store),
+            params,
+            opts,
+            subscribers: Default::default(),
+            tx_policy: None,
+        }
+    }
+
+    /// Creates a new `Blockchain` that keeps the index and block log in memory. Nothing is
+    /// persisted, which makes this useful for tests.
+    pub fn new_in_memory(params: ChainParams) -> Self {
+        if let Err(e) = params.validate() {
+            panic!("Invalid chain params: {}", e);
+        }
+        let opts = NodeOptions::default();
+        let indexer = Arc::new(Indexer::new_in_memory());
+        let store = BlockStore::new_in_memory(Arc::clone(&indexer), opts.compress_blocks);
         Blockchain {
             indexer,
             store: Mutex::new(store),
+            params,
+            opts,
+            subscribers: Default::default(),
+            tx_policy: None,
+        }
+    }
+
+    /// Sets the policy that transactions submitted to the node must satisfy in addition to the
+    /// consensus rules.
+    pub fn with_tx_policy(mut self, policy: Box<dyn TxPolicy>) -> Self {
+        self.tx_policy = Some(policy);
+        self
+    }
+
+    /// Checks the transaction against the local policy, any transaction is accepted when no policy
+    /// is set.
+    pub fn check_tx_policy(&self, tx: &TxVariant) -> Result<(), TxErr> {
+        match &self.tx_policy {
+            Some(policy) => policy.check(tx),
+            None => Ok(()),
         }
     }
 
+    #[inline]
+    pub fn params(&self) -> &ChainParams {
+        &self.params
+    }
+
     pub fn is_empty(&self) -> bool {
         self.store.lock().is_empty()
     }
@@ -81,32 +213,44 @@ impl Blockchain {
         self.indexer.index_status()
     }
 
-    pub fn reindex(&self, opts: ReindexOpts) {
-        {
-            let status = self.indexer.index_status();
-            if status != IndexStatus::None {
-                panic!("expected index status to be None, got: {:?}", status);
-            }
-        }
+    /// Removes all indexed data and resets the index status to `None`, which allows a complete
+    /// index to be rebuilt using `reindex`.
+    pub fn clear_index(&self) {
+        let _store = self.store.lock();
+        self.indexer.clear();
+        self.indexer.set_index_status(IndexStatus::None);
+    }
+
+    ///
+    /// Rebuilds the index by replaying the block log from the genesis block. The index status is
+    /// kept as `Partial` until the reindex completes, so an interrupted reindex will be restarted
+    /// from scratch the next time this is called.
+    ///
+    pub fn reindex(&self, opts: ReindexOpts) -> Result<(), ReindexErr> {
         let mut store = self.store.lock();
-        if store.get_chain_height() == 0 {
-            // Attempt to read the raw block stored at byte position 0, which must _always_ be the
-            // genesis block. Then, we find the owner wallet account creation and forcibly index it.
-            // This will prevent the receipt index process from choking when the creation account is
-            // non-existent since the genesis block is the beginning of the chain.
-            if let Ok(genesis_block) = store.raw_read_from_disk(0) {
-                let receipts = genesis_block.receipts();
-                // Two transactions: the first is the creation of the owner wallet, and the second
-                // is the configuration of the owner transaction.
-                assert_eq!(receipts.len(), 2);
-                for r in receipts {
-                    match &r.tx {
-                        TxVariant::V0(tx) => {
-                            if let TxVariantV0::CreateAccountTx(tx) = tx {
-                                let mut batch = WriteBatch::new(self.indexer());
-                                batch.insert_or_update_account(tx.account.clone());
-                                batch.commit();
-                            }
+        if self.indexer.index_status() == IndexStatus::Complete {
+            return Err(ReindexErr::IndexComplete);
+        } else if store.is_empty() {
+            return Err(ReindexErr::EmptyBlockLog);
+        }
+        self.indexer.set_index_status(IndexStatus::Partial);
+        self.indexer.clear();
+        // Attempt to read the raw block stored at byte position 0, which must _always_ be the
+        // genesis block. Then, we find the owner wallet account creation and forcibly index it.
+        // This will prevent the receipt index process from choking when the creation account is
+        // non-existent since the genesis block is the beginning of the chain.
+        if let Ok(genesis_block) = store.raw_read_from_disk(0) {
+            let receipts = genesis_block.receipts();
+            // Two transactions: the first is the creation of the owner wallet, and the second
+            // is the configuration of the owner transaction.
+            assert_eq!(receipts.len(), 2);
+            for r in receipts {
+                match &r.tx {
+                    TxVariant::V0(tx) => {
+                        if let TxVariantV0::CreateAccountTx(tx) = tx {
+                            let mut batch = WriteBatch::new(self.indexer());
+                            batch.insert_or_update_account(tx.account.clone());
+                            batch.commit().expect("Failed to commit index batch");
                         }
                     }
                 }
@@ -125,7 +269,7 @@ impl Blockchain {
         // Iterate in reverse from head to genesis block
         for height in (0..=self.get_chain_height()).rev() {
             let block = store.get(height).unwrap();
-            if current_time - block.timestamp() <= TX_MAX_EXPIRY_TIME {
+            if current_time.saturating_sub(block.timestamp()) <= TX_MAX_EXPIRY_TIME {
                 for receipt in block.receipts() {
                     let data = TxPrecompData::from_tx(&receipt.tx);
                     let expiry = data.tx().expiry();
@@ -140,13 +284,17 @@ impl Blockchain {
         }
 
         info!("Reindexing complete");
+        Ok(())
     }
 
     pub fn get_properties(&self) -> Properties {
         Properties {
             height: self.get_chain_height(),
             owner: Box::new(self.get_owner()),
-            token_supply: self.indexer.get_token_supply(),
+            token_supply: self
+                .indexer
+                .get_token_supply()
+                .expect("Failed to retrieve token supply from index"),
             network_fee: self
                 .get_network_fee()
                 .expect("unexpected error retrieving network fee"),
@@ -157,12 +305,24 @@ impl Blockchain {
     pub fn get_owner(&self) -> TxVariant {
         self.indexer
             .get_owner()
+            .expect("Failed to read owner from index")
             .expect("Failed to retrieve owner from index")
     }
 
+    /// Returns the smallest account id that is not used by any indexed account. Accounts created
+    /// by transactions that have not yet been included in a block are not considered.
+    #[inline]
+    pub fn next_account_id(&self) -> AccountId {
+        self.indexer
+            .get_next_account_id()
+            .expect("Failed to retrieve next account id from index")
+    }
+
     #[inline]
     pub fn get_chain_height(&self) -> u64 {
-        self.indexer.get_chain_height()
+        self.indexer
+            .get_chain_height()
+            .expect("Failed to retrieve chain height from index")
     }
 
     pub fn get_chain_head(&self) -> Arc<Block> {
@@ -176,6 +336,146 @@ impl Blockchain {
         store.get(height)
     }
 
+    pub fn get_genesis_block(&self) -> Arc<Block> {
+        self.get_block(0).expect("Failed to get genesis block")
+    }
+
+    ///
+    /// Walks back up to `depth` blocks from the chain head verifying each block's height, receipt
+    /// root, and previous hash link to its predecessor. When the walk reaches height 0, the genesis
+    /// block is verified as well. Returns the height of the first inconsistent block found.
+    ///
+    pub fn validate_chain_continuity(&self, depth: u64) -> Result<(), (u64, BlockErr)> {
+        let store = self.store.lock();
+        let head_height = store.get_chain_height();
+        let min_height = head_height.saturating_sub(depth);
+
+        let mut block = store
+            .get(head_height)
+            .ok_or((head_height, BlockErr::InvalidBlockHeight))?;
+        for height in (min_height..head_height).rev() {
+            let prev_block = store
+                .get(height)
+                .ok_or((height, BlockErr::InvalidBlockHeight))?;
+            let err = if block.height() != height + 1 {
+                Some(BlockErr::InvalidBlockHeight)
+            } else if !block.verify_receipt_root() {
+                Some(BlockErr::InvalidReceiptRoot)
+            } else if !block.verify_previous_hash(&prev_block) {
+                Some(BlockErr::InvalidPrevHash)
+            } else {
+                None
+            };
+            if let Some(err) = err {
+                return Err((height + 1, err));
+            }
+            block = prev_block;
+        }
+
+        if min_height == 0 && !Self::is_valid_genesis(&block) {
+            return Err((0, BlockErr::InvalidGenesis));
+        }
+        Ok(())
+    }
+
+    fn is_valid_genesis(block: &Block) -> bool {
+        let previous_hash = match block.header() {
+            BlockHeader::V0(header) => header.previous_hash,
+            BlockHeader::V1(header) => header.previous_hash,
+        };
+        let receipts = block.receipts();
+        if block.height() != 0
+            || previous_hash.as_ref() != [0; 32]
+            || !block.verify_receipt_root()
+            || receipts.len() != 2
+        {
+            return false;
+        }
+
+        let minter = match &receipts[1].tx {
+            TxVariant::V0(TxVariantV0::OwnerTx(owner)) => owner.minter.clone(),
+            _ => return false,
+        };
+        let minters = Permissions {
+            threshold: 1,
+            keys: vec![minter],
+        };
+        block.verify_signers(&minters).is_ok()
+    }
+
+    /// Returns an included transaction along with the height of the block that includes it.
+    pub fn get_transaction(&self, id: &TxId) -> Option<(u64, TxVariant)> {
+        let (height, index) = self
+            .indexer
+            .get_tx_location(id)
+            .expect("Failed to retrieve tx location from index")?;
+        let block = self.get_block(height)?;
+        let receipt = block.receipts().get(index as usize)?;
+        Some((height, receipt.tx.clone()))
+    }
+
+    /// Returns the script with the provided hash if it is or was used by an indexed account.
+    pub fn get_script(&self, hash: &ScriptHash) -> Option<Script> {
+        self.indexer
+            .get_script(hash)
+            .expect("Failed to retrieve script from index")
+    }
+
+    /// Scans the blocks from `since_height` to the chain head and returns every owner transaction
+    /// along with the height of the block that included it.
+    pub fn get_owner_history(&self, since_height: u64) -> Vec<(u64, OwnerTx)> {
+        let mut history = vec![];
+        for height in since_height..=self.get_chain_height() {
+            let block = self.get_block(height).expect("Failed to retrieve block");
+            for receipt in block.receipts() {
+                if let TxVariant::V0(TxVariantV0::OwnerTx(tx)) = &receipt.tx {
+                    history.push((height, tx.clone()));
+                }
+            }
+        }
+        history
+    }
+
+    /// Replays the mint transactions from the nearest supply checkpoint, or the genesis block when
+    /// no checkpoint is available, up to and including `height` to compute the token supply at
+    /// that height. Returns `None` if `height` is above the chain head.
+    pub fn token_supply_at_height(&self, height: u64) -> Option<Asset> {
+        if height > self.get_chain_height() {
+            return None;
+        }
+        let interval = self.params.supply_checkpoint_interval;
+        let checkpoint = if interval > 0 {
+            let checkpoint_height = height - (height % interval);
+            self.indexer
+                .get_supply_checkpoint(checkpoint_height)
+                .expect("Failed to read supply checkpoint from index")
+                .map(|supply| (checkpoint_height + 1, supply))
+        } else {
+            None
+        };
+
+        let (start_height, mut supply) = checkpoint.unwrap_or((0, Asset::default()));
+        for h in start_height..=height {
+            let block = self.get_block(h).expect("Failed to retrieve block");
+            for receipt in block.receipts() {
+                if let TxVariant::V0(TxVariantV0::MintTx(tx)) = &receipt.tx {
+                    supply = supply
+                        .checked_add(tx.amount)
+                        .expect("Token supply overflow");
+                }
+            }
+        }
+        Some(supply)
+    }
+
+    /// Gets the block header and the minter's signature at the specified `height` without the
+    /// block body.
+    pub fn get_block_header(&self, height: u64) -> Option<(BlockHeader, SigPair)> {
+        let block = self.get_block(height)?;
+        let signer = block.signer()?.clone();
+        Some((block.header(), signer))
+    }
+
     /// Gets a filtered block using the `filter` at the specified `height`. This does not match
     /// whether the `filter` contains an owner account to match block rewards.
     pub fn get_filtered_block(&self, height: u64, filter: &BlockFilter) -> Option<FilteredBlock> {
@@ -233,7 +533,19 @@ impl Blockchain {
     }
 
     pub fn get_account(&self, id: AccountId, additional_receipts: &[Receipt]) -> Option<Account> {
-        let mut acc = self.indexer.get_account(id)?;
+        let acc = self
+            .indexer
+            .get_account(id)
+            .expect("Failed to retrieve account from index")?;
+        Self::apply_additional_receipts(acc, additional_receipts)
+    }
+
+    /// Applies the changes of the `additional_receipts` to the indexed account.
+    fn apply_additional_receipts(
+        mut acc: Account,
+        additional_receipts: &[Receipt],
+    ) -> Option<Account> {
+        let id = acc.id;
         // This must perform the same actions as when a receipt is indexed. See `fn index_receipt`
         for receipt in additional_receipts {
             match &receipt.tx {
@@ -278,7 +590,7 @@ impl Blockchain {
                                 LogEntry::Destroy(_to_acc) => {
                                     if tx.from == id {
                                         acc.destroyed = true;
-                                        acc.balance = Asset::new(0);
+                                        acc.balance = Asset::zero();
                                     }
                                     // The receiving account is not allowed to see the funds until a
                                     // block is produced. The reason for this is that we cannot
@@ -298,6 +610,35 @@ impl Blockchain {
         Some(acc)
     }
 
+    /// Returns the nonce of the last transfer sent from the account, including transfers in the
+    /// `additional_receipts`. Returns `None` if the account has never sent a transfer.
+    pub fn get_account_nonce(&self, id: AccountId, additional_receipts: &[Receipt]) -> Option<u32> {
+        let pending = additional_receipts.iter().rev().find_map(|r| match &r.tx {
+            TxVariant::V0(TxVariantV0::TransferTx(tx)) if tx.from == id => Some(tx.nonce),
+            _ => None,
+        });
+        pending.or_else(|| {
+            self.indexer
+                .get_account_nonce(id)
+                .expect("Failed to retrieve account nonce from index")
+        })
+    }
+
+    /// Returns the balance of each account in the same order as `ids`. Accounts that don't exist
+    /// have no balance.
+    pub fn get_balances(
+        &self,
+        ids: &[AccountId],
+        additional_receipts: &[Receipt],
+    ) -> Vec<Option<Asset>> {
+        self.indexer
+            .get_accounts(ids)
+            .expect("Failed to retrieve accounts from index")
+            .into_iter()
+            .map(|acc| Some(Self::apply_additional_receipts(acc?, additional_receipts)?.balance))
+            .collect()
+    }
+
     pub fn get_account_info(
         &self,
         id: AccountId,
@@ -346,22 +687,29 @@ impl Blockchain {
             for r in block.receipts() {
                 handle_receipt_match!(r);
             }
-            if delta == FEE_RESET_WINDOW {
+            if delta == self.params.fee_reset_window {
                 break;
             }
         }
 
-        GRAEL_FEE_MIN.checked_mul(GRAEL_FEE_MULT.checked_pow(count as u16)?)
+        let params = &self.params;
+        params
+            .fee_min
+            .checked_mul(params.fee_mult.checked_pow(count as u16)?)
     }
 
     pub fn get_network_fee(&self) -> Option<Asset> {
         // The network fee adjusts every 5 blocks so that users have a bigger time
         // frame to confirm the fee they want to spend without suddenly changing.
-        use crate::constants::*;
+        let params = &self.params;
         let max_height = self.get_chain_height();
         let max_height = max_height - (max_height % 5);
-        let min_height = if max_height > NETWORK_FEE_AVG_WINDOW {
-            max_height - NETWORK_FEE_AVG_WINDOW
+        // Young chains do not have a full window of history to average over yet.
+        if max_height < params.network_fee_avg_window {
+            return Some(params.fee_min);
+        }
+        let min_height = if max_height > params.network_fee_avg_window {
+            max_height - params.network_fee_avg_window
         } else {
             0
         };
@@ -370,69 +718,183 @@ impl Blockchain {
         for i in min_height..=max_height {
             count += self.get_block(i).unwrap().receipts().len() as u64;
         }
-        count /= NETWORK_FEE_AVG_WINDOW;
+        count /= params.network_fee_avg_window;
         if count > u64::from(u16::max_value()) {
             return None;
         }
 
-        GRAEL_FEE_MIN.checked_mul(GRAEL_FEE_NET_MULT.checked_pow(count as u16)?)
+        params
+            .fee_min
+            .checked_mul(params.net_fee_mult.checked_pow(count as u16)?)
+    }
+
+    /// Verifies the `block` as the next block against the current chain head without inserting
+    /// it or mutating any chain state.
+    pub fn check_block(&self, block: &Block, skip_flags: SkipFlags) -> Result<(), BlockErr> {
+        self.verify_block(block, &self.get_chain_head(), skip_flags)
+    }
+
+    /// Decides whether the chain should switch to the `candidate` block from a competing fork
+    /// over the current chain head. The higher block wins, blocks at the same height are ordered
+    /// by the lowest header hash. The candidate must be a validly signed child of
+    /// `candidate_prev`.
+    pub fn should_switch_to(&self, candidate: &Block, candidate_prev: &Block) -> bool {
+        let minters = self.get_minters();
+        if candidate
+            .verify_structure_with_minters(candidate_prev, &minters)
+            .is_err()
+        {
+            return false;
+        }
+
+        let head = self.get_chain_head();
+        if candidate.height() != head.height() {
+            return candidate.height() > head.height();
+        }
+        candidate.calc_header_hash().as_ref() < head.calc_header_hash().as_ref()
     }
 
     pub fn insert_block(&self, block: Block) -> Result<(), BlockErr> {
         static SKIP_FLAGS: SkipFlags = SKIP_NONE;
-        self.verify_block(&block, &self.get_chain_head(), SKIP_FLAGS)?;
+        self.check_block(&block, SKIP_FLAGS)?;
         let mut batch = WriteBatch::new(Arc::clone(&self.indexer));
+        batch.record_undo(block.height());
         self.index_block(&mut batch, &block);
-        self.store.lock().insert(&mut batch, block);
-        batch.commit();
+        let block = {
+            let mut store = self.store.lock();
+            let height = block.height();
+            store.insert(&mut batch, block);
+            store
+                .get(height)
+                .expect("Failed to retrieve inserted block")
+        };
+        batch.commit().expect("Failed to commit index batch");
+        self.subscribers.notify(&block);
+
+        Ok(())
+    }
 
+    /// Removes every block above `height` and reverts their changes to the index so the chain
+    /// state matches the state at `height`. Only blocks inserted with `insert_block` within the
+    /// last `MAX_ROLLBACK_DEPTH` blocks can be reverted, otherwise nothing is removed.
+    pub fn rollback_to_height(&self, height: u64) -> Result<(), IndexerError> {
+        let mut store = self.store.lock();
+        let head_height = store.get_chain_height();
+        if height >= head_height {
+            return Ok(());
+        }
+        let byte_pos = self
+            .indexer
+            .get_block_byte_pos(height + 1)?
+            .expect("Failed to retrieve block byte position from index");
+        // The removed transactions must be accepted again when they are rebroadcasted
+        let mut removed_txs = vec![];
+        for h in height + 1..=head_height {
+            let block = store.get(h).expect("Failed to get block to roll back");
+            for r in block.receipts() {
+                removed_txs.push((r.tx.calc_txid(), r.tx.expiry()));
+            }
+        }
+        self.indexer
+            .revert_blocks(height + 1..=head_height, &removed_txs)?;
+        store.truncate(byte_pos);
+        info!(height, removed = head_height - height, "Rolled back chain");
         Ok(())
     }
 
+    /// Subscribes to blocks inserted with `insert_block` after the index has been committed.
+    /// Blocks are delivered in insertion order, a subscriber that falls more than
+    /// `subscription::MAX_PENDING_BLOCKS` blocks behind loses the oldest pending blocks.
+    pub fn subscribe(&self) -> BlockReceiver {
+        self.subscribers.subscribe()
+    }
+
     fn verify_block(
         &self,
         block: &Block,
         prev_block: &Block,
         skip_flags: SkipFlags,
     ) -> Result<(), BlockErr> {
-        if prev_block.height() + 1 != block.height() {
-            return Err(BlockErr::InvalidBlockHeight);
-        } else if !block.verify_receipt_root() {
-            return Err(BlockErr::InvalidReceiptRoot);
-        } else if !block.verify_previous_hash(prev_block) {
-            return Err(BlockErr::InvalidPrevHash);
+        let res = self.verify_block_contents(block, prev_block, skip_flags);
+        match &res {
+            Ok(()) => debug!(height = block.height(), "Block verified"),
+            Err(e) => warn!(height = block.height(), err = ?e, "Block rejected"),
         }
+        res
+    }
 
-        let block_signer = block.signer().ok_or(BlockErr::InvalidSignature)?;
+    /// Returns the minter set blocks must be signed by. The owner transaction names a single
+    /// minter key which forms a one of one set.
+    fn get_minters(&self) -> Permissions {
         match self.get_owner() {
-            TxVariant::V0(tx) => match tx {
-                TxVariantV0::OwnerTx(owner) => {
-                    if block_signer.pub_key != owner.minter {
-                        return Err(BlockErr::InvalidSignature);
-                    }
-                }
-                _ => unreachable!(),
+            TxVariant::V0(TxVariantV0::OwnerTx(owner)) => Permissions {
+                threshold: 1,
+                keys: vec![owner.minter],
             },
+            _ => unreachable!(),
         }
+    }
 
-        if !block_signer.verify(block.calc_header_hash().as_ref()) {
-            return Err(BlockErr::InvalidSignature);
+    fn verify_block_contents(
+        &self,
+        block: &Block,
+        prev_block: &Block,
+        skip_flags: SkipFlags,
+    ) -> Result<(), BlockErr> {
+        let height = block.height();
+        block.verify_structure_with_minters(prev_block, &self.get_minters())?;
+        debug!(height, "Block structure verified");
+
+        // Timestamps have a resolution of seconds, blocks produced within the same second share
+        // the timestamp of the previous block.
+        let timestamp = block.timestamp();
+        if timestamp < prev_block.timestamp() {
+            return Err(BlockErr::InvalidTimestamp);
+        } else if timestamp > crate::get_epoch_time() + self.opts.max_timestamp_skew {
+            return Err(BlockErr::TimestampTooFarAhead);
         }
 
         let block_receipts = block.receipts();
+        let byte_size: usize = block_receipts.iter().map(|r| r.tx.byte_size()).sum();
+        if byte_size > self.params.max_block_byte_size {
+            return Err(BlockErr::BlockTooLarge);
+        }
+        debug!(height, byte_size, "Block size verified");
+
         let len = block_receipts.len();
         for i in 0..len {
             let r = &block_receipts[i];
             let receipts = &block_receipts[0..i];
-            if let Err(e) = self.execute_tx(&TxPrecompData::from_tx(&r.tx), receipts, skip_flags) {
-                return Err(BlockErr::Tx(e));
+            let res = self.execute_tx(&TxPrecompData::from_tx(&r.tx), receipts, skip_flags);
+            trace!(height, index = i, result = ?res, "Executed block tx");
+            if let Err(e) = res {
+                return Err(BlockErr::Tx { index: i, err: e });
             }
         }
+        debug!(height, tx_count = len, "Block transactions verified");
 
         Ok(())
     }
 
     pub fn execute_tx(
+        &self,
+        data: &TxPrecompData,
+        additional_receipts: &[Receipt],
+        skip_flags: SkipFlags,
+    ) -> Result<Vec<LogEntry>, TxErr> {
+        let tx = data.tx();
+
+        verify::check_tx_limits(tx)?;
+        if tx.byte_size() > self.params.max_block_byte_size {
+            // The transaction could never be included in a block
+            return Err(TxErr::TxTooLarge);
+        }
+
+        self.execute_tx_unchecked(data, additional_receipts, skip_flags)
+    }
+
+    /// Executes the transaction against the chain state without checking its size limits.
+    fn execute_tx_unchecked(
         &self,
         data: &TxPrecompData,
         additional_receipts: &[Receipt],
@@ -440,7 +902,7 @@ impl Blockchain {
     ) -> Result<Vec<LogEntry>, TxErr> {
         macro_rules! check_zero_fee {
             ($asset:expr) => {
-                if $asset.amount != 0 {
+                if !$asset.is_zero() {
                     return Err(TxErr::InvalidFeeAmount);
                 }
             };
@@ -456,11 +918,6 @@ impl Blockchain {
         }
 
         let tx = data.tx();
-
-        if tx.sigs().len() > MAX_TX_SIGNATURES {
-            return Err(TxErr::TooManySignatures);
-        }
-
         match tx {
             TxVariant::V0(tx) => match tx {
                 TxVariantV0::OwnerTx(new_owner) => {
@@ -518,6 +975,7 @@ impl Blockchain {
                     // Sanity check to ensure too many new coins can't be minted
                     self.indexer
                         .get_token_supply()
+                        .expect("Failed to retrieve token supply from index")
                         .checked_add(mint_tx.amount)
                         .ok_or(TxErr::Arithmetic)?;
 
@@ -526,9 +984,7 @@ impl Blockchain {
                 TxVariantV0::CreateAccountTx(create_account_tx) => {
                     let new_acc = &create_account_tx.account;
 
-                    if new_acc.script.len() > MAX_SCRIPT_BYTE_SIZE {
-                        return Err(TxErr::TxTooLarge);
-                    } else if new_acc.destroyed {
+                    if new_acc.destroyed {
                         return Err(TxErr::TxProhibited);
                     } else if !new_acc.permissions.is_valid() {
                         return Err(TxErr::InvalidAccountPermissions);
@@ -606,10 +1062,6 @@ impl Blockchain {
 
                     if acc_info.account.destroyed {
                         return Err(TxErr::TxProhibited);
-                    } else if let Some(script) = &update_acc_tx.new_script {
-                        if script.len() > MAX_SCRIPT_BYTE_SIZE {
-                            return Err(TxErr::TxTooLarge);
-                        }
                     } else if let Some(perms) = &update_acc_tx.new_permissions {
                         if !perms.is_valid() {
                             return Err(TxErr::InvalidAccountPermissions);
@@ -650,44 +1102,104 @@ impl Blockchain {
 
                     Ok(vec![])
                 }
-                TxVariantV0::TransferTx(transfer) => {
-                    if transfer.memo.len() > MAX_MEMO_BYTE_SIZE {
-                        return Err(TxErr::TxTooLarge);
-                    }
-                    check_pos_amt!(transfer.amount);
-
-                    let info = self
-                        .get_account_info(transfer.from, additional_receipts)
-                        .ok_or(TxErr::AccountNotFound)?;
-                    if tx.fee < info.total_fee().ok_or(TxErr::Arithmetic)? {
-                        return Err(TxErr::InvalidFeeAmount);
-                    }
+                TxVariantV0::TransferTx(transfer) => self.check_transfer(
+                    data,
+                    transfer,
+                    additional_receipts,
+                    &mut CheckMode::ShortCircuit,
+                ),
+            },
+        }
+    }
 
-                    let bal = info
-                        .account
-                        .balance
-                        .checked_sub(transfer.fee)
-                        .ok_or(TxErr::Arithmetic)?
-                        .checked_sub(transfer.amount)
-                        .ok_or(TxErr::Arithmetic)?;
-                    check_pos_amt!(bal);
+    /// Runs the checks of a transfer transaction shared by `execute_tx` and `verify_tx_collect`.
+    /// Violations of independent checks are reported to the `mode`, a violation that prevents
+    /// the remaining checks from running is always returned.
+    fn check_transfer(
+        &self,
+        data: &TxPrecompData,
+        transfer: &TransferTx,
+        additional_receipts: &[Receipt],
+        mode: &mut CheckMode,
+    ) -> Result<Vec<LogEntry>, TxErr> {
+        if transfer.amount.amount < 0 {
+            mode.report(TxErr::InvalidAmount)?;
+        }
 
-                    let data = EngineData {
-                        script: info.account.script.into(),
-                        tx_data: data.into(),
-                        chain: self,
-                        additional_receipts,
-                    };
-                    let log = ScriptEngine::new(data).eval().map_err(TxErr::ScriptEval)?;
-                    Ok(log)
+        let info = self
+            .get_account_info(transfer.from, additional_receipts)
+            .ok_or(TxErr::AccountNotFound)?;
+        // Transfers are executed as part of the block following the chain head
+        if self.get_chain_height() + 1 >= self.params.nonce_check_height {
+            if let Some(nonce) = self.get_account_nonce(transfer.from, additional_receipts) {
+                if transfer.nonce <= nonce {
+                    mode.report(TxErr::NonceTooLow)?;
                 }
-            },
+            }
+        }
+        match info.total_fee() {
+            Some(fee) if transfer.fee < fee => mode.report(TxErr::InvalidFeeAmount)?,
+            Some(_) => {}
+            None => mode.report(TxErr::Arithmetic)?,
+        }
+
+        let bal = info
+            .account
+            .balance
+            .checked_sub(transfer.fee)
+            .and_then(|bal| bal.checked_sub(transfer.amount));
+        match bal {
+            Some(bal) if bal.amount < 0 => mode.report(TxErr::InvalidAmount)?,
+            Some(_) => {}
+            None => mode.report(TxErr::Arithmetic)?,
+        }
+
+        let data = EngineData {
+            script: info.account.script.into(),
+            tx_data: data.into(),
+            chain: self,
+            additional_receipts,
+        };
+        ScriptEngine::new(data).eval().map_err(TxErr::ScriptEval)
+    }
+
+    /// Verifies the transaction like `execute_tx` except every independent check is run and all
+    /// violations are returned. An empty result means the transaction is valid. Checks that depend
+    /// on an earlier check passing, such as the balance of an account that was not found, are
+    /// skipped when the earlier check fails.
+    pub fn verify_tx_collect(
+        &self,
+        data: &TxPrecompData,
+        additional_receipts: &[Receipt],
+        skip_flags: SkipFlags,
+    ) -> Vec<TxErr> {
+        let mut errs = verify::collect_tx_limit_errs(data.tx());
+        if data.tx().byte_size() > self.params.max_block_byte_size {
+            push_unique_err(&mut errs, TxErr::TxTooLarge);
+        }
+
+        let res = match data.tx() {
+            TxVariant::V0(TxVariantV0::TransferTx(transfer)) => {
+                let mut mode = CheckMode::Collect(&mut errs);
+                self.check_transfer(data, transfer, additional_receipts, &mut mode)
+                    .map(|_| ())
+            }
+            // The checks of the other transactions depend on each other
+            _ => self
+                .execute_tx_unchecked(data, additional_receipts, skip_flags)
+                .map(|_| ()),
+        };
+        if let Err(e) = res {
+            push_unique_err(&mut errs, e);
         }
+
+        errs
     }
 
     fn index_block(&self, batch: &mut WriteBatch, block: &Block) {
-        for r in block.receipts() {
+        for (index, r) in block.receipts().iter().enumerate() {
             Self::index_receipt(batch, r);
+            batch.set_tx_location(r.tx.calc_txid(), block.height(), index as u32);
         }
         let owner_tx = match batch.get_owner() {
             Some(tx) => tx.clone(),
@@ -699,6 +1211,11 @@ impl Blockchain {
             }
             _ => panic!("expected owner transaction"),
         };
+
+        let interval = self.params.supply_checkpoint_interval;
+        if interval > 0 && block.height() % interval == 0 {
+            batch.set_supply_checkpoint(block.height());
+        }
     }
 
     fn index_receipt(batch: &mut WriteBatch, receipt: &Receipt) {
@@ -717,17 +1234,17 @@ impl Blockchain {
                     batch.insert_or_update_account(tx.account.clone());
                 }
                 TxVariantV0::UpdateAccountTx(tx) => {
-                    let acc = batch.get_account_mut(tx.account_id);
-                    acc.balance = acc.balance.checked_sub(tx.fee).unwrap();
+                    batch.sub_bal(tx.account_id, tx.fee);
                     if let Some(script) = &tx.new_script {
-                        acc.script = script.clone();
+                        batch.set_account_script(tx.account_id, script.clone());
                     }
                     if let Some(perms) = &tx.new_permissions {
-                        acc.permissions = perms.clone();
+                        batch.get_account_mut(tx.account_id).permissions = perms.clone();
                     }
                 }
                 TxVariantV0::TransferTx(tx) => {
                     batch.sub_bal(tx.from, tx.fee.checked_add(tx.amount).unwrap());
+                    batch.set_account_nonce(tx.from, tx.nonce);
                     for entry in &receipt.log {
                         match entry {
                             LogEntry::Transfer(to_acc, amount) => batch.add_bal(*to_acc, *amount),
@@ -735,7 +1252,7 @@ impl Blockchain {
                                 let from_acc = batch.get_account_mut(tx.from);
                                 let from_cur_bal = from_acc.balance;
                                 from_acc.destroyed = true;
-                                from_acc.balance = Asset::new(0);
+                                from_acc.balance = Asset::zero();
                                 batch.add_bal(*to_acc, from_cur_bal);
                             }
                         }
@@ -747,18 +1264,14 @@ impl Blockchain {
 
     pub fn create_genesis_block(&self, minter_key: KeyPair) -> GenesisBlockInfo {
         let info = GenesisBlockInfo::new(minter_key, 0);
-        let timestamp = crate::get_epoch_time();
+        self.create_genesis_block_from_info(&info);
+        info
+    }
 
-        let owner_wallet = Account {
-            id: info.owner_id,
-            balance: Asset::default(),
-            script: info.script.clone(),
-            permissions: Permissions {
-                threshold: 2,
-                keys: info.wallet_keys.iter().map(|kp| kp.0.clone()).collect(),
-            },
-            destroyed: false,
-        };
+    /// Creates the genesis block using the minter key and owner wallet provided by the `info`.
+    pub fn create_genesis_block_from_info(&self, info: &GenesisBlockInfo) {
+        let timestamp = crate::get_epoch_time();
+        let owner_wallet = info.owner_wallet();
 
         let create_account_tx = TxVariant::V0(TxVariantV0::CreateAccountTx(CreateAccountTx {
             base: Tx {
@@ -808,33 +1321,107 @@ impl Blockchain {
         block.sign(&info.minter_key);
 
         let mut batch = WriteBatch::new(Arc::clone(&self.indexer));
+        for (index, r) in block.receipts().iter().enumerate() {
+            batch.set_tx_location(r.tx.calc_txid(), 0, index as u32);
+        }
         self.store.lock().insert_genesis(&mut batch, block);
         batch.set_owner(owner_tx);
         batch.insert_or_update_account(owner_wallet);
-        batch.commit();
+        batch.commit().expect("Failed to commit index batch");
         self.indexer.set_index_status(IndexStatus::Complete);
+    }
+}
 
-        info
+/// Determines how violations found while checking a transaction are reported.
+enum CheckMode<'a> {
+    /// The first violation is returned and no further checks run.
+    ShortCircuit,
+    /// Violations are collected and the remaining independent checks keep running.
+    Collect(&'a mut Vec<TxErr>),
+}
+
+impl CheckMode<'_> {
+    fn report(&mut self, err: TxErr) -> Result<(), TxErr> {
+        match self {
+            CheckMode::ShortCircuit => Err(err),
+            CheckMode::Collect(errs) => {
+                push_unique_err(errs, err);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn push_unique_err(errs: &mut Vec<TxErr>, err: TxErr) {
+    if !errs.contains(&err) {
+        errs.push(err);
     }
 }
 
 pub struct GenesisBlockInfo {
     pub owner_id: AccountId,
     pub minter_key: KeyPair,
-    pub wallet_keys: [KeyPair; 4],
+    /// The owner wallet key pairs when they were generated. This is empty when the genesis info
+    /// is created from public keys.
+    pub wallet_keys: Vec<KeyPair>,
+    pub permissions: Permissions,
     pub script: Script,
 }
 
 impl GenesisBlockInfo {
     pub fn new(minter_key: KeyPair, owner_id: AccountId) -> Self {
-        let wallet_keys = [
+        let wallet_keys = vec![
             KeyPair::gen(),
             KeyPair::gen(),
             KeyPair::gen(),
             KeyPair::gen(),
         ];
+        let permissions = Permissions {
+            threshold: 2,
+            keys: wallet_keys.iter().map(|kp| kp.0.clone()).collect(),
+        };
 
-        let script = Builder::new()
+        Self {
+            owner_id,
+            minter_key,
+            wallet_keys,
+            permissions,
+            script: Self::owner_script(owner_id),
+        }
+    }
+
+    /// Creates the genesis info with a pre-agreed owner wallet that requires `threshold`
+    /// signatures from the `wallet_keys`. The resulting owner wallet is deterministic given the
+    /// inputs.
+    pub fn from_keys(minter_key: KeyPair, wallet_keys: Vec<PublicKey>, threshold: u8) -> Self {
+        let owner_id = 0;
+        let permissions = Permissions {
+            threshold,
+            keys: wallet_keys,
+        };
+        assert!(permissions.is_valid(), "invalid owner wallet permissions");
+
+        Self {
+            owner_id,
+            minter_key,
+            wallet_keys: vec![],
+            permissions,
+            script: Self::owner_script(owner_id),
+        }
+    }
+
+    pub fn owner_wallet(&self) -> Account {
+        Account {
+            id: self.owner_id,
+            balance: Asset::default(),
+            script: self.script.clone(),
+            permissions: self.permissions.clone(),
+            destroyed: false,
+        }
+    }
+
+    fn owner_script(owner_id: AccountId) -> Script {
+        Builder::new()
             .push(
                 // The purpose of this function is to be used for minting transactions
                 FnBuilder::new(0, OpFrame::OpDefine(vec![]))
@@ -850,13 +1437,527 @@ impl GenesisBlockInfo {
                     .push(OpFrame::True),
             )
             .build()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sodiumoxide::{crypto::sign, randombytes};
+    use std::{env, fs, path::PathBuf};
+
+    fn seeded_key(seed: u8) -> KeyPair {
+        let seed = sign::Seed([seed; sign::SEEDBYTES]);
+        let (pk, sk) = sign::keypair_from_seed(&seed);
+        KeyPair(PublicKey(pk), PrivateKey { seed, key: sk })
+    }
+
+    #[test]
+    fn genesis_info_from_keys() {
+        let create_info = || {
+            let keys = (1..=3).map(|i| seeded_key(i).0).collect();
+            GenesisBlockInfo::from_keys(seeded_key(0), keys, 2)
+        };
+
+        let info = create_info();
+        assert_eq!(info.owner_id, 0);
+        assert!(info.wallet_keys.is_empty());
+        assert_eq!(info.permissions.threshold, 2);
+        assert_eq!(
+            info.permissions.keys,
+            (1..=3).map(|i| seeded_key(i).0).collect::<Vec<_>>()
+        );
+        assert_eq!(info.owner_wallet(), create_info().owner_wallet());
+
+        let hash = {
+            let mut buf = vec![];
+            info.owner_wallet().serialize(&mut buf);
+            double_sha256(&buf)
+        };
+        // The owner wallet must be stable across runs
+        let expected: [u8; 32] = [
+            34, 207, 110, 7, 218, 136, 183, 176, 112, 7, 15, 151, 192, 155, 216, 245, 45, 137, 179,
+            123, 219, 223, 185, 176, 216, 142, 211, 134, 223, 176, 218, 241,
+        ];
+        assert_eq!(hash.as_ref(), &expected);
+    }
+
+    fn create_chain(params: ChainParams) -> (Blockchain, GenesisBlockInfo, PathBuf) {
+        let tmp_dir = {
+            let mut tmp_dir = env::temp_dir();
+            let mut num: [u8; 8] = [0; 8];
+            randombytes::randombytes_into(&mut num);
+            tmp_dir.push(format!("godcoin_test_{}", u64::from_be_bytes(num)));
+            tmp_dir
+        };
+        fs::create_dir(&tmp_dir).expect("Could not create temp dir");
+        let log_path = Path::join(&tmp_dir, "blocklog");
+        let index_path = Path::join(&tmp_dir, "index");
+        let chain = Blockchain::new(&log_path, &index_path, params, NodeOptions::default());
+        let info = chain.create_genesis_block(KeyPair::gen());
+        (chain, info, tmp_dir)
+    }
+
+    #[test]
+    fn network_fee_is_min_on_young_chain() {
+        let produce_block = |chain: &Blockchain, info: &GenesisBlockInfo| {
+            // Fill the block with transactions that would otherwise raise the network fee
+            let receipts = (0..5)
+                .map(|_| {
+                    let mut tx = TxVariant::V0(TxVariantV0::MintTx(MintTx {
+                        base: Tx {
+                            nonce: randombytes::randombytes_uniform(u32::MAX),
+                            expiry: crate::get_epoch_time() + 30,
+                            fee: Asset::default(),
+                            signature_pairs: vec![],
+                        },
+                        to: info.owner_id,
+                        amount: Asset::new(100),
+                        attachment: vec![],
+                        attachment_name: "".to_string(),
+                    }));
+                    tx.append_sign(&info.wallet_keys[1]);
+                    tx.append_sign(&info.wallet_keys[0]);
+                    Receipt { tx, log: vec![] }
+                })
+                .collect();
+            let mut block = match chain.get_chain_head().as_ref() {
+                Block::V0(head) => head.new_child(receipts),
+                Block::V1(head) => head.new_child(receipts),
+            };
+            block.sign(&info.minter_key);
+            chain.insert_block(block).unwrap();
+        };
+
+        crate::init().unwrap();
+        let (chain, info, tmp_dir) = create_chain(ChainParams::default());
+        let min_fee = chain.params().fee_min;
+        for height in 0..=5 {
+            if height > 0 {
+                produce_block(&chain, &info);
+            }
+            assert_eq!(chain.get_chain_height(), height);
+            if [0, 1, 4, 5].contains(&height) {
+                assert_eq!(chain.get_network_fee(), Some(min_fee));
+            }
+        }
+        fs::remove_dir_all(tmp_dir).unwrap();
+    }
+
+    #[test]
+    fn validate_chain_params() {
+        assert_eq!(ChainParams::default().validate(), Ok(()));
+        let params = ChainParams {
+            network_fee_avg_window: 0,
+            ..ChainParams::default()
+        };
+        assert_eq!(params.validate(), Err(ChainParamsErr::ZeroNetworkFeeWindow));
+    }
+
+    #[test]
+    #[should_panic(expected = "network fee average window must be at least 1")]
+    fn zero_network_fee_window_is_rejected() {
+        Blockchain::new_in_memory(ChainParams {
+            network_fee_avg_window: 0,
+            ..ChainParams::default()
+        });
+    }
+
+    #[test]
+    fn tx_policy_rejects_mints() {
+        #[derive(Debug)]
+        struct RejectMints;
+
+        impl TxPolicy for RejectMints {
+            fn check(&self, tx: &TxVariant) -> Result<(), TxErr> {
+                match tx {
+                    TxVariant::V0(TxVariantV0::MintTx(_)) => Err(TxErr::TxProhibited),
+                    _ => Ok(()),
+                }
+            }
+        }
+
+        crate::init().unwrap();
+        let chain =
+            Blockchain::new_in_memory(ChainParams::default()).with_tx_policy(Box::new(RejectMints));
+        let info = chain.create_genesis_block(KeyPair::gen());
+        let chain = Arc::new(chain);
+
+        let mint = {
+            let mut tx = TxVariant::V0(TxVariantV0::MintTx(MintTx {
+                base: Tx {
+                    nonce: 1,
+                    expiry: crate::get_epoch_time() + 30,
+                    fee: Asset::default(),
+                    signature_pairs: vec![],
+                },
+                to: info.owner_id,
+                amount: Asset::new(1_000_000),
+                attachment: vec![],
+                attachment_name: "".to_string(),
+            }));
+            tx.append_sign(&info.wallet_keys[1]);
+            tx.append_sign(&info.wallet_keys[0]);
+            tx
+        };
+
+        let pool = ReceiptPool::new(Arc::clone(&chain));
+        let mint_data = TxPrecompData::from_tx(&mint);
+        assert!(chain.execute_tx(&mint_data, &[], SKIP_NONE).is_ok());
+        assert_eq!(pool.check(&mint_data, SKIP_NONE), Err(TxErr::TxProhibited));
+
+        // The policy does not apply to transactions included in blocks
+        let mut block = match chain.get_chain_head().as_ref() {
+            Block::V0(head) => head.new_child(vec![Receipt {
+                tx: mint,
+                log: vec![],
+            }]),
+            Block::V1(head) => head.new_child(vec![Receipt {
+                tx: mint,
+                log: vec![],
+            }]),
+        };
+        block.sign(&info.minter_key);
+        chain.insert_block(block).unwrap();
+
+        let fee = chain
+            .get_account_info(info.owner_id, &[])
+            .unwrap()
+            .total_fee()
             .unwrap();
+        let mut transfer = TxVariant::V0(TxVariantV0::TransferTx(TransferTx {
+            base: Tx {
+                nonce: 2,
+                expiry: crate::get_epoch_time() + 30,
+                fee,
+                signature_pairs: vec![],
+            },
+            from: info.owner_id,
+            call_fn: 0,
+            args: vec![],
+            amount: Asset::default(),
+            memo: vec![],
+        }));
+        transfer.append_sign(&info.wallet_keys[1]);
+        transfer.append_sign(&info.wallet_keys[0]);
+        let transfer_data = TxPrecompData::from_tx(&transfer);
+        assert_eq!(pool.check(&transfer_data, SKIP_NONE), Ok(vec![]));
+    }
 
-        Self {
-            owner_id,
-            minter_key,
-            wallet_keys,
-            script,
+    #[test]
+    fn in_memory_chain() {
+        crate::init().unwrap();
+        let chain = Blockchain::new_in_memory(ChainParams::default());
+        assert!(chain.is_empty());
+        let info = chain.create_genesis_block(KeyPair::gen());
+        assert!(!chain.is_empty());
+        let balance = chain.get_account(info.owner_id, &[]).unwrap().balance;
+
+        let mut tx = TxVariant::V0(TxVariantV0::MintTx(MintTx {
+            base: Tx {
+                nonce: 1,
+                expiry: crate::get_epoch_time() + 30,
+                fee: Asset::default(),
+                signature_pairs: vec![],
+            },
+            to: info.owner_id,
+            amount: Asset::new(1000),
+            attachment: vec![],
+            attachment_name: "".to_string(),
+        }));
+        tx.append_sign(&info.wallet_keys[1]);
+        tx.append_sign(&info.wallet_keys[0]);
+        let mut block = match chain.get_chain_head().as_ref() {
+            Block::V0(head) => head.new_child(vec![Receipt { tx, log: vec![] }]),
+            Block::V1(head) => head.new_child(vec![Receipt { tx, log: vec![] }]),
+        };
+        block.sign(&info.minter_key);
+        chain.insert_block(block).unwrap();
+
+        assert_eq!(chain.get_chain_height(), 1);
+        assert_eq!(chain.get_block(1).unwrap().height(), 1);
+        assert_eq!(
+            chain.get_account(info.owner_id, &[]).unwrap().balance,
+            balance.checked_add(Asset::new(1000)).unwrap()
+        );
+    }
+
+    #[test]
+    fn block_timestamp_validation() {
+        crate::init().unwrap();
+        let chain = Blockchain::new_in_memory(ChainParams::default());
+        let info = chain.create_genesis_block(KeyPair::gen());
+        let child_with_timestamp = |timestamp: u64| {
+            let mut block = match chain.get_chain_head().as_ref() {
+                Block::V0(head) => head.new_child(vec![]),
+                Block::V1(head) => head.new_child(vec![]),
+            };
+            match &mut block {
+                Block::V0(block) => block.header.timestamp = timestamp,
+                Block::V1(block) => block.header.timestamp = timestamp,
+            }
+            block.sign(&info.minter_key);
+            block
+        };
+
+        let now = crate::get_epoch_time();
+        chain.insert_block(child_with_timestamp(now)).unwrap();
+        assert_eq!(chain.get_chain_head().timestamp(), now);
+
+        assert_eq!(
+            chain.check_block(&child_with_timestamp(now - 1), SKIP_NONE),
+            Err(BlockErr::InvalidTimestamp)
+        );
+
+        let skew = MAX_BLOCK_TIMESTAMP_SKEW;
+        assert_eq!(
+            chain.check_block(&child_with_timestamp(now + skew + 60), SKIP_NONE),
+            Err(BlockErr::TimestampTooFarAhead)
+        );
+
+        chain.insert_block(child_with_timestamp(now)).unwrap();
+        chain.insert_block(child_with_timestamp(now + 1)).unwrap();
+        assert_eq!(chain.get_chain_height(), 3);
+        assert_eq!(chain.get_chain_head().timestamp(), now + 1);
+    }
+
+    #[test]
+    fn fork_choice() {
+        crate::init().unwrap();
+        let chain = Blockchain::new_in_memory(ChainParams::default());
+        let info = chain.create_genesis_block(KeyPair::gen());
+        let child = |parent: &Block, timestamp: u64| {
+            let mut block = match parent {
+                Block::V0(parent) => parent.new_child(vec![]),
+                Block::V1(parent) => parent.new_child(vec![]),
+            };
+            match &mut block {
+                Block::V0(block) => block.header.timestamp = timestamp,
+                Block::V1(block) => block.header.timestamp = timestamp,
+            }
+            block.sign(&info.minter_key);
+            block
+        };
+
+        let genesis = chain.get_genesis_block();
+        let now = crate::get_epoch_time();
+        chain.insert_block(child(&genesis, now)).unwrap();
+        let head = chain.get_chain_head();
+        assert!(!chain.should_switch_to(&head, &genesis));
+
+        // A longer fork wins regardless of its hash
+        let fork_1 = child(&genesis, now + 1);
+        let fork_2 = child(&fork_1, now + 2);
+        assert!(chain.should_switch_to(&fork_2, &fork_1));
+
+        // Forks at the same height are decided by the lowest hash
+        let head_hash = head.calc_header_hash();
+        let (mut lower, mut higher) = (None, None);
+        for timestamp in now + 1.. {
+            let fork = child(&genesis, timestamp);
+            if fork.calc_header_hash().as_ref() < head_hash.as_ref() {
+                lower.get_or_insert(fork);
+            } else {
+                higher.get_or_insert(fork);
+            }
+            if lower.is_some() && higher.is_some() {
+                break;
+            }
         }
+        assert!(chain.should_switch_to(&lower.unwrap(), &genesis));
+        assert!(!chain.should_switch_to(&higher.unwrap(), &genesis));
+
+        // The candidate must link to its parent
+        assert!(!chain.should_switch_to(&fork_2, &genesis));
+
+        // The candidate must be signed by the minter set blocks are verified against
+        let mut fork_2 = fork_2;
+        fork_2.sign(&KeyPair::gen());
+        assert!(!chain.should_switch_to(&fork_2, &fork_1));
+    }
+
+    #[test]
+    fn validate_chain_continuity() {
+        crate::init().unwrap();
+        let (chain, info, tmp_dir) = create_chain(ChainParams::default());
+        let genesis = chain.get_genesis_block();
+        assert_eq!(genesis.height(), 0);
+        assert_eq!(chain.validate_chain_continuity(100), Ok(()));
+
+        for _ in 0..3 {
+            let mut block = match chain.get_chain_head().as_ref() {
+                Block::V0(head) => head.new_child(vec![]),
+                Block::V1(head) => head.new_child(vec![]),
+            };
+            block.sign(&info.minter_key);
+            chain.insert_block(block).unwrap();
+        }
+        assert_eq!(chain.get_chain_height(), 3);
+        assert_eq!(chain.get_genesis_block(), genesis);
+        assert_eq!(chain.validate_chain_continuity(100), Ok(()));
+        assert_eq!(chain.validate_chain_continuity(0), Ok(()));
+
+        // Bypass verification to store a block that does not link to its predecessor, followed by
+        // a block that correctly links to the tampered block
+        {
+            let mut store = chain.store.lock();
+            let mut batch = WriteBatch::new(chain.indexer());
+            let mut block = match store.get(3).unwrap().as_ref() {
+                Block::V0(head) => head.new_child(vec![]),
+                Block::V1(head) => head.new_child(vec![]),
+            };
+            match &mut block {
+                Block::V0(block) => block.header.previous_hash = double_sha256(&[]),
+                Block::V1(block) => block.header.previous_hash = double_sha256(&[]),
+            }
+            block.sign(&info.minter_key);
+            let mut child = match &block {
+                Block::V0(block) => block.new_child(vec![]),
+                Block::V1(block) => block.new_child(vec![]),
+            };
+            child.sign(&info.minter_key);
+            store.insert(&mut batch, block);
+            store.insert(&mut batch, child);
+            batch.commit().unwrap();
+        }
+
+        assert_eq!(chain.validate_chain_continuity(1), Ok(()));
+        assert_eq!(
+            chain.validate_chain_continuity(2),
+            Err((4, BlockErr::InvalidPrevHash))
+        );
+        assert_eq!(
+            chain.validate_chain_continuity(100),
+            Err((4, BlockErr::InvalidPrevHash))
+        );
+        fs::remove_dir_all(tmp_dir).unwrap();
+    }
+
+    #[test]
+    fn validate_genesis_block() {
+        crate::init().unwrap();
+        let (chain, info, tmp_dir) = create_chain(ChainParams::default());
+        let genesis = chain.get_genesis_block();
+        assert!(Blockchain::is_valid_genesis(&genesis));
+
+        let modify_and_sign = |f: &dyn Fn(&mut BlockV0), key: &KeyPair| {
+            let mut block = genesis.as_ref().clone();
+            match &mut block {
+                Block::V0(block) => f(block),
+                Block::V1(_) => unreachable!(),
+            }
+            block.sign(key);
+            block
+        };
+
+        let block = modify_and_sign(&|_| {}, &info.minter_key);
+        assert!(Blockchain::is_valid_genesis(&block));
+
+        let block = modify_and_sign(&|b| b.header.height = 1, &info.minter_key);
+        assert!(!Blockchain::is_valid_genesis(&block));
+
+        let block = modify_and_sign(
+            &|b| b.header.previous_hash = double_sha256(&[]),
+            &info.minter_key,
+        );
+        assert!(!Blockchain::is_valid_genesis(&block));
+
+        let block = modify_and_sign(&|b| b.receipts.truncate(1), &info.minter_key);
+        assert!(!Blockchain::is_valid_genesis(&block));
+
+        let block = modify_and_sign(&|_| {}, &KeyPair::gen());
+        assert!(!Blockchain::is_valid_genesis(&block));
+        fs::remove_dir_all(tmp_dir).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid owner wallet permissions")]
+    fn genesis_info_from_keys_invalid_threshold() {
+        GenesisBlockInfo::from_keys(seeded_key(0), vec![seeded_key(1).0], 2);
+    }
+
+    #[test]
+    fn warn_on_rejected_block() {
+        use std::fmt;
+        use tracing::{
+            field::{Field, Visit},
+            span, Event, Level, Metadata, Subscriber,
+        };
+
+        type Events = Vec<(Level, Option<u64>)>;
+
+        /// Captures the level and height field of every event.
+        #[derive(Clone, Default)]
+        struct Capture(Arc<Mutex<Events>>);
+
+        struct HeightVisitor(Option<u64>);
+
+        impl Visit for HeightVisitor {
+            fn record_u64(&mut self, field: &Field, value: u64) {
+                if field.name() == "height" {
+                    self.0 = Some(value);
+                }
+            }
+
+            fn record_debug(&mut self, _: &Field, _: &dyn fmt::Debug) {}
+        }
+
+        impl Subscriber for Capture {
+            fn enabled(&self, _: &Metadata) -> bool {
+                true
+            }
+
+            fn new_span(&self, _: &span::Attributes) -> span::Id {
+                span::Id::from_u64(1)
+            }
+
+            fn record(&self, _: &span::Id, _: &span::Record) {}
+
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+            fn event(&self, event: &Event) {
+                let mut visitor = HeightVisitor(None);
+                event.record(&mut visitor);
+                self.0
+                    .lock()
+                    .push((event.metadata().level().clone(), visitor.0));
+            }
+
+            fn enter(&self, _: &span::Id) {}
+
+            fn exit(&self, _: &span::Id) {}
+        }
+
+        crate::init().unwrap();
+        let (chain, info, tmp_dir) = create_chain(ChainParams::default());
+        let capture = Capture::default();
+        tracing::subscriber::with_default(capture.clone(), || {
+            let mut block = match chain.get_chain_head().as_ref() {
+                Block::V0(head) => head.new_child(vec![]),
+                Block::V1(head) => head.new_child(vec![]),
+            };
+            // Signed by a key other than the minter
+            block.sign(&KeyPair::gen());
+            assert_eq!(chain.insert_block(block), Err(BlockErr::InvalidSignature));
+
+            let mut block = match chain.get_chain_head().as_ref() {
+                Block::V0(head) => head.new_child(vec![]),
+                Block::V1(head) => head.new_child(vec![]),
+            };
+            block.sign(&info.minter_key);
+            chain.insert_block(block).unwrap();
+        });
+
+        let events = capture.0.lock();
+        let warnings = events
+            .iter()
+            .filter(|(level, _)| *level == Level::WARN)
+            .collect::<Vec<_>>();
+        assert_eq!(warnings, vec![&(Level::WARN, Some(1))]);
+        assert!(events.contains(&(Level::DEBUG, Some(1))));
+
+        drop(chain);
+        fs::remove_dir_all(tmp_dir).unwrap();
     }
 }
@@ -31,11 +31,34 @@ impl ReceiptPool {
         self.chain.get_account_info(id, &self.receipts)
     }
 
+    #[inline]
+    pub fn get_account_nonce(&self, id: AccountId) -> Option<u32> {
+        self.chain.get_account_nonce(id, &self.receipts)
+    }
+
     pub fn push(
         &mut self,
         data: TxPrecompData,
         skip_flags: skip_flags::SkipFlags,
     ) -> Result<(), TxErr> {
+        let log = self.check(&data, skip_flags)?;
+
+        self.indexer.insert_txid(data.txid(), data.tx().expiry());
+        self.receipts.push(Receipt {
+            tx: data.take(),
+            log,
+        });
+        Ok(())
+    }
+
+    /// Executes the transaction against the chain and the pending receipts without adding it to
+    /// the pool, then checks it against the chain's transaction policy. Returns the log the
+    /// transaction would produce when pushed.
+    pub fn check(
+        &self,
+        data: &TxPrecompData,
+        skip_flags: skip_flags::SkipFlags,
+    ) -> Result<Vec<LogEntry>, TxErr> {
         let current_time = crate::get_epoch_time();
 
         let expiry = data.tx().expiry();
@@ -45,19 +68,28 @@ impl ReceiptPool {
             return Err(TxErr::TxDupe);
         }
 
-        let log = self.chain.execute_tx(&data, &self.receipts, skip_flags)?;
-
-        self.indexer.insert_txid(data.txid(), expiry);
-        self.receipts.push(Receipt {
-            tx: data.take(),
-            log,
-        });
-        Ok(())
+        let log = self.chain.execute_tx(data, &self.receipts, skip_flags)?;
+        self.chain.check_tx_policy(data.tx())?;
+        Ok(log)
     }
 
-    pub fn flush(&mut self) -> Vec<Receipt> {
-        let mut receipts = Vec::with_capacity(DEFAULT_RECEIPT_CAPACITY);
-        mem::swap(&mut receipts, &mut self.receipts);
+    /// Removes the receipts in the order they were pushed until the sum of their serialized
+    /// transaction sizes would exceed `max_byte_size`. The remaining receipts stay in the pool
+    /// for the next block.
+    pub fn flush(&mut self, max_byte_size: usize) -> Vec<Receipt> {
+        let mut byte_size = 0;
+        let len = self
+            .receipts
+            .iter()
+            .take_while(|r| {
+                byte_size += r.tx.byte_size();
+                byte_size <= max_byte_size
+            })
+            .count();
+
+        let mut remaining = self.receipts.split_off(len);
+        remaining.reserve(DEFAULT_RECEIPT_CAPACITY);
+        let receipts = mem::replace(&mut self.receipts, remaining);
         self.indexer.purge_expired_txids();
         receipts
     }
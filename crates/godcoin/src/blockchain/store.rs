@@ -4,7 +4,7 @@ use std::{
     collections::HashMap,
     convert::TryInto,
     fs::{File, OpenOptions},
-    io::{Cursor, Read, Seek, SeekFrom, Write},
+    io::{self, Cursor, Read, Seek, SeekFrom, Write},
     path::Path,
     sync::Arc,
 };
@@ -14,11 +14,88 @@ use crate::blockchain::{block::*, index::*};
 
 const MAX_CACHE_SIZE: u64 = 100;
 
+/// Marks a record whose block bytes are zstd compressed. Uncompressed records begin with the
+/// big-endian block header version, whose first byte is 0 for every known version.
+const COMPRESSED_RECORD: u8 = 0xFF;
+const COMPRESSION_LEVEL: i32 = 3;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct ReindexOpts {
     pub auto_trim: bool,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ReindexErr {
+    /// The index is already complete. Clear the index first to rebuild it.
+    IndexComplete,
+    /// The block log has no blocks to replay.
+    EmptyBlockLog,
+}
+
+/// Storage of the block log. Writes are always appended to the end of the log.
+#[derive(Debug)]
+enum BlockLog {
+    File(File),
+    Mem(Cursor<Vec<u8>>),
+}
+
+impl BlockLog {
+    fn len(&self) -> u64 {
+        match self {
+            BlockLog::File(f) => f.metadata().unwrap().len(),
+            BlockLog::Mem(cur) => cur.get_ref().len() as u64,
+        }
+    }
+
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        match self {
+            BlockLog::File(f) => f.set_len(len),
+            BlockLog::Mem(cur) => {
+                cur.get_mut().truncate(len as usize);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Read for BlockLog {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            BlockLog::File(f) => f.read(buf),
+            BlockLog::Mem(cur) => cur.read(buf),
+        }
+    }
+}
+
+impl Write for BlockLog {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            BlockLog::File(f) => f.write(buf),
+            BlockLog::Mem(cur) => {
+                // Mirror the append mode of the file
+                cur.seek(SeekFrom::End(0))?;
+                cur.write(buf)
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            BlockLog::File(f) => f.flush(),
+            BlockLog::Mem(cur) => cur.flush(),
+        }
+    }
+}
+
+impl Seek for BlockLog {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            BlockLog::File(f) => f.seek(pos),
+            BlockLog::Mem(cur) => cur.seek(pos),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct BlockStore {
     indexer: Arc<Indexer>,
@@ -27,12 +104,17 @@ pub struct BlockStore {
     blocks: HashMap<u64, Arc<Block>>,
     genesis_block: Option<Arc<Block>>,
 
-    file: RefCell<File>,
+    file: RefCell<BlockLog>,
     byte_pos_tail: u64,
+    compress: bool,
 }
 
 impl BlockStore {
-    pub fn new(blocklog_file: &Path, indexer: Arc<Indexer>) -> BlockStore {
+    ///
+    /// Opens the block log. When `compress` is set, newly written blocks are zstd compressed.
+    /// Blocks are read regardless of whether they were written compressed.
+    ///
+    pub fn new(blocklog_file: &Path, indexer: Arc<Indexer>, compress: bool) -> BlockStore {
         let (file, tail) = {
             let f = OpenOptions::new()
                 .create(true)
@@ -43,7 +125,16 @@ impl BlockStore {
             let m = f.metadata().unwrap();
             (f, m.len())
         };
+        Self::with_log(BlockLog::File(file), tail, indexer, compress)
+    }
+
+    /// Creates a block log that is kept in memory. The blocks are lost when the store is dropped.
+    pub fn new_in_memory(indexer: Arc<Indexer>, compress: bool) -> BlockStore {
+        let log = BlockLog::Mem(Cursor::new(Vec::new()));
+        Self::with_log(log, 0, indexer, compress)
+    }
 
+    fn with_log(file: BlockLog, tail: u64, indexer: Arc<Indexer>, compress: bool) -> BlockStore {
         let mut store = BlockStore {
             indexer,
 
@@ -53,6 +144,7 @@ impl BlockStore {
 
             file: RefCell::new(file),
             byte_pos_tail: tail,
+            compress,
         };
 
         store.init_state();
@@ -80,8 +172,7 @@ impl BlockStore {
     }
 
     pub fn is_empty(&self) -> bool {
-        let meta = self.file.borrow().metadata().unwrap();
-        meta.len() == 0
+        self.file.borrow().len() == 0
     }
 
     pub fn insert(&mut self, batch: &mut WriteBatch, block: Block) {
@@ -116,6 +207,16 @@ impl BlockStore {
         batch.set_block_byte_pos(0, 0);
     }
 
+    /// Removes every block starting at `byte_pos` from the block log and reloads the chain height
+    /// from the index. The index must already have been reverted to the block preceding
+    /// `byte_pos`.
+    pub fn truncate(&mut self, byte_pos: u64) {
+        self.file.borrow_mut().set_len(byte_pos).unwrap();
+        self.byte_pos_tail = byte_pos;
+        self.blocks.clear();
+        self.init_state();
+    }
+
     pub fn reindex_blocks<F>(&mut self, opts: ReindexOpts, mut index_fn: F)
     where
         F: FnMut(&mut WriteBatch, &Block),
@@ -135,7 +236,7 @@ impl BlockStore {
                         error!("Invalid height ({}) detected at byte pos {}", height, pos);
                         if opts.auto_trim {
                             warn!("Truncating block log");
-                            let f = self.file.borrow();
+                            let mut f = self.file.borrow_mut();
                             f.set_len(pos).unwrap();
                             self.byte_pos_tail = pos;
                         } else {
@@ -161,7 +262,7 @@ impl BlockStore {
                         );
                         if opts.auto_trim {
                             warn!("Truncating block log");
-                            let f = self.file.borrow();
+                            let mut f = self.file.borrow_mut();
                             f.set_len(pos).unwrap();
                             self.byte_pos_tail = pos;
                             break;
@@ -173,7 +274,7 @@ impl BlockStore {
             }
         }
 
-        batch.commit();
+        batch.commit().expect("Failed to commit index batch");
         self.indexer.set_index_status(IndexStatus::Complete);
         self.init_state();
     }
@@ -183,7 +284,10 @@ impl BlockStore {
             return None;
         }
 
-        let pos = self.indexer.get_block_byte_pos(height)?;
+        let pos = self
+            .indexer
+            .get_block_byte_pos(height)
+            .expect("Failed to retrieve block byte position from index")?;
         self.raw_read_from_disk(pos).ok()
     }
 
@@ -211,6 +315,13 @@ impl BlockStore {
             buf
         };
 
+        let block_vec = match block_vec.split_first() {
+            Some((&COMPRESSED_RECORD, compressed)) => {
+                zstd::decode_all(compressed).map_err(|_| ReadError::CorruptBlock)?
+            }
+            _ => block_vec,
+        };
+
         let mut cursor = Cursor::<&[u8]>::new(&block_vec);
         Block::deserialize(&mut cursor).ok_or(ReadError::CorruptBlock)
     }
@@ -218,6 +329,13 @@ impl BlockStore {
     fn write_to_disk(&mut self, block: &Block) {
         let vec = &mut Vec::with_capacity(1_048_576);
         block.serialize(vec);
+        if self.compress {
+            let mut buf = Vec::with_capacity(vec.len() / 2);
+            buf.push(COMPRESSED_RECORD);
+            zstd::stream::copy_encode(&vec[..], &mut buf, COMPRESSION_LEVEL)
+                .expect("Failed to compress block");
+            *vec = buf;
+        }
         let len = vec.len() as u32;
         let crc = crc32c(vec);
 
@@ -250,7 +368,10 @@ impl BlockStore {
     }
 
     fn init_state(&mut self) {
-        self.height = self.indexer.get_chain_height();
+        self.height = self
+            .indexer
+            .get_chain_height()
+            .expect("Failed to retrieve chain height from index");
         self.genesis_block = self.get(0);
         if !self.is_empty() && self.indexer.index_status() == IndexStatus::Complete {
             // Init block cache
@@ -272,3 +393,140 @@ pub enum ReadError {
     Eof,
     CorruptBlock,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        asset::Asset,
+        blockchain::receipt::Receipt,
+        crypto::{Digest, KeyPair},
+        tx::*,
+    };
+    use sodiumoxide::randombytes;
+    use std::{env, fs, path::PathBuf};
+
+    fn create_tmp_dir() -> PathBuf {
+        let mut tmp_dir = env::temp_dir();
+        let mut num: [u8; 8] = [0; 8];
+        randombytes::randombytes_into(&mut num);
+        tmp_dir.push(format!("godcoin_test_{}", u64::from_be_bytes(num)));
+        fs::create_dir(&tmp_dir).expect("Could not create temp dir");
+        tmp_dir
+    }
+
+    fn open_store(dir: &Path, compress: bool) -> BlockStore {
+        let indexer = Arc::new(Indexer::new(&Path::join(dir, "index")));
+        BlockStore::new(&Path::join(dir, "blocklog"), indexer, compress)
+    }
+
+    fn create_blocks(count: u64) -> Vec<Block> {
+        let key = KeyPair::gen();
+        let mut genesis = Block::V0(BlockV0 {
+            header: BlockHeaderV0 {
+                previous_hash: Digest::from_slice(&[0; 32]).unwrap(),
+                height: 0,
+                timestamp: 0,
+                receipt_root: calc_receipt_root(&[]),
+            },
+            signer: None,
+            rewards: Asset::default(),
+            receipts: vec![],
+        });
+        genesis.sign(&key);
+
+        let mut blocks = vec![genesis];
+        for _ in 1..count {
+            // Attachments are highly compressible
+            let receipts = vec![Receipt {
+                tx: TxVariant::V0(TxVariantV0::MintTx(MintTx {
+                    base: Tx {
+                        nonce: 0,
+                        expiry: 0,
+                        fee: Asset::default(),
+                        signature_pairs: vec![],
+                    },
+                    to: 0,
+                    amount: Asset::new(100),
+                    attachment: vec![0; 4096],
+                    attachment_name: "".to_string(),
+                })),
+                log: vec![],
+            }];
+            let mut block = match blocks.last().unwrap() {
+                Block::V0(block) => block.new_child(receipts),
+                Block::V1(block) => block.new_child(receipts),
+            };
+            block.sign(&key);
+            blocks.push(block);
+        }
+        blocks
+    }
+
+    fn write_blocks(store: &mut BlockStore, blocks: &[Block]) {
+        let mut batch = WriteBatch::new(Arc::clone(&store.indexer));
+        for block in blocks {
+            if block.height() == 0 {
+                store.insert_genesis(&mut batch, block.clone());
+            } else {
+                store.insert(&mut batch, block.clone());
+            }
+        }
+        batch.commit().unwrap();
+        store.indexer.set_index_status(IndexStatus::Complete);
+    }
+
+    fn assert_blocks_on_disk(store: &BlockStore, blocks: &[Block]) {
+        assert_eq!(store.get_chain_height(), blocks.len() as u64 - 1);
+        for block in blocks {
+            let disk_block = store.read_from_disk(block.height()).unwrap();
+            assert_eq!(&disk_block, block);
+        }
+    }
+
+    #[test]
+    fn compressed_blocks_round_trip() {
+        let blocks = create_blocks(5);
+
+        let compressed_dir = create_tmp_dir();
+        write_blocks(&mut open_store(&compressed_dir, true), &blocks);
+        // Reopening without compression must still read the compressed blocks
+        let store = open_store(&compressed_dir, false);
+        assert_blocks_on_disk(&store, &blocks);
+        let compressed_len = store.byte_pos_tail;
+
+        let uncompressed_dir = create_tmp_dir();
+        write_blocks(&mut open_store(&uncompressed_dir, false), &blocks);
+        let store = open_store(&uncompressed_dir, true);
+        assert_blocks_on_disk(&store, &blocks);
+        assert!(compressed_len < store.byte_pos_tail);
+
+        fs::remove_dir_all(compressed_dir).unwrap();
+        fs::remove_dir_all(uncompressed_dir).unwrap();
+    }
+
+    #[test]
+    fn mixed_compression_block_log() {
+        let blocks = create_blocks(6);
+        let dir = create_tmp_dir();
+
+        write_blocks(&mut open_store(&dir, false), &blocks[0..2]);
+        write_blocks(&mut open_store(&dir, true), &blocks[2..4]);
+        write_blocks(&mut open_store(&dir, false), &blocks[4..]);
+
+        let mut store = open_store(&dir, true);
+        assert_blocks_on_disk(&store, &blocks);
+
+        // Reindexing walks the record boundaries of both record types
+        store.indexer.set_index_status(IndexStatus::Partial);
+        store.indexer.clear();
+        let mut heights = vec![];
+        store.reindex_blocks(ReindexOpts { auto_trim: false }, |_, block| {
+            heights.push(block.height());
+        });
+        assert_eq!(heights, (0..6).collect::<Vec<_>>());
+        assert_blocks_on_disk(&store, &blocks);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,94 @@
+use super::Block;
+use parking_lot::{Condvar, Mutex};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+/// Maximum number of undelivered blocks a subscriber holds before the oldest is dropped.
+pub const MAX_PENDING_BLOCKS: usize = 64;
+
+#[derive(Debug, Default)]
+struct State {
+    pending: VecDeque<Arc<Block>>,
+    closed: bool,
+}
+
+#[derive(Debug, Default)]
+struct Queue {
+    state: Mutex<State>,
+    cond: Condvar,
+}
+
+/// Receives every block inserted into the chain after the subscription was created.
+#[derive(Debug)]
+pub struct BlockReceiver(Arc<Queue>);
+
+impl BlockReceiver {
+    /// Blocks until the next block is available. Returns `None` once the chain is dropped and all
+    /// pending blocks have been received.
+    pub fn recv(&self) -> Option<Arc<Block>> {
+        let mut state = self.0.state.lock();
+        loop {
+            if let Some(block) = state.pending.pop_front() {
+                return Some(block);
+            } else if state.closed {
+                return None;
+            }
+            self.0.cond.wait(&mut state);
+        }
+    }
+
+    /// Waits up to `timeout` for the next block to become available.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Arc<Block>> {
+        let mut state = self.0.state.lock();
+        if state.pending.is_empty() && !state.closed {
+            self.0.cond.wait_for(&mut state, timeout);
+        }
+        state.pending.pop_front()
+    }
+
+    pub fn try_recv(&self) -> Option<Arc<Block>> {
+        self.0.state.lock().pending.pop_front()
+    }
+}
+
+#[derive(Debug, Default)]
+pub(super) struct Subscribers(Mutex<Vec<Weak<Queue>>>);
+
+impl Subscribers {
+    pub fn subscribe(&self) -> BlockReceiver {
+        let queue = Arc::new(Queue::default());
+        self.0.lock().push(Arc::downgrade(&queue));
+        BlockReceiver(queue)
+    }
+
+    /// Delivers the `block` to every live subscriber without blocking on slow receivers.
+    pub fn notify(&self, block: &Arc<Block>) {
+        let mut subs = self.0.lock();
+        subs.retain(|sub| match sub.upgrade() {
+            Some(queue) => {
+                let mut state = queue.state.lock();
+                if state.pending.len() >= MAX_PENDING_BLOCKS {
+                    state.pending.pop_front();
+                }
+                state.pending.push_back(Arc::clone(block));
+                queue.cond.notify_one();
+                true
+            }
+            None => false,
+        });
+    }
+}
+
+impl Drop for Subscribers {
+    fn drop(&mut self) {
+        for sub in self.0.lock().iter() {
+            if let Some(queue) = sub.upgrade() {
+                queue.state.lock().closed = true;
+                queue.cond.notify_all();
+            }
+        }
+    }
+}
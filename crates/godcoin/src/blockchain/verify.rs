@@ -0,0 +1,150 @@
+use super::TxErr;
+use crate::{
+    constants::{MAX_MEMO_BYTE_SIZE, MAX_SCRIPT_BYTE_SIZE, MAX_TX_SIGNATURES},
+    tx::{TxVariant, TxVariantV0},
+};
+use std::fmt;
+
+/// Local admission rules imposed on top of the consensus rules. The policy is checked when a
+/// transaction is submitted to the node, transactions included in blocks are not subject to it.
+pub trait TxPolicy: fmt::Debug + Send + Sync {
+    fn check(&self, tx: &TxVariant) -> Result<(), TxErr>;
+}
+
+/// Checks the size limits of a transaction that can be verified without any chain state. These
+/// limits are checked before the transaction is executed against the chain.
+pub fn check_tx_limits(tx: &TxVariant) -> Result<(), TxErr> {
+    match collect_tx_limit_errs(tx).first() {
+        Some(err) => Err(*err),
+        None => Ok(()),
+    }
+}
+
+/// Same as `check_tx_limits` except every exceeded limit is returned.
+pub fn collect_tx_limit_errs(tx: &TxVariant) -> Vec<TxErr> {
+    let mut errs = Vec::new();
+    if tx.sigs().len() > MAX_TX_SIGNATURES {
+        errs.push(TxErr::TooManySignatures);
+    }
+
+    let script_too_large = match tx.script() {
+        Some(script) => script.len() > MAX_SCRIPT_BYTE_SIZE,
+        None => false,
+    };
+    let memo_too_large = match tx {
+        TxVariant::V0(TxVariantV0::TransferTx(tx)) => tx.memo.len() > MAX_MEMO_BYTE_SIZE,
+        _ => false,
+    };
+    let too_large = script_too_large || memo_too_large;
+    if too_large {
+        errs.push(TxErr::TxTooLarge);
+    }
+
+    errs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        account::{Account, Permissions},
+        asset::Asset,
+        crypto::KeyPair,
+        tx::*,
+    };
+
+    fn base_tx() -> Tx {
+        Tx {
+            nonce: 0,
+            expiry: 1,
+            fee: Asset::new(100),
+            signature_pairs: vec![],
+        }
+    }
+
+    fn transfer_tx(memo_len: usize) -> TxVariant {
+        TxVariant::V0(TxVariantV0::TransferTx(TransferTx {
+            base: base_tx(),
+            from: 0,
+            call_fn: 0,
+            args: vec![],
+            amount: Asset::default(),
+            memo: vec![0; memo_len],
+        }))
+    }
+
+    #[test]
+    fn too_many_signatures() {
+        let mut tx = transfer_tx(0);
+        let key = KeyPair::gen();
+        for _ in 0..MAX_TX_SIGNATURES {
+            tx.append_sign(&key);
+        }
+        assert_eq!(check_tx_limits(&tx), Ok(()));
+
+        tx.append_sign(&key);
+        assert_eq!(check_tx_limits(&tx), Err(TxErr::TooManySignatures));
+    }
+
+    #[test]
+    fn oversized_script() {
+        let create_tx = |script_len: usize| {
+            let mut account = Account::create_default(
+                1,
+                Permissions {
+                    threshold: 1,
+                    keys: vec![KeyPair::gen().0],
+                },
+            );
+            account.script = vec![0; script_len].into();
+            TxVariant::V0(TxVariantV0::CreateAccountTx(CreateAccountTx {
+                base: base_tx(),
+                creator: 0,
+                account,
+            }))
+        };
+        assert_eq!(check_tx_limits(&create_tx(MAX_SCRIPT_BYTE_SIZE)), Ok(()));
+        assert_eq!(
+            check_tx_limits(&create_tx(MAX_SCRIPT_BYTE_SIZE + 1)),
+            Err(TxErr::TxTooLarge)
+        );
+
+        let update_tx = |script_len: usize| {
+            TxVariant::V0(TxVariantV0::UpdateAccountTx(UpdateAccountTx {
+                base: base_tx(),
+                account_id: 1,
+                new_script: Some(vec![0; script_len].into()),
+                new_permissions: None,
+            }))
+        };
+        assert_eq!(check_tx_limits(&update_tx(MAX_SCRIPT_BYTE_SIZE)), Ok(()));
+        assert_eq!(
+            check_tx_limits(&update_tx(MAX_SCRIPT_BYTE_SIZE + 1)),
+            Err(TxErr::TxTooLarge)
+        );
+    }
+
+    #[test]
+    fn collect_limit_errs() {
+        let mut tx = transfer_tx(MAX_MEMO_BYTE_SIZE + 1);
+        let key = KeyPair::gen();
+        for _ in 0..=MAX_TX_SIGNATURES {
+            tx.append_sign(&key);
+        }
+        assert_eq!(
+            collect_tx_limit_errs(&tx),
+            vec![TxErr::TooManySignatures, TxErr::TxTooLarge]
+        );
+        assert_eq!(check_tx_limits(&tx), Err(TxErr::TooManySignatures));
+        assert!(collect_tx_limit_errs(&transfer_tx(0)).is_empty());
+    }
+
+    #[test]
+    fn oversized_memo() {
+        assert_eq!(check_tx_limits(&transfer_tx(MAX_MEMO_BYTE_SIZE)), Ok(()));
+        assert_eq!(
+            check_tx_limits(&transfer_tx(MAX_MEMO_BYTE_SIZE + 1)),
+            Err(TxErr::TxTooLarge)
+        );
+    }
+}
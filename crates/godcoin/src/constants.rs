@@ -10,13 +10,25 @@ pub const GRAEL_ACC_CREATE_MIN_BAL_MULT: Asset = Asset::new(200_000);
 
 pub const NETWORK_FEE_AVG_WINDOW: u64 = 10;
 pub const FEE_RESET_WINDOW: usize = 4;
+pub const SUPPLY_CHECKPOINT_INTERVAL: u64 = 1000;
+pub const MAX_ROLLBACK_DEPTH: u64 = 1000;
 
 pub const TX_MAX_EXPIRY_TIME: u64 = 60 * 60 * 24 * 30;
 pub const BLOCK_PROD_TIME: u64 = 3;
+pub const MAX_BLOCK_TIMESTAMP_SKEW: u64 = 120;
 
 pub const MAX_MEMO_BYTE_SIZE: usize = 1024;
 pub const MAX_SCRIPT_BYTE_SIZE: usize = 2048;
+pub const MAX_SCRIPT_STEPS: u32 = 10_000;
 pub const MAX_TX_SIGNATURES: usize = 8;
+pub const MAX_BLOCK_BYTE_SIZE: usize = 4 * 1024 * 1024;
+
+// Length caps applied when decoding variable length fields. These only guard against oversized
+// allocations and are looser than the limits above so that oversized fields are still reported
+// as validation errors.
+pub const MAX_SCRIPT_DECODE_SIZE: usize = MAX_SCRIPT_BYTE_SIZE * 2;
+pub const MAX_MEMO_DECODE_SIZE: usize = MAX_MEMO_BYTE_SIZE * 2;
+pub const MAX_FIELD_DECODE_SIZE: usize = 1024 * 1024;
 
 #[cfg(not(any(test, feature = "testnet")))]
 pub const CHAIN_ID: [u8; 2] = [0x00, 0x00];
@@ -24,6 +36,14 @@ pub const CHAIN_ID: [u8; 2] = [0x00, 0x00];
 #[cfg(any(test, feature = "testnet"))]
 pub const CHAIN_ID: [u8; 2] = [0x00, 0x01];
 
+// Height of the first block whose transfers must use increasing nonces. Transfers on mainnet were
+// created with random nonces and the check is not scheduled there yet.
+#[cfg(not(any(test, feature = "testnet")))]
+pub const NONCE_CHECK_HEIGHT: u64 = u64::MAX;
+
+#[cfg(any(test, feature = "testnet"))]
+pub const NONCE_CHECK_HEIGHT: u64 = 0;
+
 mod tests {
     #[allow(unused_imports)]
     use super::*;
@@ -12,11 +12,28 @@ impl PublicKey {
         sign::verify_detached(&sig.0, msg, &self.0)
     }
 
+    /// Verifies a detached signature over an arbitrary message created with `KeyPair::sign_msg`.
+    #[inline]
+    pub fn verify_msg(&self, msg: &[u8], sig: &Signature) -> bool {
+        self.verify(msg, sig)
+    }
+
     #[inline]
     pub fn from_slice(bytes: &[u8]) -> Option<PublicKey> {
         let key = sign::PublicKey::from_slice(bytes)?;
         Some(PublicKey(key))
     }
+
+    /// Returns the raw key bytes, as written by `BufWrite::push_pub_key`.
+    #[inline]
+    pub fn to_bytes(&self) -> [u8; sign::PUBLICKEYBYTES] {
+        (self.0).0
+    }
+
+    #[inline]
+    pub fn from_bytes(bytes: &[u8; sign::PUBLICKEYBYTES]) -> PublicKey {
+        PublicKey(sign::PublicKey(*bytes))
+    }
 }
 
 impl AsRef<[u8]> for PublicKey {
@@ -64,6 +81,13 @@ impl KeyPair {
         }
     }
 
+    /// Creates a detached signature over an arbitrary message, such as a challenge that proves
+    /// ownership of the key. The returned pair can be checked with `SigPair::verify`.
+    #[inline]
+    pub fn sign_msg(&self, msg: &[u8]) -> SigPair {
+        self.sign(msg)
+    }
+
     #[inline]
     pub fn verify(&self, msg: &[u8], sig: &Signature) -> bool {
         PublicKey::verify(&self.0, msg, sig)
@@ -81,6 +105,7 @@ impl KeyPair {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::serializer::BufWrite;
 
     #[test]
     fn sign_message() {
@@ -100,4 +125,45 @@ mod tests {
         let kp = KeyPair::gen();
         assert!(!kp.verify(msg, &sig));
     }
+
+    #[test]
+    fn public_key_bytes_round_trip() {
+        for _ in 0..100 {
+            let key = KeyPair::gen().0;
+            let bytes = key.to_bytes();
+            assert_eq!(PublicKey::from_bytes(&bytes), key);
+            assert_eq!(PublicKey::from_slice(&bytes), Some(key.clone()));
+
+            let mut buf = vec![];
+            buf.push_pub_key(&key);
+            assert_eq!(buf, bytes);
+        }
+    }
+
+    #[test]
+    fn sign_arbitrary_messages() {
+        let kp = KeyPair::gen();
+        let large = {
+            let mut buf = vec![0; 1024 * 1024];
+            randombytes::randombytes_into(&mut buf);
+            buf
+        };
+
+        for msg in &[&[][..], b"challenge", &large] {
+            let pair = kp.sign_msg(msg);
+            assert_eq!(pair.pub_key, kp.0);
+            assert!(pair.verify(msg));
+            assert!(kp.0.verify_msg(msg, &pair.signature));
+
+            // Signatures do not verify against a different message
+            let mut other = msg.to_vec();
+            other.push(0);
+            assert!(!pair.verify(&other));
+            assert!(!kp.0.verify_msg(&other, &pair.signature));
+        }
+
+        // Signatures do not verify against a different key
+        let pair = kp.sign_msg(&large);
+        assert!(!KeyPair::gen().0.verify_msg(&large, &pair.signature));
+    }
 }
@@ -0,0 +1,81 @@
+use sodiumoxide::crypto::{box_, sealedbox};
+
+use super::key::{PrivateKey, PublicKey};
+
+/// Number of bytes a sealed memo adds to the plaintext.
+pub const SEALED_MEMO_OVERHEAD: usize = sealedbox::SEALBYTES;
+
+/// Encrypts the memo so that only the owner of the `recipient` key can read it. The sender is
+/// anonymous and cannot decrypt the memo after it is sealed. Returns `None` if the public key is
+/// not a valid curve point.
+pub fn seal_memo(plaintext: &[u8], recipient: &PublicKey) -> Option<Vec<u8>> {
+    let pk = to_curve25519_pk(recipient)?;
+    Some(sealedbox::seal(plaintext, &pk))
+}
+
+/// Decrypts a memo sealed with `seal_memo` to the public key of `recipient_secret`. Returns `None`
+/// if the memo was sealed to another key or has been tampered with.
+pub fn open_memo(ciphertext: &[u8], recipient_secret: &PrivateKey) -> Option<Vec<u8>> {
+    // The ed25519 secret key holds the public key in its last 32 bytes
+    let recipient = PublicKey::from_slice(&recipient_secret.key.0[32..])?;
+    let pk = to_curve25519_pk(&recipient)?;
+    let sk = to_curve25519_sk(recipient_secret);
+    sealedbox::open(ciphertext, &pk, &sk).ok()
+}
+
+fn to_curve25519_pk(key: &PublicKey) -> Option<box_::PublicKey> {
+    let mut buf = [0; box_::PUBLICKEYBYTES];
+    let res = unsafe {
+        libsodium_sys::crypto_sign_ed25519_pk_to_curve25519(
+            buf.as_mut_ptr(),
+            key.0.as_ref().as_ptr(),
+        )
+    };
+    if res == 0 {
+        Some(box_::PublicKey(buf))
+    } else {
+        None
+    }
+}
+
+fn to_curve25519_sk(key: &PrivateKey) -> box_::SecretKey {
+    let mut buf = [0; box_::SECRETKEYBYTES];
+    unsafe {
+        libsodium_sys::crypto_sign_ed25519_sk_to_curve25519(buf.as_mut_ptr(), key.key.0.as_ptr());
+    }
+    let sk = box_::SecretKey(buf);
+    sodiumoxide::utils::memzero(&mut buf);
+    sk
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::KeyPair;
+
+    #[test]
+    fn seal_and_open_memo() {
+        let recipient = KeyPair::gen();
+        for memo in &[&b""[..], b"hello world", &[0xFF; 1024]] {
+            let sealed = seal_memo(memo, &recipient.0).unwrap();
+            assert_eq!(sealed.len(), memo.len() + SEALED_MEMO_OVERHEAD);
+            assert_eq!(open_memo(&sealed, &recipient.1).as_deref(), Some(*memo));
+        }
+    }
+
+    #[test]
+    fn open_memo_with_wrong_key() {
+        let recipient = KeyPair::gen();
+        let other = KeyPair::gen();
+        let sealed = seal_memo(b"hello world", &recipient.0).unwrap();
+        assert_eq!(open_memo(&sealed, &other.1), None);
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(open_memo(&tampered, &recipient.1), None);
+        assert_eq!(
+            open_memo(&sealed[..SEALED_MEMO_OVERHEAD - 1], &recipient.1),
+            None
+        );
+    }
+}
@@ -1,14 +1,17 @@
 use sodiumoxide::crypto::{hash::sha256, sign};
 use std::{
-    fmt::{self, Debug, Formatter},
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
     ops::Deref,
 };
 
 pub mod key;
+pub mod memo;
 pub mod sigpair;
 pub mod wif;
 
 pub use self::key::*;
+pub use self::memo::*;
 pub use self::sigpair::*;
 pub use self::wif::*;
 
@@ -55,6 +58,47 @@ macro_rules! impl_wrapper {
 impl_wrapper!(Digest, sha256::Digest);
 impl_wrapper!(Signature, sign::Signature);
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DigestHexError {
+    InvalidLen,
+    InvalidHex,
+}
+
+impl Error for DigestHexError {}
+
+impl Display for DigestHexError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let desc = match self {
+            DigestHexError::InvalidLen => "invalid digest length",
+            DigestHexError::InvalidHex => "invalid hex encoding",
+        };
+        write!(f, "{}", desc)
+    }
+}
+
+impl Digest {
+    /// Decodes a digest from a hex string that must be exactly `DIGEST_BYTES * 2` characters.
+    pub fn from_hex(hex: &str) -> Result<Self, DigestHexError> {
+        if hex.len() != DIGEST_BYTES * 2 {
+            return Err(DigestHexError::InvalidLen);
+        }
+        let mut buf = [0; DIGEST_BYTES];
+        faster_hex::hex_decode(hex.as_bytes(), &mut buf).map_err(|_| DigestHexError::InvalidHex)?;
+        Ok(Digest(sha256::Digest(buf)))
+    }
+
+    #[inline]
+    pub fn to_hex(&self) -> String {
+        faster_hex::hex_string(self.as_ref()).unwrap()
+    }
+}
+
+impl Display for Digest {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
 #[inline]
 pub fn double_sha256(buf: &[u8]) -> Digest {
     Digest(sha256::hash(sha256::hash(buf).as_ref()))
@@ -97,4 +141,34 @@ mod test {
         };
         assert_eq!(digest_a, digest_b);
     }
+
+    #[test]
+    fn digest_hex_round_trip() {
+        for i in 0..100u32 {
+            let digest = double_sha256(&i.to_be_bytes());
+            let hex = digest.to_hex();
+            assert_eq!(hex.len(), DIGEST_BYTES * 2);
+            assert_eq!(hex, digest.to_string());
+            assert_eq!(Digest::from_hex(&hex), Ok(digest));
+        }
+    }
+
+    #[test]
+    fn digest_hex_rejects_invalid_input() {
+        let hex = double_sha256(&[1, 2, 3]).to_hex();
+        assert_eq!(Digest::from_hex(""), Err(DigestHexError::InvalidLen));
+        assert_eq!(
+            Digest::from_hex(&hex[..hex.len() - 2]),
+            Err(DigestHexError::InvalidLen)
+        );
+        assert_eq!(
+            Digest::from_hex(&format!("{}00", hex)),
+            Err(DigestHexError::InvalidLen)
+        );
+
+        let invalid = format!("zz{}", &hex[2..]);
+        assert_eq!(Digest::from_hex(&invalid), Err(DigestHexError::InvalidHex));
+        let invalid = format!("{}g", &hex[1..]);
+        assert_eq!(Digest::from_hex(&invalid), Err(DigestHexError::InvalidHex));
+    }
 }
@@ -1,5 +1,13 @@
 use super::{PublicKey, Signature};
 use sodiumoxide::crypto::sign;
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// Number of bytes of a signature pair encoded as the public key followed by the signature.
+pub const SIG_PAIR_BYTES: usize = sign::PUBLICKEYBYTES + sign::SIGNATUREBYTES;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SigPair {
@@ -13,3 +21,87 @@ impl SigPair {
         sign::verify_detached(&self.signature.0, msg, &self.pub_key.0)
     }
 }
+
+impl Display for SigPair {
+    /// Formats the pair as the base64 encoding of the public key followed by the signature.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut buf = Vec::with_capacity(SIG_PAIR_BYTES);
+        buf.extend_from_slice(self.pub_key.as_ref());
+        buf.extend_from_slice(self.signature.as_ref());
+        f.write_str(&base64::encode(&buf))
+    }
+}
+
+impl FromStr for SigPair {
+    type Err = SigPairParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let buf = base64::decode(s).map_err(|_| SigPairParseError::InvalidBase64)?;
+        if buf.len() != SIG_PAIR_BYTES {
+            return Err(SigPairParseError::InvalidLen);
+        }
+        let (pub_key, signature) = buf.split_at(sign::PUBLICKEYBYTES);
+        Ok(SigPair {
+            pub_key: PublicKey::from_slice(pub_key).unwrap(),
+            signature: Signature::from_slice(signature).unwrap(),
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SigPairParseError {
+    InvalidLen,
+    InvalidBase64,
+}
+
+impl Error for SigPairParseError {}
+
+impl Display for SigPairParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let desc = match self {
+            SigPairParseError::InvalidLen => "invalid signature pair length",
+            SigPairParseError::InvalidBase64 => "invalid base64 encoding",
+        };
+        write!(f, "{}", desc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::KeyPair;
+
+    #[test]
+    fn sig_pair_string_round_trip() {
+        for i in 0..100u32 {
+            let msg = i.to_be_bytes();
+            let pair = KeyPair::gen().sign(&msg);
+            let s = pair.to_string();
+            let decoded: SigPair = s.parse().unwrap();
+            assert_eq!(decoded, pair);
+            assert!(decoded.verify(&msg));
+        }
+    }
+
+    #[test]
+    fn sig_pair_string_rejects_invalid_input() {
+        let s = KeyPair::gen().sign(b"hello").to_string();
+        assert_eq!(
+            s[..s.len() - 4].parse::<SigPair>(),
+            Err(SigPairParseError::InvalidLen)
+        );
+        assert_eq!("".parse::<SigPair>(), Err(SigPairParseError::InvalidLen));
+        assert_eq!(
+            s[..s.len() - 1].parse::<SigPair>(),
+            Err(SigPairParseError::InvalidLen)
+        );
+        assert_eq!(
+            format!("{}A", s).parse::<SigPair>(),
+            Err(SigPairParseError::InvalidBase64)
+        );
+        assert_eq!(
+            format!("!{}", &s[1..]).parse::<SigPair>(),
+            Err(SigPairParseError::InvalidBase64)
+        );
+    }
+}
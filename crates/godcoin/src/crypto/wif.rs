@@ -50,6 +50,23 @@ impl Display for WifError {
     }
 }
 
+/// A key decoded from a WIF without knowing its type ahead of time.
+#[derive(Clone, Debug)]
+pub enum WifKey {
+    Public(PublicKey),
+    Private(KeyPair),
+}
+
+/// Decodes a public or private key WIF. The key type is determined by the public address prefix
+/// and verified against the version byte.
+pub fn decode_wif(s: &str) -> Result<WifKey, WifError> {
+    if s.starts_with(PUB_ADDRESS_PREFIX) {
+        PublicKey::from_wif(s).map(WifKey::Public)
+    } else {
+        PrivateKey::from_wif(s).map(WifKey::Private)
+    }
+}
+
 impl Wif<AccountId, Box<str>> for AccountId {
     fn from_wif(s: &str) -> Result<AccountId, WifError> {
         if s.len() < 3 || &s[0..3] != PUB_ADDRESS_PREFIX {
@@ -230,6 +247,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn decode_any_wif() {
+        let kp = KeyPair::gen();
+        match decode_wif(&kp.0.to_wif()).unwrap() {
+            WifKey::Public(dec) => assert_eq!(dec, kp.0),
+            key => panic!("expected a public key: {:?}", key),
+        }
+        match decode_wif(&kp.1.to_wif()).unwrap() {
+            WifKey::Private(dec) => assert_eq!(dec.0, kp.0),
+            key => panic!("expected a private key: {:?}", key),
+        }
+
+        let mut bytes = bs58::decode("3GAD3otqozDorfu1iDpMQJ1gzWp8PRFEjVHZivZdedKW3i3KtM")
+            .into_vec()
+            .unwrap();
+        bytes[0] = PUB_BUF_PREFIX;
+        let wif = bs58::encode(bytes).into_string();
+        assert_eq!(
+            decode_wif(&wif).unwrap_err().kind,
+            WifErrorKind::InvalidPrefix
+        );
+        assert_eq!(
+            decode_wif("GODFVarNr3nEqUnvquCn").unwrap_err().kind,
+            WifErrorKind::InvalidLen
+        );
+    }
+
     #[test]
     fn import_account_id_from_wif() {
         assert_eq!(AccountId::from_wif("GODFVarNr3nEqUnvquCn"), Ok(0));
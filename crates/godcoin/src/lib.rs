@@ -30,13 +30,14 @@ pub mod prelude {
     pub use super::asset::{self, Asset, AssetError, AssetErrorKind};
     pub use super::blockchain::{
         self, index::IndexStatus, AccountInfo, Block, BlockFilter, BlockHeader, BlockHeaderV0,
-        BlockV0, Blockchain, FilteredBlock, LogEntry, Properties, Receipt, ReceiptPool,
+        BlockV0, Blockchain, ChainParams, FilteredBlock, LogEntry, NodeOptions, Properties,
+        Receipt, ReceiptPool,
     };
     pub use super::crypto::{
         DoubleSha256, KeyPair, PrivateKey, PublicKey, SigPair, Wif, WifError, WifErrorKind,
     };
     pub use super::net::{self, rpc, Body, Msg};
-    pub use super::script::{self, OpFrame, Script, ScriptEngine};
+    pub use super::script::{self, OpFrame, Script, ScriptEngine, ScriptHash};
     pub use super::serializer::{BufRead, BufWrite};
     pub use super::tx::{
         CreateAccountTx, MintTx, OwnerTx, TransferTx, Tx, TxId, TxPrecompData, TxVariant,
@@ -12,7 +12,11 @@ pub struct Msg {
 }
 
 impl Msg {
+    /// Serializes the message prefixed with a `u32` frame length. The frame length is the amount
+    /// of bytes following the prefix.
     pub fn serialize(&self, buf: &mut Vec<u8>) {
+        let frame_pos = buf.len();
+        buf.push_u32(0);
         buf.push_u32(self.id);
         match &self.body {
             Body::Error(e) => {
@@ -38,9 +42,23 @@ impl Msg {
                 buf.push_u64(*nonce);
             }
         }
+
+        let frame_len = (buf.len() - frame_pos - 4) as u32;
+        buf[frame_pos..frame_pos + 4].copy_from_slice(&frame_len.to_be_bytes());
     }
 
+    /// Deserializes a message after validating the frame length matches the remaining bytes in
+    /// the `cursor`.
     pub fn deserialize(cursor: &mut Cursor<&[u8]>) -> io::Result<Self> {
+        let frame_len = u64::from(cursor.take_u32()?);
+        let remaining = cursor.get_ref().len() as u64 - cursor.position();
+        if frame_len != remaining {
+            return Err(Error::new(
+                io::ErrorKind::InvalidData,
+                "frame length mismatch",
+            ));
+        }
+
         let id = cursor.take_u32()?;
         let body = match cursor.take_u8()? {
             t if t == BodyType::Error as u8 => Body::Error(ErrorKind::deserialize(cursor)?),
@@ -87,6 +105,8 @@ pub enum ErrorKind {
     InvalidRequest,
     InvalidHeight,
     TxValidation(TxErr),
+    /// The request took too long to be processed.
+    Timeout,
 }
 
 impl ErrorKind {
@@ -101,6 +121,7 @@ impl ErrorKind {
                 buf.push(0x04);
                 err.serialize(buf);
             }
+            Self::Timeout => buf.push(0x05),
         }
     }
 
@@ -112,6 +133,7 @@ impl ErrorKind {
             0x02 => Self::InvalidRequest,
             0x03 => Self::InvalidHeight,
             0x04 => Self::TxValidation(TxErr::deserialize(cursor)?),
+            0x05 => Self::Timeout,
             _ => {
                 return Err(io::Error::new(
                     io::ErrorKind::InvalidData,
@@ -121,3 +143,97 @@ impl ErrorKind {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::*,
+        script::{EvalErr, EvalErrKind},
+    };
+
+    #[test]
+    fn serialize_error_kinds() {
+        let kinds = vec![
+            ErrorKind::Io,
+            ErrorKind::BytesRemaining,
+            ErrorKind::InvalidRequest,
+            ErrorKind::InvalidHeight,
+            ErrorKind::TxValidation(TxErr::Arithmetic),
+            ErrorKind::TxValidation(TxErr::ScriptEval(EvalErr::new(
+                42,
+                EvalErrKind::ScriptRetFalse,
+            ))),
+            ErrorKind::Timeout,
+        ];
+
+        for kind in kinds {
+            let msg = Msg {
+                id: 123,
+                body: Body::Error(kind),
+            };
+            let mut buf = Vec::new();
+            msg.serialize(&mut buf);
+
+            let mut cur = Cursor::<&[u8]>::new(&buf);
+            let dec = Msg::deserialize(&mut cur).unwrap();
+            assert_eq!(cur.position(), buf.len() as u64);
+            assert_eq!(msg, dec);
+        }
+    }
+
+    #[test]
+    fn deserialize_frame_length_mismatch() {
+        let msg = Msg {
+            id: 123,
+            body: Body::Response(rpc::Response::GetProperties(Properties {
+                height: 1,
+                owner: Box::new(TxVariant::V0(TxVariantV0::OwnerTx(OwnerTx {
+                    base: Tx {
+                        nonce: 0,
+                        expiry: 1,
+                        fee: Asset::default(),
+                        signature_pairs: vec![],
+                    },
+                    minter: KeyPair::gen().0,
+                    wallet: 0,
+                }))),
+                network_fee: Asset::new(100),
+                token_supply: Asset::new(1000),
+            })),
+        };
+        let mut buf = Vec::new();
+        msg.serialize(&mut buf);
+
+        {
+            let mut cur = Cursor::<&[u8]>::new(&buf);
+            assert_eq!(Msg::deserialize(&mut cur).unwrap(), msg);
+            assert_eq!(cur.position(), buf.len() as u64);
+        }
+
+        {
+            let buf = &buf[..buf.len() - 1];
+            let mut cur = Cursor::<&[u8]>::new(buf);
+            let err = Msg::deserialize(&mut cur).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert_eq!(err.to_string(), "frame length mismatch");
+        }
+
+        {
+            let mut buf = buf.clone();
+            buf.push(0);
+            let mut cur = Cursor::<&[u8]>::new(&buf);
+            let err = Msg::deserialize(&mut cur).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert_eq!(err.to_string(), "frame length mismatch");
+        }
+    }
+
+    #[test]
+    fn deserialize_invalid_error_kind() {
+        let buf = [0xFF];
+        let mut cur = Cursor::<&[u8]>::new(&buf);
+        let err = ErrorKind::deserialize(&mut cur).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
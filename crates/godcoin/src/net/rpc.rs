@@ -1,4 +1,4 @@
-use crate::{prelude::*, serializer::*};
+use crate::{constants::MAX_SCRIPT_DECODE_SIZE, prelude::*, serializer::*};
 use std::{
     io::{self, Cursor, Error},
     mem,
@@ -11,9 +11,10 @@ pub enum RpcType {
     Broadcast = 0x10,
     SetBlockFilter = 0x11,
     ClearBlockFilter = 0x12,
-    /// Subscribe to receive block updates. Any block filters applied may be ignored.
+    /// Subscribe to receive block and/or transaction updates. Any block filters applied may be
+    /// ignored.
     Subscribe = 0x13,
-    /// Unsubscribe from receiving block updates.
+    /// Unsubscribe from receiving any updates.
     Unsubscribe = 0x14,
 
     // Getters
@@ -22,20 +23,76 @@ pub enum RpcType {
     GetFullBlock = 0x22,
     GetBlockRange = 0x23,
     GetAccountInfo = 0x24,
+    GetBlockHeader = 0x25,
+    GetBlockHeaderRange = 0x26,
+    GetBlockBatch = 0x27,
+    GetAccount = 0x28,
+    GetTransaction = 0x29,
+    /// Validates a transaction without broadcasting it.
+    CheckTx = 0x2A,
+    GetScript = 0x2B,
+    GetBalances = 0x2C,
+    GetAccountNonce = 0x2D,
 }
 
+/// Events a client receives after subscribing. Block events are sent as a `GetBlock` response and
+/// transaction events are relayed as a `Broadcast` request.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SubscriptionKind {
+    Blocks = 0x01,
+    Txs = 0x02,
+    All = 0x03,
+}
+
+impl SubscriptionKind {
+    /// Returns whether events of the `other` kind are included in this subscription.
+    #[inline]
+    pub fn contains(self, other: SubscriptionKind) -> bool {
+        (self as u8) & (other as u8) == other as u8
+    }
+
+    pub fn from_u8(kind: u8) -> Option<Self> {
+        match kind {
+            t if t == Self::Blocks as u8 => Some(Self::Blocks),
+            t if t == Self::Txs as u8 => Some(Self::Txs),
+            t if t == Self::All as u8 => Some(Self::All),
+            _ => None,
+        }
+    }
+}
+
+/// Maximum amount of block headers that can be requested in a single `GetBlockHeaderRange`
+/// request.
+pub const MAX_BLOCK_HEADER_RANGE: u64 = 1000;
+
+/// Maximum amount of full blocks that can be returned in a single `GetBlockBatch` response.
+pub const MAX_BLOCK_BATCH: u16 = 100;
+
+/// Maximum amount of accounts whose balances can be requested in a single `GetBalances` request.
+pub const MAX_BALANCE_BATCH: u16 = 100;
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Request {
     Broadcast(TxVariant),
     SetBlockFilter(BlockFilter),
     ClearBlockFilter,
-    Subscribe,
+    Subscribe(SubscriptionKind),
     Unsubscribe,
     GetProperties,
     GetBlock(u64),           // height
     GetFullBlock(u64),       // height
     GetBlockRange(u64, u64), // min height, max height
     GetAccountInfo(AccountId),
+    GetBlockHeader(u64),           // height
+    GetBlockHeaderRange(u64, u64), // min height, max height
+    GetBlockBatch { start: u64, count: u16 },
+    GetAccount(AccountId),
+    GetTransaction(TxId),
+    CheckTx(TxVariant),
+    GetScript(ScriptHash),
+    GetBalances(Vec<AccountId>),
+    GetAccountNonce(AccountId),
 }
 
 impl Request {
@@ -55,7 +112,11 @@ impl Request {
                 }
             }
             Self::ClearBlockFilter => buf.push(RpcType::ClearBlockFilter as u8),
-            Self::Subscribe => buf.push(RpcType::Subscribe as u8),
+            Self::Subscribe(kind) => {
+                buf.reserve_exact(2);
+                buf.push(RpcType::Subscribe as u8);
+                buf.push(*kind as u8);
+            }
             Self::Unsubscribe => buf.push(RpcType::Unsubscribe as u8),
             Self::GetProperties => buf.push(RpcType::GetProperties as u8),
             Self::GetBlock(height) => {
@@ -79,6 +140,56 @@ impl Request {
                 buf.push(RpcType::GetAccountInfo as u8);
                 buf.push_u64(*acc);
             }
+            Self::GetBlockHeader(height) => {
+                buf.reserve_exact(9);
+                buf.push(RpcType::GetBlockHeader as u8);
+                buf.push_u64(*height);
+            }
+            Self::GetBlockHeaderRange(min_height, max_height) => {
+                buf.reserve_exact(1 + (2 * mem::size_of::<u64>()));
+                buf.push(RpcType::GetBlockHeaderRange as u8);
+                buf.push_u64(*min_height);
+                buf.push_u64(*max_height);
+            }
+            Self::GetBlockBatch { start, count } => {
+                buf.reserve_exact(1 + mem::size_of::<u64>() + mem::size_of::<u16>());
+                buf.push(RpcType::GetBlockBatch as u8);
+                buf.push_u64(*start);
+                buf.push_u16(*count);
+            }
+            Self::GetAccount(acc) => {
+                buf.reserve_exact(9);
+                buf.push(RpcType::GetAccount as u8);
+                buf.push_u64(*acc);
+            }
+            Self::GetTransaction(id) => {
+                buf.reserve_exact(33);
+                buf.push(RpcType::GetTransaction as u8);
+                buf.extend_from_slice(id.as_ref());
+            }
+            Self::CheckTx(tx) => {
+                buf.reserve_exact(1 + tx.byte_size());
+                buf.push(RpcType::CheckTx as u8);
+                tx.serialize(buf);
+            }
+            Self::GetScript(hash) => {
+                buf.reserve_exact(33);
+                buf.push(RpcType::GetScript as u8);
+                buf.extend_from_slice(hash.as_ref());
+            }
+            Self::GetBalances(ids) => {
+                buf.reserve_exact(3 + (ids.len() * mem::size_of::<AccountId>()));
+                buf.push(RpcType::GetBalances as u8);
+                buf.push_u16(ids.len() as u16);
+                for id in ids {
+                    buf.push_u64(*id);
+                }
+            }
+            Self::GetAccountNonce(acc) => {
+                buf.reserve_exact(9);
+                buf.push(RpcType::GetAccountNonce as u8);
+                buf.push_u64(*acc);
+            }
         }
     }
 
@@ -99,7 +210,12 @@ impl Request {
                 Ok(Self::SetBlockFilter(filter))
             }
             t if t == RpcType::ClearBlockFilter as u8 => Ok(Self::ClearBlockFilter),
-            t if t == RpcType::Subscribe as u8 => Ok(Self::Subscribe),
+            t if t == RpcType::Subscribe as u8 => {
+                let kind = SubscriptionKind::from_u8(cursor.take_u8()?).ok_or_else(|| {
+                    Error::new(io::ErrorKind::InvalidData, "invalid subscription kind")
+                })?;
+                Ok(Self::Subscribe(kind))
+            }
             t if t == RpcType::Unsubscribe as u8 => Ok(Self::Unsubscribe),
             t if t == RpcType::GetProperties as u8 => Ok(Self::GetProperties),
             t if t == RpcType::GetBlock as u8 => {
@@ -119,6 +235,52 @@ impl Request {
                 let acc = cursor.take_u64()?;
                 Ok(Self::GetAccountInfo(acc))
             }
+            t if t == RpcType::GetBlockHeader as u8 => {
+                let height = cursor.take_u64()?;
+                Ok(Self::GetBlockHeader(height))
+            }
+            t if t == RpcType::GetBlockHeaderRange as u8 => {
+                let min_height = cursor.take_u64()?;
+                let max_height = cursor.take_u64()?;
+                Ok(Self::GetBlockHeaderRange(min_height, max_height))
+            }
+            t if t == RpcType::GetBlockBatch as u8 => {
+                let start = cursor.take_u64()?;
+                let count = cursor.take_u16()?;
+                Ok(Self::GetBlockBatch { start, count })
+            }
+            t if t == RpcType::GetAccount as u8 => {
+                let acc = cursor.take_u64()?;
+                Ok(Self::GetAccount(acc))
+            }
+            t if t == RpcType::GetTransaction as u8 => {
+                let id = TxId::from_digest(cursor.take_digest()?);
+                Ok(Self::GetTransaction(id))
+            }
+            t if t == RpcType::CheckTx as u8 => {
+                let tx = TxVariant::deserialize(cursor)
+                    .ok_or_else(|| Error::new(io::ErrorKind::InvalidData, "failed to decode tx"))?;
+                Ok(Self::CheckTx(tx))
+            }
+            t if t == RpcType::GetScript as u8 => {
+                let hash = ScriptHash(cursor.take_digest()?);
+                Ok(Self::GetScript(hash))
+            }
+            t if t == RpcType::GetBalances as u8 => {
+                let len = cursor.take_u16()?;
+                if len > MAX_BALANCE_BATCH {
+                    return Err(Error::new(io::ErrorKind::InvalidData, "too many accounts"));
+                }
+                let mut ids = Vec::with_capacity(usize::from(len));
+                for _ in 0..len {
+                    ids.push(cursor.take_u64()?);
+                }
+                Ok(Self::GetBalances(ids))
+            }
+            t if t == RpcType::GetAccountNonce as u8 => {
+                let acc = cursor.take_u64()?;
+                Ok(Self::GetAccountNonce(acc))
+            }
             _ => Err(Error::new(
                 io::ErrorKind::InvalidData,
                 "invalid rpc request",
@@ -139,6 +301,22 @@ pub enum Response {
     GetFullBlock(Arc<Block>),
     GetBlockRange,
     GetAccountInfo(AccountInfo),
+    GetBlockHeader {
+        header: BlockHeader,
+        signer: SigPair,
+    },
+    GetBlockHeaderRange(Vec<(BlockHeader, SigPair)>),
+    GetBlockBatch(Vec<Arc<Block>>),
+    GetAccount(Option<Account>),
+    GetTransaction(Option<(u64, TxVariant)>), // height, tx
+    /// The transaction would be accepted. Rejected transactions return a `TxValidation` error.
+    CheckTx,
+    GetScript(Option<Script>),
+    /// Balances in the same order as the requested accounts. Unknown accounts have no balance.
+    GetBalances(Vec<Option<Asset>>),
+    /// Nonce of the last transfer sent from the account, including pending transfers. Accounts
+    /// that never sent a transfer have no nonce.
+    GetAccountNonce(Option<u32>),
 }
 
 impl Response {
@@ -154,7 +332,7 @@ impl Response {
                 buf.push(RpcType::GetProperties as u8);
                 buf.push_u64(props.height);
                 {
-                    let mut tx_buf = Vec::with_capacity(4096);
+                    let mut tx_buf = Vec::with_capacity(props.owner.byte_size());
                     props.owner.serialize(&mut tx_buf);
                     buf.extend_from_slice(&tx_buf);
                 }
@@ -189,6 +367,66 @@ impl Response {
                 buf.push_asset(info.net_fee);
                 buf.push_asset(info.account_fee);
             }
+            Self::GetBlockHeader { header, signer } => {
+                buf.push(RpcType::GetBlockHeader as u8);
+                header.serialize(buf);
+                buf.push_sig_pair(signer);
+            }
+            Self::GetBlockHeaderRange(headers) => {
+                buf.reserve_exact(5 + (headers.len() * 256));
+                buf.push(RpcType::GetBlockHeaderRange as u8);
+                buf.push_u32(headers.len() as u32);
+                for (header, signer) in headers {
+                    header.serialize(buf);
+                    buf.push_sig_pair(signer);
+                }
+            }
+            Self::GetBlockBatch(blocks) => {
+                buf.reserve_exact(1_048_576);
+                buf.push(RpcType::GetBlockBatch as u8);
+                buf.push_u16(blocks.len() as u16);
+                for block in blocks {
+                    block.serialize(buf);
+                }
+            }
+            Self::GetAccount(acc) => {
+                buf.reserve_exact(2 + mem::size_of::<Account>());
+                buf.push(RpcType::GetAccount as u8);
+                match acc {
+                    Some(acc) => {
+                        buf.push(0x01);
+                        acc.serialize(buf);
+                    }
+                    None => buf.push(0x00),
+                }
+            }
+            Self::GetTransaction(res) => {
+                buf.reserve_exact(4096);
+                buf.push(RpcType::GetTransaction as u8);
+                buf.push_option(res.as_ref(), |buf, (height, tx)| {
+                    buf.push_u64(*height);
+                    tx.serialize(buf);
+                });
+            }
+            Self::CheckTx => buf.push(RpcType::CheckTx as u8),
+            Self::GetScript(script) => {
+                buf.reserve_exact(6 + script.as_ref().map_or(0, |s| s.len()));
+                buf.push(RpcType::GetScript as u8);
+                buf.push_option(script.as_ref(), |buf, script| buf.push_bytes(script));
+            }
+            Self::GetBalances(balances) => {
+                buf.reserve_exact(3 + (balances.len() * 10));
+                buf.push(RpcType::GetBalances as u8);
+                buf.push_u16(balances.len() as u16);
+                for balance in balances {
+                    buf.push_option(*balance, |buf, balance| buf.push_asset(balance));
+                }
+            }
+            Self::GetAccountNonce(nonce) => {
+                buf.reserve_exact(6);
+                buf.push(RpcType::GetAccountNonce as u8);
+                buf.push_option(*nonce, |buf, nonce| buf.push_u32(nonce));
+            }
         }
     }
 
@@ -263,6 +501,84 @@ impl Response {
                     account_fee,
                 }))
             }
+            t if t == RpcType::GetBlockHeader as u8 => {
+                let header = BlockHeader::deserialize(cursor)
+                    .ok_or_else(|| Error::from(io::ErrorKind::UnexpectedEof))?;
+                let signer = cursor.take_sig_pair()?;
+                Ok(Self::GetBlockHeader { header, signer })
+            }
+            t if t == RpcType::GetBlockHeaderRange as u8 => {
+                let len = cursor.take_u32()?;
+                if u64::from(len) > MAX_BLOCK_HEADER_RANGE {
+                    return Err(Error::new(
+                        io::ErrorKind::InvalidData,
+                        "too many block headers",
+                    ));
+                }
+                let mut headers = Vec::with_capacity(len as usize);
+                for _ in 0..len {
+                    let header = BlockHeader::deserialize(cursor)
+                        .ok_or_else(|| Error::from(io::ErrorKind::UnexpectedEof))?;
+                    let signer = cursor.take_sig_pair()?;
+                    headers.push((header, signer));
+                }
+                Ok(Self::GetBlockHeaderRange(headers))
+            }
+            t if t == RpcType::GetBlockBatch as u8 => {
+                let len = cursor.take_u16()?;
+                if len > MAX_BLOCK_BATCH {
+                    return Err(Error::new(io::ErrorKind::InvalidData, "too many blocks"));
+                }
+                let mut blocks = Vec::with_capacity(usize::from(len));
+                for _ in 0..len {
+                    let block = Block::deserialize(cursor)
+                        .ok_or_else(|| Error::from(io::ErrorKind::UnexpectedEof))?;
+                    blocks.push(Arc::new(block));
+                }
+                Ok(Self::GetBlockBatch(blocks))
+            }
+            t if t == RpcType::GetAccount as u8 => match cursor.take_u8()? {
+                0x00 => Ok(Self::GetAccount(None)),
+                0x01 => Ok(Self::GetAccount(Some(Account::deserialize(cursor)?))),
+                _ => Err(Error::new(
+                    io::ErrorKind::InvalidData,
+                    "invalid GetAccount response",
+                )),
+            },
+            t if t == RpcType::GetTransaction as u8 => {
+                let res = cursor.take_option(|cursor| {
+                    let height = cursor.take_u64()?;
+                    let tx = TxVariant::deserialize(cursor).ok_or_else(|| {
+                        Error::new(io::ErrorKind::InvalidData, "failed to decode tx")
+                    })?;
+                    Ok((height, tx))
+                })?;
+                Ok(Self::GetTransaction(res))
+            }
+            t if t == RpcType::CheckTx as u8 => Ok(Self::CheckTx),
+            t if t == RpcType::GetScript as u8 => {
+                let script = cursor.take_option(|cursor| {
+                    Ok(Script::new(
+                        cursor.take_bytes_capped(MAX_SCRIPT_DECODE_SIZE)?,
+                    ))
+                })?;
+                Ok(Self::GetScript(script))
+            }
+            t if t == RpcType::GetBalances as u8 => {
+                let len = cursor.take_u16()?;
+                if len > MAX_BALANCE_BATCH {
+                    return Err(Error::new(io::ErrorKind::InvalidData, "too many balances"));
+                }
+                let mut balances = Vec::with_capacity(usize::from(len));
+                for _ in 0..len {
+                    balances.push(cursor.take_option(|cursor| cursor.take_asset())?);
+                }
+                Ok(Self::GetBalances(balances))
+            }
+            t if t == RpcType::GetAccountNonce as u8 => {
+                let nonce = cursor.take_option(|cursor| cursor.take_u32())?;
+                Ok(Self::GetAccountNonce(nonce))
+            }
             _ => Err(Error::new(
                 io::ErrorKind::InvalidData,
                 "invalid rpc response",
@@ -270,3 +586,226 @@ impl Response {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::Digest;
+
+    fn get_test_props() -> Properties {
+        Properties {
+            height: 123,
+            owner: Box::new(TxVariant::V0(TxVariantV0::OwnerTx(OwnerTx {
+                base: Tx {
+                    nonce: 1,
+                    expiry: 2,
+                    fee: Asset::default(),
+                    signature_pairs: vec![],
+                },
+                minter: KeyPair::gen().0,
+                wallet: 5,
+            }))),
+            network_fee: Asset::new(100),
+            token_supply: Asset::new(1000),
+        }
+    }
+
+    #[test]
+    fn serialize_get_properties() {
+        let props = get_test_props();
+        let res = Response::GetProperties(props.clone());
+        let mut buf = Vec::new();
+        res.serialize(&mut buf);
+
+        let mut cur = Cursor::<&[u8]>::new(&buf);
+        let dec = Response::deserialize(&mut cur).unwrap();
+        assert_eq!(cur.position(), buf.len() as u64);
+        match dec {
+            Response::GetProperties(dec) => {
+                assert_eq!(dec.owner, props.owner);
+                assert_eq!(dec, props);
+            }
+            _ => panic!("Expected GetProperties response: {:?}", dec),
+        }
+    }
+
+    #[test]
+    fn deserialize_get_properties_without_owner() {
+        // Payload format that does not include the owner transaction
+        let props = get_test_props();
+        let mut buf = Vec::new();
+        buf.push(RpcType::GetProperties as u8);
+        buf.push_u64(props.height);
+        buf.push_asset(props.network_fee);
+        buf.push_asset(props.token_supply);
+
+        let mut cur = Cursor::<&[u8]>::new(&buf);
+        let err = Response::deserialize(&mut cur).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn serialize_subscribe() {
+        for kind in &[
+            SubscriptionKind::Blocks,
+            SubscriptionKind::Txs,
+            SubscriptionKind::All,
+        ] {
+            let req = Request::Subscribe(*kind);
+            let mut buf = Vec::new();
+            req.serialize(&mut buf);
+            let mut cur = Cursor::<&[u8]>::new(&buf);
+            assert_eq!(Request::deserialize(&mut cur).unwrap(), req);
+            assert_eq!(cur.position(), buf.len() as u64);
+        }
+
+        for kind in &[0x00, 0x04] {
+            let buf = [RpcType::Subscribe as u8, *kind];
+            let mut cur = Cursor::<&[u8]>::new(&buf);
+            let err = Request::deserialize(&mut cur).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+
+        assert!(SubscriptionKind::All.contains(SubscriptionKind::Blocks));
+        assert!(SubscriptionKind::All.contains(SubscriptionKind::Txs));
+        assert!(SubscriptionKind::Blocks.contains(SubscriptionKind::Blocks));
+        assert!(!SubscriptionKind::Blocks.contains(SubscriptionKind::Txs));
+        assert!(!SubscriptionKind::Txs.contains(SubscriptionKind::All));
+    }
+
+    #[test]
+    fn serialize_get_account() {
+        let req = Request::GetAccount(1234);
+        let mut buf = Vec::new();
+        req.serialize(&mut buf);
+        let mut cur = Cursor::<&[u8]>::new(&buf);
+        assert_eq!(Request::deserialize(&mut cur).unwrap(), req);
+        assert_eq!(cur.position(), buf.len() as u64);
+
+        let acc = Account::create_default(
+            1234,
+            Permissions {
+                threshold: 1,
+                keys: vec![KeyPair::gen().0],
+            },
+        );
+        for res in &[Response::GetAccount(Some(acc)), Response::GetAccount(None)] {
+            let mut buf = Vec::new();
+            res.serialize(&mut buf);
+            let mut cur = Cursor::<&[u8]>::new(&buf);
+            assert_eq!(&Response::deserialize(&mut cur).unwrap(), res);
+            assert_eq!(cur.position(), buf.len() as u64);
+        }
+    }
+
+    #[test]
+    fn serialize_get_script() {
+        let script = Script::new(vec![0x00, 0x01, 0x02]);
+        let req = Request::GetScript(script.hash());
+        let mut buf = Vec::new();
+        req.serialize(&mut buf);
+        let mut cur = Cursor::<&[u8]>::new(&buf);
+        assert_eq!(Request::deserialize(&mut cur).unwrap(), req);
+        assert_eq!(cur.position(), buf.len() as u64);
+
+        for res in &[Response::GetScript(Some(script)), Response::GetScript(None)] {
+            let mut buf = Vec::new();
+            res.serialize(&mut buf);
+            let mut cur = Cursor::<&[u8]>::new(&buf);
+            assert_eq!(&Response::deserialize(&mut cur).unwrap(), res);
+            assert_eq!(cur.position(), buf.len() as u64);
+        }
+    }
+
+    #[test]
+    fn serialize_get_account_nonce() {
+        let req = Request::GetAccountNonce(123);
+        let mut buf = Vec::new();
+        req.serialize(&mut buf);
+        let mut cur = Cursor::<&[u8]>::new(&buf);
+        assert_eq!(Request::deserialize(&mut cur).unwrap(), req);
+        assert_eq!(cur.position(), buf.len() as u64);
+
+        for res in &[
+            Response::GetAccountNonce(Some(u32::MAX)),
+            Response::GetAccountNonce(None),
+        ] {
+            let mut buf = Vec::new();
+            res.serialize(&mut buf);
+            let mut cur = Cursor::<&[u8]>::new(&buf);
+            assert_eq!(&Response::deserialize(&mut cur).unwrap(), res);
+            assert_eq!(cur.position(), buf.len() as u64);
+        }
+    }
+
+    #[test]
+    fn serialize_get_balances() {
+        let req = Request::GetBalances(vec![1, 2, u64::MAX]);
+        let mut buf = Vec::new();
+        req.serialize(&mut buf);
+        let mut cur = Cursor::<&[u8]>::new(&buf);
+        assert_eq!(Request::deserialize(&mut cur).unwrap(), req);
+        assert_eq!(cur.position(), buf.len() as u64);
+
+        let res = Response::GetBalances(vec![Some(Asset::new(100)), None, Some(Asset::new(0))]);
+        let mut buf = Vec::new();
+        res.serialize(&mut buf);
+        let mut cur = Cursor::<&[u8]>::new(&buf);
+        assert_eq!(Response::deserialize(&mut cur).unwrap(), res);
+        assert_eq!(cur.position(), buf.len() as u64);
+
+        let ids = vec![0; usize::from(MAX_BALANCE_BATCH) + 1];
+        let mut buf = Vec::new();
+        Request::GetBalances(ids).serialize(&mut buf);
+        let mut cur = Cursor::<&[u8]>::new(&buf);
+        assert!(Request::deserialize(&mut cur).is_err());
+    }
+
+    #[test]
+    fn serialize_get_transaction() {
+        let id = TxId::from_digest(Digest::from_slice(&[7; 32]).unwrap());
+        let req = Request::GetTransaction(id);
+        let mut buf = Vec::new();
+        req.serialize(&mut buf);
+        let mut cur = Cursor::<&[u8]>::new(&buf);
+        assert_eq!(Request::deserialize(&mut cur).unwrap(), req);
+        assert_eq!(cur.position(), buf.len() as u64);
+
+        let tx = get_test_props().owner;
+        for res in &[
+            Response::GetTransaction(Some((123, *tx))),
+            Response::GetTransaction(None),
+        ] {
+            let mut buf = Vec::new();
+            res.serialize(&mut buf);
+            let mut cur = Cursor::<&[u8]>::new(&buf);
+            assert_eq!(&Response::deserialize(&mut cur).unwrap(), res);
+            assert_eq!(cur.position(), buf.len() as u64);
+        }
+    }
+
+    #[test]
+    fn serialize_check_tx() {
+        let req = Request::CheckTx(*get_test_props().owner);
+        let mut buf = Vec::new();
+        req.serialize(&mut buf);
+        let mut cur = Cursor::<&[u8]>::new(&buf);
+        assert_eq!(Request::deserialize(&mut cur).unwrap(), req);
+        assert_eq!(cur.position(), buf.len() as u64);
+
+        let res = Response::CheckTx;
+        let mut buf = Vec::new();
+        res.serialize(&mut buf);
+        let mut cur = Cursor::<&[u8]>::new(&buf);
+        assert_eq!(Response::deserialize(&mut cur).unwrap(), res);
+        assert_eq!(cur.position(), buf.len() as u64);
+    }
+
+    #[test]
+    fn deserialize_invalid_get_account_presence() {
+        let buf = [RpcType::GetAccount as u8, 0x02];
+        let mut cur = Cursor::<&[u8]>::new(&buf);
+        let err = Response::deserialize(&mut cur).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1,4 +1,4 @@
-use super::{op::*, Script};
+use super::{error::*, op::*, Script};
 use crate::{constants::MAX_SCRIPT_BYTE_SIZE, serializer::*};
 
 type FnRef = (u8, u32); // ID, pointer
@@ -57,6 +57,28 @@ impl Builder {
         self.body.extend(&function.byte_code);
         self
     }
+
+    /// Appends the functions of already encoded script byte code, such as the output of a
+    /// previous build. Returns an error if the byte code does not disassemble cleanly.
+    pub fn push_raw(mut self, byte_code: &[u8]) -> Result<Self, EvalErr> {
+        let script = Script::from(byte_code);
+        script.disassemble()?;
+        let fns = script
+            .fn_table()
+            .map_err(|_| EvalErr::new(0, EvalErrKind::HeaderReadErr))?;
+        if self.lookup_table.len() + fns.len() > usize::from(u8::MAX) {
+            panic!("cannot push more than {} functions", u8::MAX);
+        }
+
+        let header_len = 1 + (fns.len() * 5);
+        let body_offset = self.body.len() as u32;
+        for (id, ptr) in fns {
+            self.lookup_table
+                .push((id, ptr - header_len as u32 + body_offset));
+        }
+        self.body.extend(&byte_code[header_len..]);
+        Ok(self)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -105,6 +127,10 @@ impl FnBuilder {
                 self.byte_code.push(Operand::PushAsset.into());
                 self.byte_code.extend(&asset.amount.to_be_bytes());
             }
+            OpFrame::Int(int) => {
+                self.byte_code.push(Operand::PushInt.into());
+                self.byte_code.extend(&int.to_be_bytes());
+            }
             // Arithmetic
             OpFrame::OpLoadAmt => self.byte_code.push(Operand::OpLoadAmt.into()),
             OpFrame::OpLoadRemAmt => self.byte_code.push(Operand::OpLoadRemAmt.into()),
@@ -142,6 +168,10 @@ impl FnBuilder {
                 self.byte_code.push(Operand::OpCheckTimeFastFail.into());
                 self.byte_code.push_u64(time);
             }
+            // Comparison
+            OpFrame::OpNumEqual => self.byte_code.push(Operand::OpNumEqual.into()),
+            OpFrame::OpLessThan => self.byte_code.push(Operand::OpLessThan.into()),
+            OpFrame::OpGreaterThan => self.byte_code.push(Operand::OpGreaterThan.into()),
         }
         self
     }
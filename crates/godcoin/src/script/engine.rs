@@ -1,10 +1,11 @@
-use std::{borrow::Cow, convert::TryInto, mem};
+use std::{borrow::Cow, convert::TryFrom, io, mem};
 
 use super::{stack::*, *};
 use crate::{
     account::{AccountId, PermsSigVerifyErr},
     asset::Asset,
     blockchain::{Blockchain, LogEntry, Receipt},
+    constants::MAX_SCRIPT_STEPS,
     serializer::BufRead,
     tx::{TxPrecompData, TxVariant, TxVariantV0},
 };
@@ -15,6 +16,15 @@ macro_rules! map_err_type {
     };
 }
 
+/// Maps an argument read failure to an error kind. Running out of input means the provided
+/// arguments don't match the function signature while other failures are malformed encodings.
+fn arg_err_kind(e: &io::Error) -> EvalErrKind {
+    match e.kind() {
+        io::ErrorKind::UnexpectedEof => EvalErrKind::ArgMismatch,
+        _ => EvalErrKind::ArgDeserialization,
+    }
+}
+
 #[derive(Debug)]
 pub struct EngineData<'a> {
     pub script: Cow<'a, Script>,
@@ -31,6 +41,8 @@ pub struct ScriptEngine<'a> {
     log: Vec<LogEntry>,
     total_amt: Asset,
     remaining_amt: Asset,
+    steps: u32,
+    max_steps: u32,
 }
 
 impl<'a> ScriptEngine<'a> {
@@ -49,9 +61,18 @@ impl<'a> ScriptEngine<'a> {
             log: vec![],
             total_amt,
             remaining_amt: total_amt,
+            steps: 0,
+            max_steps: MAX_SCRIPT_STEPS,
         }
     }
 
+    /// Overrides the maximum number of steps the script may take before evaluation is aborted.
+    /// Every decoded op costs one step and permission checks cost one step per account.
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
     /// Returns the log the script produces after execution completes. If any error occurs during
     /// evaluation, execution will be aborted and return an error.
     #[inline]
@@ -94,17 +115,21 @@ impl<'a> ScriptEngine<'a> {
                             Arg::AccountId => {
                                 let id = bin_args
                                     .take_u64()
-                                    .map_err(|_| self.new_err(EvalErrKind::ArgDeserialization))?;
+                                    .map_err(|e| self.new_err(arg_err_kind(&e)))?;
                                 map_err_type!(self, self.stack.push(OpFrame::AccountId(id)))?;
                             }
                             Arg::Asset => {
                                 let asset = bin_args
                                     .take_asset()
-                                    .map_err(|_| self.new_err(EvalErrKind::ArgDeserialization))?;
+                                    .map_err(|e| self.new_err(arg_err_kind(&e)))?;
                                 map_err_type!(self, self.stack.push(OpFrame::Asset(asset)))?;
                             }
                         }
                     }
+                    // Every provided byte must be consumed by the function signature
+                    if bin_args.position() != bin_args.get_ref().len() as u64 {
+                        return Err(self.new_err(EvalErrKind::ArgMismatch));
+                    }
                 }
                 _ => return Err(self.new_err(EvalErrKind::InvalidEntryPoint)),
             }
@@ -183,6 +208,7 @@ impl<'a> ScriptEngine<'a> {
                 OpFrame::True => map_err_type!(self, self.stack.push(op))?,
                 OpFrame::AccountId(_) => map_err_type!(self, self.stack.push(op))?,
                 OpFrame::Asset(_) => map_err_type!(self, self.stack.push(op))?,
+                OpFrame::Int(_) => map_err_type!(self, self.stack.push(op))?,
                 // Arithmetic
                 OpFrame::OpLoadAmt => {
                     map_err_type!(self, self.stack.push(OpFrame::Asset(self.total_amt)))?;
@@ -325,6 +351,22 @@ impl<'a> ScriptEngine<'a> {
                         return Err(self.new_err(EvalErrKind::ScriptRetFalse));
                     }
                 }
+                // Comparison
+                OpFrame::OpNumEqual => {
+                    let b = map_err_type!(self, self.stack.pop_int())?;
+                    let a = map_err_type!(self, self.stack.pop_int())?;
+                    map_err_type!(self, self.stack.push(a == b))?;
+                }
+                OpFrame::OpLessThan => {
+                    let b = map_err_type!(self, self.stack.pop_int())?;
+                    let a = map_err_type!(self, self.stack.pop_int())?;
+                    map_err_type!(self, self.stack.push(a < b))?;
+                }
+                OpFrame::OpGreaterThan => {
+                    let b = map_err_type!(self, self.stack.pop_int())?;
+                    let a = map_err_type!(self, self.stack.pop_int())?;
+                    map_err_type!(self, self.stack.push(a > b))?;
+                }
             }
         }
 
@@ -379,112 +421,11 @@ impl<'a> ScriptEngine<'a> {
     }
 
     fn consume_op(&mut self) -> Result<Option<OpFrame>, EvalErr> {
-        macro_rules! read_bytes {
-            ($self:expr, $len:expr) => {
-                match $self.data.script.get($self.pos..$self.pos + $len) {
-                    Some(b) => {
-                        $self.pos += $len;
-                        b
-                    }
-                    None => {
-                        return Err($self.new_err(EvalErrKind::UnexpectedEOF));
-                    }
-                }
-            };
-            ($self:expr) => {
-                match $self.data.script.get($self.pos) {
-                    Some(b) => {
-                        $self.pos += 1;
-                        *b
-                    }
-                    None => {
-                        return Err($self.new_err(EvalErrKind::UnexpectedEOF));
-                    }
-                }
-            };
-        }
-
         if self.pos == self.data.script.len() {
             return Ok(None);
         }
-        let byte = self.data.script[self.pos];
-        self.pos += 1;
-
-        match byte {
-            // Function definition
-            o if o == Operand::OpDefine as u8 => {
-                let arg_cnt = read_bytes!(self);
-                let mut args = Vec::with_capacity(usize::from(arg_cnt));
-                for _ in 0..arg_cnt {
-                    let tag_byte = read_bytes!(self);
-                    let arg = tag_byte
-                        .try_into()
-                        .map_err(|_| self.new_err(EvalErrKind::UnknownArgType))?;
-                    args.push(arg);
-                }
-                Ok(Some(OpFrame::OpDefine(args)))
-            }
-            // Events
-            o if o == Operand::OpTransfer as u8 => Ok(Some(OpFrame::OpTransfer)),
-            o if o == Operand::OpDestroy as u8 => Ok(Some(OpFrame::OpDestroy)),
-            // Push value
-            o if o == Operand::PushFalse as u8 => Ok(Some(OpFrame::False)),
-            o if o == Operand::PushTrue as u8 => Ok(Some(OpFrame::True)),
-            o if o == Operand::PushAccountId as u8 => {
-                let slice = read_bytes!(self, mem::size_of::<u64>());
-                let id = u64::from_be_bytes(slice.try_into().unwrap());
-                Ok(Some(OpFrame::AccountId(id)))
-            }
-            o if o == Operand::PushAsset as u8 => {
-                let slice = read_bytes!(self, mem::size_of::<i64>());
-                let amt = i64::from_be_bytes(slice.try_into().unwrap());
-                let amt = Asset::new(amt);
-                Ok(Some(OpFrame::Asset(amt)))
-            }
-            // Arithmetic
-            o if o == Operand::OpLoadAmt as u8 => Ok(Some(OpFrame::OpLoadAmt)),
-            o if o == Operand::OpLoadRemAmt as u8 => Ok(Some(OpFrame::OpLoadRemAmt)),
-            o if o == Operand::OpAdd as u8 => Ok(Some(OpFrame::OpAdd)),
-            o if o == Operand::OpSub as u8 => Ok(Some(OpFrame::OpSub)),
-            o if o == Operand::OpMul as u8 => Ok(Some(OpFrame::OpMul)),
-            o if o == Operand::OpDiv as u8 => Ok(Some(OpFrame::OpDiv)),
-            // Logic
-            o if o == Operand::OpNot as u8 => Ok(Some(OpFrame::OpNot)),
-            o if o == Operand::OpIf as u8 => Ok(Some(OpFrame::OpIf)),
-            o if o == Operand::OpElse as u8 => Ok(Some(OpFrame::OpElse)),
-            o if o == Operand::OpEndIf as u8 => Ok(Some(OpFrame::OpEndIf)),
-            o if o == Operand::OpReturn as u8 => Ok(Some(OpFrame::OpReturn)),
-            o if o == Operand::OpAbort as u8 => Ok(Some(OpFrame::OpAbort)),
-            // Crypto
-            o if o == Operand::OpCheckPerms as u8 => Ok(Some(OpFrame::OpCheckPerms)),
-            o if o == Operand::OpCheckPermsFastFail as u8 => {
-                Ok(Some(OpFrame::OpCheckPermsFastFail))
-            }
-            o if o == Operand::OpCheckMultiPerms as u8 => {
-                let threshold = read_bytes!(self);
-                let acc_count = read_bytes!(self);
-                Ok(Some(OpFrame::OpCheckMultiPerms(threshold, acc_count)))
-            }
-            o if o == Operand::OpCheckMultiPermsFastFail as u8 => {
-                let threshold = read_bytes!(self);
-                let acc_count = read_bytes!(self);
-                Ok(Some(OpFrame::OpCheckMultiPermsFastFail(
-                    threshold, acc_count,
-                )))
-            }
-            // Lock time
-            o if o == Operand::OpCheckTime as u8 => {
-                let slice = read_bytes!(self, mem::size_of::<u64>());
-                let time = u64::from_be_bytes(slice.try_into().unwrap());
-                Ok(Some(OpFrame::OpCheckTime(time)))
-            }
-            o if o == Operand::OpCheckTimeFastFail as u8 => {
-                let slice = read_bytes!(self, mem::size_of::<u64>());
-                let time = u64::from_be_bytes(slice.try_into().unwrap());
-                Ok(Some(OpFrame::OpCheckTimeFastFail(time)))
-            }
-            _ => Err(self.new_err(EvalErrKind::UnknownOp)),
-        }
+        self.charge_steps(1)?;
+        decode_op(&self.data.script, &mut self.pos).map_err(|e| self.new_err(e))
     }
 
     fn check_acc_perms(&mut self, threshold: usize, accs: &[AccountId]) -> Result<bool, EvalErr> {
@@ -494,6 +435,7 @@ impl<'a> ScriptEngine<'a> {
             return Ok(false);
         }
 
+        self.charge_steps(accs.len())?;
         let txid = self.data.tx_data.txid().as_ref();
         let sigs = self.data.tx_data.tx().sigs();
 
@@ -520,6 +462,15 @@ impl<'a> ScriptEngine<'a> {
         Ok(valid_threshold >= threshold)
     }
 
+    fn charge_steps(&mut self, steps: usize) -> Result<(), EvalErr> {
+        let steps = u32::try_from(steps).unwrap_or(u32::MAX);
+        self.steps = self.steps.saturating_add(steps);
+        if self.steps > self.max_steps {
+            return Err(self.new_err(EvalErrKind::StepLimitExceeded));
+        }
+        Ok(())
+    }
+
     fn new_err(&self, err: EvalErrKind) -> EvalErr {
         EvalErr::new(self.pos as u32, err)
     }
@@ -530,7 +481,7 @@ mod tests {
     use super::*;
     use crate::{
         account::{Account, Permissions},
-        blockchain::WriteBatch,
+        blockchain::{ChainParams, NodeOptions, WriteBatch},
         crypto::{KeyPair, SigPair, Signature},
         tx::{TransferTx, Tx, TxVariant, TxVariantV0},
     };
@@ -745,6 +696,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn compare_ints() {
+        let eval_cmp = |a: i64, b: i64, op: OpFrame| {
+            let mut res = None;
+            TestEngine::new().get(
+                Builder::new().push(
+                    FnBuilder::new(0, OpFrame::OpDefine(vec![]))
+                        .push(OpFrame::Int(a))
+                        .push(OpFrame::Int(b))
+                        .push(op),
+                ),
+                |_, mut engine| match engine.call_fn(0) {
+                    Ok(_) => res = Some(true),
+                    Err(e) => {
+                        assert_eq!(e.err, EvalErrKind::ScriptRetFalse);
+                        res = Some(false);
+                    }
+                },
+            );
+            res.unwrap()
+        };
+
+        assert!(eval_cmp(5, 5, OpFrame::OpNumEqual));
+        assert!(!eval_cmp(5, -5, OpFrame::OpNumEqual));
+
+        assert!(eval_cmp(-10, 3, OpFrame::OpLessThan));
+        assert!(!eval_cmp(3, 3, OpFrame::OpLessThan));
+        assert!(!eval_cmp(i64::MAX, i64::MIN, OpFrame::OpLessThan));
+
+        assert!(eval_cmp(i64::MAX, i64::MIN, OpFrame::OpGreaterThan));
+        assert!(!eval_cmp(3, 3, OpFrame::OpGreaterThan));
+        assert!(!eval_cmp(-10, 3, OpFrame::OpGreaterThan));
+    }
+
+    #[test]
+    fn fail_compare_non_int() {
+        for frame in &[
+            OpFrame::AccountId(0),
+            OpFrame::True,
+            OpFrame::Asset(Asset::new(1)),
+        ] {
+            TestEngine::new().get(
+                Builder::new().push(
+                    FnBuilder::new(0, OpFrame::OpDefine(vec![]))
+                        .push(frame.clone())
+                        .push(OpFrame::Int(1))
+                        .push(OpFrame::OpNumEqual),
+                ),
+                |_, mut engine| {
+                    assert_eq!(
+                        engine.call_fn(0).unwrap_err().err,
+                        EvalErrKind::InvalidItemOnStack
+                    );
+                },
+            );
+        }
+    }
+
     #[test]
     fn call_unknown_fn() {
         TestEngine::new().get(
@@ -808,6 +817,83 @@ mod tests {
         });
     }
 
+    #[test]
+    fn call_args_mismatch() {
+        let script = Builder::new()
+            .push(
+                FnBuilder::new(1, OpFrame::OpDefine(vec![Arg::AccountId, Arg::Asset]))
+                    .push(OpFrame::True),
+            )
+            .build()
+            .unwrap();
+        let id = 1234;
+        let asset = "1234.00000 TEST".parse().unwrap();
+
+        let check_args = |args: Vec<u8>| {
+            let engine = TestEngine::new();
+            let tx = engine.new_transfer_tx(1, args, &[]);
+            engine.get_direct(tx, script.clone(), |_, mut engine| {
+                assert_eq!(engine.call_fn(1).unwrap_err().err, EvalErrKind::ArgMismatch);
+            });
+        };
+
+        // Too few arguments
+        check_args(vec![]);
+        check_args({
+            let mut args = vec![];
+            args.push_u64(id);
+            args
+        });
+
+        // Too many arguments
+        check_args({
+            let mut args = vec![];
+            args.push_u64(id);
+            args.push_asset(asset);
+            args.push_u64(id);
+            args
+        });
+
+        // Arguments provided in the wrong order
+        check_args({
+            let mut args = vec![];
+            args.push_asset(asset);
+            args.push_u64(id);
+            args
+        });
+    }
+
+    #[test]
+    fn step_limit() {
+        let script = {
+            let mut b = FnBuilder::new(0, OpFrame::OpDefine(vec![])).push(OpFrame::True);
+            for _ in 0..100 {
+                b = b.push(OpFrame::OpNot);
+            }
+            Builder::new().push(b).build().unwrap()
+        };
+
+        {
+            let engine = TestEngine::new();
+            let tx = engine.new_transfer_tx(0, vec![], &[]);
+            engine.get_direct(tx, script.clone(), |test, engine| {
+                let from_entry = test.from_transfer_entry("10.00000 TEST");
+                assert_eq!(engine.eval().unwrap(), vec![from_entry]);
+            });
+        }
+        {
+            let engine = TestEngine::new();
+            let tx = engine.new_transfer_tx(0, vec![], &[]);
+            engine.get_direct(tx, script, |_, engine| {
+                let engine = engine.with_max_steps(50);
+                assert_eq!(
+                    engine.eval().unwrap_err().err,
+                    EvalErrKind::StepLimitExceeded
+                );
+            });
+        }
+    }
+
     #[test]
     fn eval_uses_transfer_tx_call_fn() {
         let script = Builder::new()
@@ -1624,7 +1710,7 @@ mod tests {
             engine.to_acc.destroyed = true;
             engine.index_account(engine.to_acc.clone());
             let indexer = engine.chain.indexer();
-            let to_acc = indexer.get_account(engine.to_acc.id).unwrap();
+            let to_acc = indexer.get_account(engine.to_acc.id).unwrap().unwrap();
             assert!(to_acc.destroyed);
         }
 
@@ -1848,7 +1934,12 @@ mod tests {
 
             let log_path = Path::join(&tmp_dir, "blocklog");
             let index_path = Path::join(&tmp_dir, "index");
-            let chain = Blockchain::new(&log_path, &index_path);
+            let chain = Blockchain::new(
+                &log_path,
+                &index_path,
+                ChainParams::default(),
+                NodeOptions::default(),
+            );
 
             // Create a head that some tests require.
             chain.create_genesis_block(KeyPair::gen());
@@ -1874,7 +1965,7 @@ mod tests {
             let mut batch = WriteBatch::new(chain.indexer());
             batch.insert_or_update_account(from_acc.clone());
             batch.insert_or_update_account(to_acc.clone());
-            batch.commit();
+            batch.commit().unwrap();
 
             Self {
                 tmp_dir,
@@ -1903,7 +1994,7 @@ mod tests {
         fn index_account(&self, account: Account) {
             let mut batch = WriteBatch::new(self.chain.indexer());
             batch.insert_or_update_account(account);
-            batch.commit();
+            batch.commit().unwrap();
         }
 
         fn from_transfer_entry(&self, amt: &str) -> LogEntry {
@@ -30,6 +30,8 @@ pub enum EvalErrKind {
     Arithmetic = 0x0C,
     InvalidAmount = 0x0D,
     AccountNotFound = 0x0E,
+    ArgMismatch = 0x0F,
+    StepLimitExceeded = 0x10,
 }
 
 impl TryFrom<u8> for EvalErrKind {
@@ -52,6 +54,8 @@ impl TryFrom<u8> for EvalErrKind {
             t if t == Self::Arithmetic as u8 => Self::Arithmetic,
             t if t == Self::InvalidAmount as u8 => Self::InvalidAmount,
             t if t == Self::AccountNotFound as u8 => Self::AccountNotFound,
+            t if t == Self::ArgMismatch as u8 => Self::ArgMismatch,
+            t if t == Self::StepLimitExceeded as u8 => Self::StepLimitExceeded,
             _ => return Err(()),
         })
     }
@@ -1,4 +1,7 @@
-use crate::serializer::*;
+use crate::{
+    crypto::{double_sha256, Digest},
+    serializer::*,
+};
 use std::{
     borrow::Cow,
     fmt::{self, Debug, Formatter},
@@ -22,12 +25,29 @@ pub const MAX_FRAME_STACK: usize = 64;
 #[derive(Clone, PartialEq, Eq)]
 pub struct Script(Vec<u8>);
 
+/// Identifies a script by the hash of its byte code.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ScriptHash(pub Digest);
+
+impl AsRef<[u8]> for ScriptHash {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
 impl Script {
     #[inline]
     pub fn new(byte_code: Vec<u8>) -> Self {
         Script(byte_code)
     }
 
+    /// Calculates the double SHA-256 hash of the script byte code.
+    #[inline]
+    pub fn hash(&self) -> ScriptHash {
+        ScriptHash(double_sha256(&self.0))
+    }
+
     pub fn get_fn_ptr(&self, fn_id: u8) -> io::Result<Option<u32>> {
         let mut cur = Cursor::<&[u8]>::new(&self.0);
         let fn_count = cur.take_u8()?;
@@ -42,6 +62,50 @@ impl Script {
 
         Ok(None)
     }
+
+    /// Decodes the byte code into the list of ops following the function lookup table. Every
+    /// function pointer must refer to an OpDefine op.
+    pub fn disassemble(&self) -> Result<Vec<OpFrame>, EvalErr> {
+        let fns = self
+            .fn_table()
+            .map_err(|_| EvalErr::new(0, EvalErrKind::HeaderReadErr))?;
+
+        let mut pos = 1 + fns.len() * 5;
+        let mut ops = vec![];
+        let mut def_positions = vec![];
+        loop {
+            let op_pos = pos;
+            match decode_op(&self.0, &mut pos).map_err(|e| EvalErr::new(pos as u32, e))? {
+                Some(op) => {
+                    if let OpFrame::OpDefine(_) = op {
+                        def_positions.push(op_pos as u32);
+                    }
+                    ops.push(op);
+                }
+                None => break,
+            }
+        }
+
+        for (_, ptr) in fns {
+            if !def_positions.contains(&ptr) {
+                return Err(EvalErr::new(ptr, EvalErrKind::InvalidEntryPoint));
+            }
+        }
+        Ok(ops)
+    }
+
+    /// Returns the function ID and byte pointer pairs from the lookup table.
+    pub(crate) fn fn_table(&self) -> io::Result<Vec<(u8, u32)>> {
+        let mut cur = Cursor::<&[u8]>::new(&self.0);
+        let fn_count = cur.take_u8()?;
+        let mut fns = Vec::with_capacity(usize::from(fn_count));
+        for _ in 0..fn_count {
+            let id = cur.take_u8()?;
+            let ptr = cur.take_u32()?;
+            fns.push((id, ptr));
+        }
+        Ok(fns)
+    }
 }
 
 impl Debug for Script {
@@ -51,6 +115,13 @@ impl Debug for Script {
     }
 }
 
+impl From<&Script> for ScriptHash {
+    #[inline]
+    fn from(script: &Script) -> Self {
+        script.hash()
+    }
+}
+
 impl From<&[u8]> for Script {
     #[inline]
     fn from(slice: &[u8]) -> Self {
@@ -94,3 +165,74 @@ impl Deref for Script {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_hash() {
+        let script = Script::new(vec![0x00, 0x01, 0x02]);
+        let hash = script.hash();
+        assert_eq!(hash, ScriptHash::from(&script));
+        assert_eq!(hash.0, double_sha256(&[0x00, 0x01, 0x02]));
+        assert_eq!(hash, Script::new(vec![0x00, 0x01, 0x02]).hash());
+        assert_ne!(hash, Script::new(vec![0x00, 0x01]).hash());
+    }
+
+    #[test]
+    fn push_raw_fragment() {
+        let fragment = Builder::new()
+            .push(
+                FnBuilder::new(0, OpFrame::OpDefine(vec![Arg::AccountId]))
+                    .push(OpFrame::OpCheckPerms),
+            )
+            .build()
+            .unwrap();
+        let fragment_ops = fragment.disassemble().unwrap();
+        assert_eq!(
+            fragment_ops,
+            vec![
+                OpFrame::OpDefine(vec![Arg::AccountId]),
+                OpFrame::OpCheckPerms
+            ]
+        );
+
+        let script = Builder::new()
+            .push(FnBuilder::new(1, OpFrame::OpDefine(vec![])).push(OpFrame::True))
+            .push_raw(&fragment)
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut expected = vec![OpFrame::OpDefine(vec![]), OpFrame::True];
+        expected.extend(fragment_ops);
+        assert_eq!(script.disassemble().unwrap(), expected);
+        assert_eq!(script.get_fn_ptr(0).unwrap(), Some(14));
+        assert_eq!(script.get_fn_ptr(1).unwrap(), Some(11));
+
+        // Truncated op arguments
+        let mut bytes = fragment.to_vec();
+        bytes.truncate(bytes.len() - 1);
+        bytes.push(Operand::PushAccountId.into());
+        assert_eq!(
+            Builder::new().push_raw(&bytes).unwrap_err().err,
+            EvalErrKind::UnexpectedEOF
+        );
+
+        // Unknown op
+        let mut bytes = fragment.to_vec();
+        bytes.push(0xFF);
+        assert_eq!(
+            Builder::new().push_raw(&bytes).unwrap_err().err,
+            EvalErrKind::UnknownOp
+        );
+
+        // Function pointer not referencing a function definition
+        let mut bytes = fragment.to_vec();
+        bytes[4] += 1;
+        assert_eq!(
+            Builder::new().push_raw(&bytes).unwrap_err().err,
+            EvalErrKind::InvalidEntryPoint
+        );
+    }
+}
@@ -1,5 +1,9 @@
+use super::EvalErrKind;
 use crate::{account::AccountId, asset::Asset};
-use std::convert::TryFrom;
+use std::{
+    convert::{TryFrom, TryInto},
+    mem,
+};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
@@ -16,6 +20,7 @@ pub enum Operand {
     PushTrue = 0x21,
     PushAccountId = 0x22,
     PushAsset = 0x23,
+    PushInt = 0x24,
 
     // Arithmetic
     OpLoadAmt = 0x30,
@@ -42,6 +47,11 @@ pub enum Operand {
     // Lock time
     OpCheckTime = 0x60,
     OpCheckTimeFastFail = 0x61,
+
+    // Comparison
+    OpNumEqual = 0x70,
+    OpLessThan = 0x71,
+    OpGreaterThan = 0x72,
 }
 
 impl From<Operand> for u8 {
@@ -64,6 +74,7 @@ pub enum OpFrame {
     True,
     AccountId(AccountId),
     Asset(Asset),
+    Int(i64),
 
     // Arithmetic
     OpLoadAmt,
@@ -90,6 +101,11 @@ pub enum OpFrame {
     // Lock time
     OpCheckTime(u64), // Epoch time in seconds
     OpCheckTimeFastFail(u64),
+
+    // Comparison
+    OpNumEqual,
+    OpLessThan,
+    OpGreaterThan,
 }
 
 impl From<bool> for OpFrame {
@@ -127,3 +143,121 @@ impl Into<u8> for Arg {
         self as u8
     }
 }
+
+/// Decodes the op at `pos` in `code` and advances `pos` past it. Returns `None` when `pos` is at the
+/// end of the byte code.
+pub(crate) fn decode_op(code: &[u8], pos: &mut usize) -> Result<Option<OpFrame>, EvalErrKind> {
+    macro_rules! read_bytes {
+        ($len:expr) => {
+            match code.get(*pos..*pos + $len) {
+                Some(b) => {
+                    *pos += $len;
+                    b
+                }
+                None => {
+                    return Err(EvalErrKind::UnexpectedEOF);
+                }
+            }
+        };
+        () => {
+            match code.get(*pos) {
+                Some(b) => {
+                    *pos += 1;
+                    *b
+                }
+                None => {
+                    return Err(EvalErrKind::UnexpectedEOF);
+                }
+            }
+        };
+    }
+
+    if *pos == code.len() {
+        return Ok(None);
+    }
+    let byte = code[*pos];
+    *pos += 1;
+
+    match byte {
+        // Function definition
+        o if o == Operand::OpDefine as u8 => {
+            let arg_cnt = read_bytes!();
+            let mut args = Vec::with_capacity(usize::from(arg_cnt));
+            for _ in 0..arg_cnt {
+                let tag_byte = read_bytes!();
+                let arg = tag_byte
+                    .try_into()
+                    .map_err(|_| EvalErrKind::UnknownArgType)?;
+                args.push(arg);
+            }
+            Ok(Some(OpFrame::OpDefine(args)))
+        }
+        // Events
+        o if o == Operand::OpTransfer as u8 => Ok(Some(OpFrame::OpTransfer)),
+        o if o == Operand::OpDestroy as u8 => Ok(Some(OpFrame::OpDestroy)),
+        // Push value
+        o if o == Operand::PushFalse as u8 => Ok(Some(OpFrame::False)),
+        o if o == Operand::PushTrue as u8 => Ok(Some(OpFrame::True)),
+        o if o == Operand::PushAccountId as u8 => {
+            let slice = read_bytes!(mem::size_of::<u64>());
+            let id = u64::from_be_bytes(slice.try_into().unwrap());
+            Ok(Some(OpFrame::AccountId(id)))
+        }
+        o if o == Operand::PushAsset as u8 => {
+            let slice = read_bytes!(mem::size_of::<i64>());
+            let amt = i64::from_be_bytes(slice.try_into().unwrap());
+            let amt = Asset::new(amt);
+            Ok(Some(OpFrame::Asset(amt)))
+        }
+        o if o == Operand::PushInt as u8 => {
+            let slice = read_bytes!(mem::size_of::<i64>());
+            let int = i64::from_be_bytes(slice.try_into().unwrap());
+            Ok(Some(OpFrame::Int(int)))
+        }
+        // Arithmetic
+        o if o == Operand::OpLoadAmt as u8 => Ok(Some(OpFrame::OpLoadAmt)),
+        o if o == Operand::OpLoadRemAmt as u8 => Ok(Some(OpFrame::OpLoadRemAmt)),
+        o if o == Operand::OpAdd as u8 => Ok(Some(OpFrame::OpAdd)),
+        o if o == Operand::OpSub as u8 => Ok(Some(OpFrame::OpSub)),
+        o if o == Operand::OpMul as u8 => Ok(Some(OpFrame::OpMul)),
+        o if o == Operand::OpDiv as u8 => Ok(Some(OpFrame::OpDiv)),
+        // Logic
+        o if o == Operand::OpNot as u8 => Ok(Some(OpFrame::OpNot)),
+        o if o == Operand::OpIf as u8 => Ok(Some(OpFrame::OpIf)),
+        o if o == Operand::OpElse as u8 => Ok(Some(OpFrame::OpElse)),
+        o if o == Operand::OpEndIf as u8 => Ok(Some(OpFrame::OpEndIf)),
+        o if o == Operand::OpReturn as u8 => Ok(Some(OpFrame::OpReturn)),
+        o if o == Operand::OpAbort as u8 => Ok(Some(OpFrame::OpAbort)),
+        // Crypto
+        o if o == Operand::OpCheckPerms as u8 => Ok(Some(OpFrame::OpCheckPerms)),
+        o if o == Operand::OpCheckPermsFastFail as u8 => Ok(Some(OpFrame::OpCheckPermsFastFail)),
+        o if o == Operand::OpCheckMultiPerms as u8 => {
+            let threshold = read_bytes!();
+            let acc_count = read_bytes!();
+            Ok(Some(OpFrame::OpCheckMultiPerms(threshold, acc_count)))
+        }
+        o if o == Operand::OpCheckMultiPermsFastFail as u8 => {
+            let threshold = read_bytes!();
+            let acc_count = read_bytes!();
+            Ok(Some(OpFrame::OpCheckMultiPermsFastFail(
+                threshold, acc_count,
+            )))
+        }
+        // Lock time
+        o if o == Operand::OpCheckTime as u8 => {
+            let slice = read_bytes!(mem::size_of::<u64>());
+            let time = u64::from_be_bytes(slice.try_into().unwrap());
+            Ok(Some(OpFrame::OpCheckTime(time)))
+        }
+        o if o == Operand::OpCheckTimeFastFail as u8 => {
+            let slice = read_bytes!(mem::size_of::<u64>());
+            let time = u64::from_be_bytes(slice.try_into().unwrap());
+            Ok(Some(OpFrame::OpCheckTimeFastFail(time)))
+        }
+        // Comparison
+        o if o == Operand::OpNumEqual as u8 => Ok(Some(OpFrame::OpNumEqual)),
+        o if o == Operand::OpLessThan as u8 => Ok(Some(OpFrame::OpLessThan)),
+        o if o == Operand::OpGreaterThan as u8 => Ok(Some(OpFrame::OpGreaterThan)),
+        _ => Err(EvalErrKind::UnknownOp),
+    }
+}
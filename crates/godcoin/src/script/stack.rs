@@ -54,6 +54,14 @@ impl Stack {
         }
     }
 
+    pub fn pop_int(&mut self) -> Result<i64, EvalErrKind> {
+        let frame = self.pop()?;
+        match frame {
+            OpFrame::Int(int) => Ok(int),
+            _ => Err(EvalErrKind::InvalidItemOnStack),
+        }
+    }
+
     #[inline]
     #[cfg(test)]
     pub fn is_empty(&self) -> bool {
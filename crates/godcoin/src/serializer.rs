@@ -29,20 +29,64 @@ fn zigzag_decode(from: u64) -> i64 {
     ((from >> 1) ^ (-((from & 1) as i64)) as u64) as i64
 }
 
+/// Returns the number of bytes `push_varint` writes for `value`.
+pub fn varint_byte_size(value: u64) -> usize {
+    if value == 0 {
+        return 1;
+    }
+    let bits = 64 - value.leading_zeros() as usize;
+    (bits - 1) / 7 + 1
+}
+
+/// Returns the number of bytes `push_var_i64` writes for `value`.
+#[inline]
+pub fn var_i64_byte_size(value: i64) -> usize {
+    varint_byte_size(zigzag_encode(value))
+}
+
+/// Returns the number of bytes `push_bytes` writes for `value`.
+#[inline]
+pub fn bytes_byte_size(value: &[u8]) -> usize {
+    4 + value.len()
+}
+
 pub trait BufWrite {
+    fn push_u8(&mut self, value: u8);
     fn push_u16(&mut self, value: u16);
     fn push_u32(&mut self, value: u32);
     fn push_i64(&mut self, value: i64);
     fn push_var_i64(&mut self, value: i64);
+    fn push_varint(&mut self, value: u64);
     fn push_u64(&mut self, value: u64);
     fn push_bytes(&mut self, value: &[u8]);
     fn push_digest(&mut self, value: &Digest);
     fn push_pub_key(&mut self, value: &PublicKey);
     fn push_sig_pair(&mut self, value: &SigPair);
     fn push_asset(&mut self, value: Asset);
+
+    /// Pushes a presence byte of 0x01 followed by the value written by `push` when the value is
+    /// `Some`, otherwise a single 0x00 byte.
+    fn push_option<T, F>(&mut self, value: Option<T>, push: F)
+    where
+        Self: Sized,
+        F: FnOnce(&mut Self, T),
+    {
+        match value {
+            Some(value) => {
+                self.push_u8(0x01);
+                push(self, value);
+            }
+            None => self.push_u8(0x00),
+        }
+    }
 }
 
 impl BufWrite for Vec<u8> {
+    #[inline]
+    fn push_u8(&mut self, value: u8) {
+        self.push(value);
+    }
+
     #[inline]
     fn push_u16(&mut self, value: u16) {
         self.extend(&value.to_be_bytes());
@@ -76,6 +120,18 @@ impl BufWrite for Vec<u8> {
         }
     }
 
+    fn push_varint(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.push(byte);
+                break;
+            }
+            self.push(byte | 0x80);
+        }
+    }
+
     #[inline]
     fn push_u64(&mut self, value: u64) {
         self.extend(&value.to_be_bytes());
@@ -114,12 +170,31 @@ pub trait BufRead {
     fn take_u32(&mut self) -> Result<u32, Error>;
     fn take_i64(&mut self) -> Result<i64, Error>;
     fn take_var_i64(&mut self) -> Result<i64, Error>;
+    fn take_varint(&mut self) -> Result<u64, Error>;
     fn take_u64(&mut self) -> Result<u64, Error>;
     fn take_bytes(&mut self) -> Result<Vec<u8>, Error>;
+    fn take_bytes_capped(&mut self, max: usize) -> Result<Vec<u8>, Error>;
     fn take_digest(&mut self) -> Result<Digest, Error>;
     fn take_pub_key(&mut self) -> Result<PublicKey, Error>;
     fn take_sig_pair(&mut self) -> Result<SigPair, Error>;
     fn take_asset(&mut self) -> Result<Asset, Error>;
+
+    /// Reads a presence byte written by `push_option`, returning the value read by `take` when the
+    /// byte is 0x01. Any presence byte other than 0x00 or 0x01 is rejected.
+    fn take_option<T, F>(&mut self, take: F) -> Result<Option<T>, Error>
+    where
+        Self: Sized,
+        F: FnOnce(&mut Self) -> Result<T, Error>,
+    {
+        match self.take_u8()? {
+            0x00 => Ok(None),
+            0x01 => Ok(Some(take(self)?)),
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                "invalid option presence byte",
+            )),
+        }
+    }
 }
 
 impl<T: AsRef<[u8]> + Read> BufRead for Cursor<T> {
@@ -170,6 +245,29 @@ impl<T: AsRef<[u8]> + Read> BufRead for Cursor<T> {
         Ok(zigzag_decode(result))
     }
 
+    fn take_varint(&mut self) -> Result<u64, Error> {
+        let mut result: u64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.take_u8()?;
+            // The tenth byte may only carry the most significant bit of a u64
+            if shift == 63 && byte > 0x01 {
+                return Err(Error::new(ErrorKind::InvalidData, "overflow taking varint"));
+            }
+
+            result |= u64::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                // A trailing zero byte means the value could have been encoded in fewer bytes
+                if byte == 0 && shift > 0 {
+                    return Err(Error::new(ErrorKind::InvalidData, "overlong varint"));
+                }
+                return Ok(result);
+            }
+
+            shift += 7;
+        }
+    }
+
     fn take_u64(&mut self) -> Result<u64, Error> {
         let mut buf = [0u8; 8];
         self.read_exact(&mut buf)?;
@@ -177,7 +275,25 @@ impl<T: AsRef<[u8]> + Read> BufRead for Cursor<T> {
     }
 
     fn take_bytes(&mut self) -> Result<Vec<u8>, Error> {
+        self.take_bytes_capped(u32::MAX as usize)
+    }
+
+    fn take_bytes_capped(&mut self, max: usize) -> Result<Vec<u8>, Error> {
         let len = self.take_u32()? as usize;
+        if len > max {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "byte length exceeds maximum",
+            ));
+        }
+        // Never allocate more than the input can provide
+        let remaining = (self.get_ref().as_ref().len() as u64).saturating_sub(self.position());
+        if len as u64 > remaining {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "byte length exceeds input",
+            ));
+        }
         let buf = read_exact_bytes!(self, len);
         Ok(buf)
     }
@@ -232,6 +348,138 @@ mod tests {
         assert_eq!(num, dec);
     }
 
+    #[test]
+    fn bytes_serialization() {
+        let mut v = vec![];
+        v.push_bytes(&[1, 2, 3]);
+
+        let mut c = Cursor::<&[u8]>::new(&v);
+        assert_eq!(c.take_bytes().unwrap(), vec![1, 2, 3]);
+        let mut c = Cursor::<&[u8]>::new(&v);
+        assert_eq!(c.take_bytes_capped(3).unwrap(), vec![1, 2, 3]);
+        let mut c = Cursor::<&[u8]>::new(&v);
+        let err = c.take_bytes_capped(2).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn bytes_oversized_length_prefix() {
+        // Claims a 4GB payload with only a few bytes following
+        let mut v = vec![];
+        v.push_u32(u32::MAX);
+        v.extend_from_slice(&[1, 2, 3]);
+
+        let mut c = Cursor::<&[u8]>::new(&v);
+        let err = c.take_bytes_capped(1024).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        let mut c = Cursor::<&[u8]>::new(&v);
+        let err = c.take_bytes().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn varint_serialization() {
+        fn check(value: u64, encoded: &[u8]) {
+            let mut v = vec![];
+            v.push_varint(value);
+            assert_eq!(v, encoded);
+
+            let mut c = Cursor::<&[u8]>::new(&v);
+            assert_eq!(c.take_varint().unwrap(), value);
+            assert_eq!(c.position() as usize, v.len());
+        }
+
+        check(0, &[0x00]);
+        check(1, &[0x01]);
+        check(127, &[0x7F]);
+        check(128, &[0x80, 0x01]);
+        check(300, &[0xAC, 0x02]);
+        check(16_383, &[0xFF, 0x7F]);
+        check(16_384, &[0x80, 0x80, 0x01]);
+        check(
+            u64::MAX,
+            &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01],
+        );
+    }
+
+    #[test]
+    fn varint_byte_sizes() {
+        let values = [0, 1, 127, 128, 16_383, 16_384, u64::MAX >> 1, u64::MAX];
+        for value in values.iter() {
+            let mut v = vec![];
+            v.push_varint(*value);
+            assert_eq!(varint_byte_size(*value), v.len());
+        }
+
+        let values = [0, 1, -1, 63, -64, 64, -65, i64::MIN, i64::MAX];
+        for value in values.iter() {
+            let mut v = vec![];
+            v.push_var_i64(*value);
+            assert_eq!(var_i64_byte_size(*value), v.len());
+        }
+    }
+
+    #[test]
+    fn varint_rejects_malformed_input() {
+        fn take(bytes: &[u8]) -> Result<u64, Error> {
+            Cursor::<&[u8]>::new(bytes).take_varint()
+        }
+
+        // Continuation bit set without a following byte
+        assert_eq!(take(&[0x80]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(
+            take(&[0xFF, 0xFF]).unwrap_err().kind(),
+            ErrorKind::UnexpectedEof
+        );
+
+        // Overlong encodings of 0 and 1
+        assert_eq!(
+            take(&[0x80, 0x00]).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(
+            take(&[0x81, 0x80, 0x00]).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+
+        // Values that overflow a u64
+        assert_eq!(
+            take(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(
+            take(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x81, 0x00])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn option_serialization() {
+        let asset: Asset = "12.34567 TEST".parse().unwrap();
+        let mut v = vec![];
+        v.push_option(Some(asset), |buf, a| buf.push_asset(a));
+        v.push_option(None, |buf, a| buf.push_asset(a));
+        assert_eq!(v[0], 0x01);
+        assert_eq!(v[v.len() - 1], 0x00);
+
+        let mut c = Cursor::<&[u8]>::new(&v);
+        assert_eq!(c.take_option(|c| c.take_asset()).unwrap(), Some(asset));
+        assert_eq!(c.take_option(|c| c.take_asset()).unwrap(), None);
+        assert_eq!(c.position() as usize, v.len());
+
+        for byte in &[0x02, 0xFF] {
+            let buf = [*byte, 0x00];
+            let mut c = Cursor::<&[u8]>::new(&buf);
+            let err = c.take_option(|c| c.take_asset()).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+        }
+    }
+
     #[test]
     fn asset_serialization() {
         let a = "12.34567 TEST".parse().unwrap();
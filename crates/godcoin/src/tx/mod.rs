@@ -1,5 +1,6 @@
 use std::{
     borrow::Cow,
+    cmp,
     io::Cursor,
     ops::{Deref, DerefMut},
 };
@@ -7,11 +8,15 @@ use std::{
 use crate::{
     account::{Account, AccountId, Permissions},
     asset::Asset,
-    constants::CHAIN_ID,
+    constants::{
+        CHAIN_ID, MAX_FIELD_DECODE_SIZE, MAX_MEMO_DECODE_SIZE, MAX_SCRIPT_DECODE_SIZE,
+        MAX_TX_SIGNATURES,
+    },
     crypto::{Digest, DoubleSha256, KeyPair, PublicKey, SigPair},
     script::Script,
     serializer::*,
 };
+use sodiumoxide::crypto::sign::{PUBLICKEYBYTES, SIGNATUREBYTES};
 
 #[macro_use]
 mod util;
@@ -28,13 +33,16 @@ pub enum TxType {
 
 pub trait SerializeTx {
     fn serialize(&self, v: &mut Vec<u8>);
+
+    /// Returns the exact number of bytes `serialize` writes.
+    fn byte_size(&self) -> usize;
 }
 
 pub trait DeserializeTx<T> {
     fn deserialize(cur: &mut Cursor<&[u8]>, tx: Tx) -> Option<T>;
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct TxId(Digest);
 
 impl TxId {
@@ -111,6 +119,13 @@ impl TxVariant {
         }
     }
 
+    #[inline]
+    pub fn fee(&self) -> Asset {
+        match self {
+            TxVariant::V0(tx) => tx.fee,
+        }
+    }
+
     #[inline]
     pub fn sigs(&self) -> &[SigPair] {
         match self {
@@ -125,9 +140,43 @@ impl TxVariant {
         }
     }
 
+    /// Returns the script carried by the transaction. Only account creation and account updates
+    /// that replace the script carry one.
+    pub fn script(&self) -> Option<&Script> {
+        match self {
+            TxVariant::V0(tx) => match tx {
+                TxVariantV0::OwnerTx(_) => None,
+                TxVariantV0::MintTx(_) => None,
+                TxVariantV0::CreateAccountTx(tx) => Some(&tx.account.script),
+                TxVariantV0::UpdateAccountTx(tx) => tx.new_script.as_ref(),
+                TxVariantV0::TransferTx(_) => None,
+            },
+        }
+    }
+
+    /// Returns the exact number of bytes `serialize` writes, including the signature pairs.
+    pub fn byte_size(&self) -> usize {
+        match self {
+            TxVariant::V0(var) => {
+                let sigs = &var.signature_pairs;
+                let sigs_size = varint_byte_size(sigs.len() as u64)
+                    + sigs.len() * (PUBLICKEYBYTES + SIGNATUREBYTES);
+                let tx_size = match var {
+                    TxVariantV0::OwnerTx(tx) => tx.byte_size(),
+                    TxVariantV0::MintTx(tx) => tx.byte_size(),
+                    TxVariantV0::CreateAccountTx(tx) => tx.byte_size(),
+                    TxVariantV0::UpdateAccountTx(tx) => tx.byte_size(),
+                    TxVariantV0::TransferTx(tx) => tx.byte_size(),
+                };
+                // Tx version (2 bytes)
+                2 + tx_size + sigs_size
+            }
+        }
+    }
+
     #[inline]
     pub fn calc_txid(&self) -> TxId {
-        let mut buf = Vec::with_capacity(4096);
+        let mut buf = Vec::with_capacity(self.byte_size());
         self.serialize_without_sigs(&mut buf);
 
         let digest = {
@@ -157,7 +206,7 @@ impl TxVariant {
             TxVariant::V0(var) => {
                 macro_rules! serialize_sigs {
                     ($name:expr) => {{
-                        buf.push($name.signature_pairs.len() as u8);
+                        buf.push_varint($name.signature_pairs.len() as u64);
                         for sig in &$name.signature_pairs {
                             buf.push_sig_pair(sig)
                         }
@@ -211,8 +260,10 @@ impl TxVariant {
                     }
                 };
                 tx.signature_pairs = {
-                    let len = cur.take_u8().ok()?;
-                    let mut sigs = Vec::with_capacity(len as usize);
+                    let len = cur.take_varint().ok()?;
+                    // The count is untrusted, avoid preallocating more than a valid tx can hold
+                    let mut sigs =
+                        Vec::with_capacity(cmp::min(len, MAX_TX_SIGNATURES as u64) as usize);
                     for _ in 0..len {
                         sigs.push(cur.take_sig_pair().ok()?)
                     }
@@ -288,6 +339,12 @@ impl Tx {
         v.push_asset(self.fee);
     }
 
+    /// Returns the number of bytes written by the tx type and `serialize_header`.
+    #[inline]
+    fn header_byte_size(&self) -> usize {
+        1 + 4 + 8 + var_i64_byte_size(self.fee.amount)
+    }
+
     fn deserialize_header(cur: &mut Cursor<&[u8]>) -> Option<(Tx, TxType)> {
         let tx_type = match cur.take_u8().ok()? {
             t if t == TxType::Owner as u8 => TxType::Owner,
@@ -325,6 +382,10 @@ impl SerializeTx for OwnerTx {
         v.push_pub_key(&self.minter);
         v.push_u64(self.wallet);
     }
+
+    fn byte_size(&self) -> usize {
+        self.header_byte_size() + PUBLICKEYBYTES + 8
+    }
 }
 
 impl DeserializeTx<OwnerTx> for OwnerTx {
@@ -357,15 +418,23 @@ impl SerializeTx for MintTx {
         v.push_bytes(&self.attachment);
         v.push_bytes(self.attachment_name.as_bytes());
     }
+
+    fn byte_size(&self) -> usize {
+        self.header_byte_size()
+            + 8
+            + var_i64_byte_size(self.amount.amount)
+            + bytes_byte_size(&self.attachment)
+            + bytes_byte_size(self.attachment_name.as_bytes())
+    }
 }
 
 impl DeserializeTx<MintTx> for MintTx {
     fn deserialize(cur: &mut Cursor<&[u8]>, tx: Tx) -> Option<Self> {
         let to = cur.take_u64().ok()?;
         let amount = cur.take_asset().ok()?;
-        let attachment = cur.take_bytes().ok()?;
+        let attachment = cur.take_bytes_capped(MAX_FIELD_DECODE_SIZE).ok()?;
         let attachment_name = {
-            let bytes = cur.take_bytes().ok()?;
+            let bytes = cur.take_bytes_capped(MAX_FIELD_DECODE_SIZE).ok()?;
             String::from_utf8(bytes).ok()?
         };
         Some(Self {
@@ -392,6 +461,10 @@ impl SerializeTx for CreateAccountTx {
         buf.push_u64(self.creator);
         self.account.serialize(buf);
     }
+
+    fn byte_size(&self) -> usize {
+        self.header_byte_size() + 8 + self.account.byte_size()
+    }
 }
 
 impl DeserializeTx<CreateAccountTx> for CreateAccountTx {
@@ -434,13 +507,21 @@ impl SerializeTx for UpdateAccountTx {
             None => buf.push(0x00),
         }
     }
+
+    fn byte_size(&self) -> usize {
+        let script_size = self.new_script.as_ref().map_or(0, |s| bytes_byte_size(s));
+        let perms_size = self.new_permissions.as_ref().map_or(0, |p| p.byte_size());
+        self.header_byte_size() + 8 + 1 + script_size + 1 + perms_size
+    }
 }
 
 impl DeserializeTx<UpdateAccountTx> for UpdateAccountTx {
     fn deserialize(cur: &mut Cursor<&[u8]>, tx: Tx) -> Option<Self> {
         let account_id = cur.take_u64().ok()?;
         let new_script = match cur.take_u8().ok()? {
-            0x01 => Some(Script::new(cur.take_bytes().ok()?)),
+            0x01 => Some(Script::new(
+                cur.take_bytes_capped(MAX_SCRIPT_DECODE_SIZE).ok()?,
+            )),
             0x00 => None,
             _ => return None,
         };
@@ -478,15 +559,24 @@ impl SerializeTx for TransferTx {
         v.push_asset(self.amount);
         v.push_bytes(&self.memo);
     }
+
+    fn byte_size(&self) -> usize {
+        self.header_byte_size()
+            + 8
+            + 1
+            + bytes_byte_size(&self.args)
+            + var_i64_byte_size(self.amount.amount)
+            + bytes_byte_size(&self.memo)
+    }
 }
 
 impl DeserializeTx<TransferTx> for TransferTx {
     fn deserialize(cur: &mut Cursor<&[u8]>, tx: Tx) -> Option<TransferTx> {
         let from = cur.take_u64().ok()?;
         let call_fn = cur.take_u8().ok()?;
-        let args = cur.take_bytes().ok()?;
+        let args = cur.take_bytes_capped(MAX_FIELD_DECODE_SIZE).ok()?;
         let amount = cur.take_asset().ok()?;
-        let memo = cur.take_bytes().ok()?;
+        let memo = cur.take_bytes_capped(MAX_MEMO_DECODE_SIZE).ok()?;
         Some(TransferTx {
             base: tx,
             from,
@@ -545,6 +635,162 @@ mod tests {
         assert_eq!(owner_tx.sigs()[1], dec.sigs()[1]);
     }
 
+    #[test]
+    fn serialize_tx_with_many_sigs() {
+        let key = crypto::KeyPair::gen();
+        let mut tx = TxVariant::V0(TxVariantV0::OwnerTx(OwnerTx {
+            base: Tx {
+                nonce: 123456789,
+                expiry: 1230,
+                fee: get_asset("123.00000 TEST"),
+                signature_pairs: vec![],
+            },
+            minter: key.0.clone(),
+            wallet: 0xFF,
+        }));
+        // Counts beyond a single byte must not be truncated
+        (0..300).for_each(|_| tx.append_sign(&key));
+
+        let mut v = vec![];
+        tx.serialize(&mut v);
+
+        let mut c = Cursor::<&[u8]>::new(&v);
+        let dec = TxVariant::deserialize(&mut c).unwrap();
+        assert_eq!(dec.sigs().len(), 300);
+        assert_eq!(tx, dec);
+    }
+
+    #[test]
+    fn tx_script() {
+        let key = crypto::KeyPair::gen();
+        let base = Tx {
+            nonce: 123456789,
+            expiry: 1230,
+            fee: get_asset("1.00000 TEST"),
+            signature_pairs: vec![],
+        };
+        let script = Script::new(vec![0x00, 0x01, 0x02]);
+        let mut account = Account::create_default(
+            1234,
+            Permissions {
+                threshold: 1,
+                keys: vec![key.0.clone()],
+            },
+        );
+        account.script = script.clone();
+
+        let owner_tx = TxVariant::V0(TxVariantV0::OwnerTx(OwnerTx {
+            base: base.clone(),
+            minter: key.0.clone(),
+            wallet: 0xFF,
+        }));
+        assert_eq!(owner_tx.script(), None);
+
+        let mint_tx = TxVariant::V0(TxVariantV0::MintTx(MintTx {
+            base: base.clone(),
+            to: 0xFF,
+            amount: get_asset("1.00000 TEST"),
+            attachment: vec![],
+            attachment_name: "".to_string(),
+        }));
+        assert_eq!(mint_tx.script(), None);
+
+        let create_tx = TxVariant::V0(TxVariantV0::CreateAccountTx(CreateAccountTx {
+            base: base.clone(),
+            creator: 0xFF,
+            account,
+        }));
+        assert_eq!(create_tx.script(), Some(&script));
+
+        let update_tx = |new_script: Option<Script>| {
+            TxVariant::V0(TxVariantV0::UpdateAccountTx(UpdateAccountTx {
+                base: base.clone(),
+                account_id: 0xFF,
+                new_script,
+                new_permissions: None,
+            }))
+        };
+        assert_eq!(update_tx(None).script(), None);
+        assert_eq!(update_tx(Some(script.clone())).script(), Some(&script));
+
+        let transfer_tx = TxVariant::V0(TxVariantV0::TransferTx(TransferTx {
+            base: base.clone(),
+            from: 0xFF,
+            call_fn: 0,
+            args: vec![],
+            amount: get_asset("1.00000 TEST"),
+            memo: vec![],
+        }));
+        assert_eq!(transfer_tx.script(), None);
+    }
+
+    #[test]
+    fn byte_size_matches_serialized_len() {
+        let key = crypto::KeyPair::gen();
+        let base = |fee: &str| Tx {
+            nonce: 123456789,
+            expiry: 1230,
+            fee: get_asset(fee),
+            signature_pairs: vec![],
+        };
+        let perms = Permissions {
+            threshold: 1,
+            keys: vec![key.0.clone(), crypto::KeyPair::gen().0],
+        };
+        let mut account = Account::create_default(1234, perms.clone());
+        account.balance = get_asset("-5.00000 TEST");
+
+        let txs = vec![
+            TxVariant::V0(TxVariantV0::OwnerTx(OwnerTx {
+                base: base("0.00000 TEST"),
+                minter: key.0.clone(),
+                wallet: 0xFF,
+            })),
+            TxVariant::V0(TxVariantV0::MintTx(MintTx {
+                base: base("1.00000 TEST"),
+                to: 0xFF,
+                amount: get_asset("123456789.00000 TEST"),
+                attachment: vec![1; 300],
+                attachment_name: "abc.pdf".to_string(),
+            })),
+            TxVariant::V0(TxVariantV0::CreateAccountTx(CreateAccountTx {
+                base: base("2.50000 TEST"),
+                creator: 0xFF,
+                account,
+            })),
+            TxVariant::V0(TxVariantV0::UpdateAccountTx(UpdateAccountTx {
+                base: base("0.00001 TEST"),
+                account_id: 0xFF,
+                new_script: None,
+                new_permissions: None,
+            })),
+            TxVariant::V0(TxVariantV0::UpdateAccountTx(UpdateAccountTx {
+                base: base("0.00001 TEST"),
+                account_id: 0xFF,
+                new_script: Some(Script::new(vec![0; 200])),
+                new_permissions: Some(perms),
+            })),
+            TxVariant::V0(TxVariantV0::TransferTx(TransferTx {
+                base: base("10.00000 TEST"),
+                from: 0xFF,
+                call_fn: 1,
+                args: vec![1, 2, 3],
+                amount: get_asset("1.00000 TEST"),
+                memo: vec![4; 1024],
+            })),
+        ];
+
+        for mut tx in txs {
+            for sig_count in &[0, 1, 200] {
+                tx.sigs_mut().clear();
+                (0..*sig_count).for_each(|_| tx.append_sign(&key));
+                let mut v = vec![];
+                tx.serialize(&mut v);
+                assert_eq!(tx.byte_size(), v.len());
+            }
+        }
+    }
+
     #[test]
     fn serialize_owner() {
         let minter = crypto::KeyPair::gen();
@@ -1,4 +1,4 @@
-use clap::{App, Arg};
+use clap::{App, Arg, SubCommand};
 use godcoin::{blockchain::ReindexOpts, prelude::*};
 use hyper::{
     service::{make_service_fn, service_fn},
@@ -9,6 +9,7 @@ use serde::Deserialize;
 use std::{
     env, fs,
     path::{Path, PathBuf},
+    time::Duration,
 };
 use tokio::runtime::Builder;
 use tracing::{error, info};
@@ -20,6 +21,13 @@ struct Config {
     enable_stale_production: bool,
     bind_address: Option<String>,
     metrics_bind_address: Option<String>,
+    /// Interval in seconds between heartbeat pings sent to clients.
+    heartbeat_interval: Option<u64>,
+    /// Time in seconds a client may leave a ping unanswered before it is disconnected.
+    heartbeat_timeout: Option<u64>,
+    compress_blocks: Option<bool>,
+    /// Whether an incomplete index is rebuilt on startup without passing `--reindex`.
+    auto_reindex: Option<bool>,
 }
 
 fn main() {
@@ -31,6 +39,69 @@ fn main() {
     godcoin::init().unwrap();
     godcoin_server::init();
 
+    let home = {
+        match env::var("GODCOIN_HOME") {
+            Ok(s) => PathBuf::from(s),
+            Err(_) => Path::join(&dirs::data_local_dir().unwrap(), "godcoin"),
+        }
+    };
+
+    let home = home.to_string_lossy();
+    let args = App::new("godcoin-server")
+        .about("GODcoin core server daemon")
+        .version(env!("CARGO_PKG_VERSION"))
+        .arg(
+            Arg::with_name("home")
+                .long("home")
+                .global(true)
+                .default_value(&home)
+                .empty_values(false)
+                .help("Home directory which defaults to env var GODCOIN_HOME"),
+        )
+        .arg(
+            Arg::with_name("reindex")
+                .long("reindex")
+                .help("Reindexes the block log"),
+        )
+        .arg(
+            Arg::with_name("auto_trim")
+                .long("reindex-trim-corrupt")
+                .global(true)
+                .help("Trims any corruption detected in the block log during reindexing"),
+        )
+        .subcommand(
+            SubCommand::with_name("reindex")
+                .about("Rebuilds the index from the block log and exits"),
+        )
+        .get_matches();
+
+    let reindex_cmd = args.subcommand_matches("reindex");
+    let (home, auto_trim) = {
+        // Global arguments passed after the subcommand are only present in its matches
+        let args = reindex_cmd.unwrap_or(&args);
+        let home = PathBuf::from(args.value_of("home").expect("Failed to obtain home path"));
+        (home, args.is_present("auto_trim"))
+    };
+
+    if reindex_cmd.is_some() {
+        let blocklog_loc = Path::join(&home, "blklog");
+        if !Path::is_file(&blocklog_loc) {
+            error!("No block log found at {:?}", blocklog_loc);
+            std::process::exit(1);
+        }
+        let index_loc = Path::join(&home, "index");
+        let config = load_config(&home);
+        let compress_blocks = config.compress_blocks.unwrap_or(false);
+        let chain = godcoin_server::open_chain(&blocklog_loc, &index_loc, compress_blocks);
+        if let Err(e) = godcoin_server::replay_block_log(&chain, ReindexOpts { auto_trim }) {
+            error!("Failed to replay the block log: {:?}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let reindex_requested = args.is_present("reindex");
+
     let mut rt = Builder::new()
         .threaded_scheduler()
         .enable_all()
@@ -38,37 +109,6 @@ fn main() {
         .unwrap();
 
     rt.spawn(async move {
-        let home = {
-            match env::var("GODCOIN_HOME") {
-                Ok(s) => PathBuf::from(s),
-                Err(_) => Path::join(&dirs::data_local_dir().unwrap(), "godcoin"),
-            }
-        };
-
-        let home = home.to_string_lossy();
-        let args = App::new("godcoin-server")
-            .about("GODcoin core server daemon")
-            .version(env!("CARGO_PKG_VERSION"))
-            .arg(
-                Arg::with_name("home")
-                    .long("home")
-                    .default_value(&home)
-                    .empty_values(false)
-                    .help("Home directory which defaults to env var GODCOIN_HOME"),
-            )
-            .arg(
-                Arg::with_name("reindex")
-                    .long("reindex")
-                    .help("Reindexes the block log"),
-            )
-            .arg(
-                Arg::with_name("auto_trim")
-                    .long("reindex-trim-corrupt")
-                    .help("Trims any corruption detected in the block log during reindexing"),
-            )
-            .get_matches();
-
-        let home = PathBuf::from(args.value_of("home").expect("Failed to obtain home path"));
         let (blocklog_loc, index_loc) = {
             if !Path::is_dir(&home) {
                 let res = std::fs::create_dir(&home);
@@ -82,10 +122,7 @@ fn main() {
             (blocklog_loc, index_loc)
         };
 
-        let config_file = Path::join(&home, "config.toml");
-        info!("Opening configuration file at {:?}", config_file);
-        let config_file = fs::read(config_file).expect("Failed to open config");
-        let config: Config = toml::from_str(&String::from_utf8(config_file).unwrap()).unwrap();
+        let config = load_config(&home);
 
         if let Some(bind_address) = config.metrics_bind_address {
             let service = make_service_fn(|_| async {
@@ -122,7 +159,7 @@ fn main() {
             .bind_address
             .unwrap_or_else(|| "127.0.0.1:7777".to_string());
 
-        let reindex = if args.is_present("reindex") {
+        let reindex = if reindex_requested {
             info!("User requested reindexing");
             if Path::exists(&index_loc) {
                 info!("Deleting current index");
@@ -131,13 +168,19 @@ fn main() {
             } else {
                 info!("Current index does not exist");
             }
-            let auto_trim = args.is_present("auto_trim");
             Some(ReindexOpts { auto_trim })
         } else {
             None
         };
 
         let enable_stale_production = config.enable_stale_production;
+        let heartbeat_interval = config
+            .heartbeat_interval
+            .map(Duration::from_secs)
+            .unwrap_or(godcoin_server::client::DEFAULT_HEARTBEAT_INTERVAL);
+        let heartbeat_timeout = config.heartbeat_timeout.map(Duration::from_secs).unwrap_or(
+            heartbeat_interval * godcoin_server::client::DEFAULT_HEARTBEAT_TIMEOUT_INTERVALS,
+        );
         godcoin_server::start(godcoin_server::ServerOpts {
             blocklog_loc,
             index_loc,
@@ -145,6 +188,10 @@ fn main() {
             bind_addr,
             reindex,
             enable_stale_production,
+            heartbeat_interval,
+            heartbeat_timeout,
+            compress_blocks: config.compress_blocks.unwrap_or(false),
+            auto_reindex: config.auto_reindex.unwrap_or(false),
         });
     });
 
@@ -154,6 +201,13 @@ fn main() {
     });
 }
 
+fn load_config(home: &Path) -> Config {
+    let config_file = Path::join(home, "config.toml");
+    info!("Opening configuration file at {:?}", config_file);
+    let config_file = fs::read(config_file).expect("Failed to open config");
+    toml::from_str(&String::from_utf8(config_file).unwrap()).unwrap()
+}
+
 fn install_panic_hook() {
     let default_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic_info| {
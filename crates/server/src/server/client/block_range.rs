@@ -36,19 +36,16 @@ impl Stream for AsyncBlockRange {
 
     fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Option<Self::Item>> {
         if self.min_height <= self.max_height {
+            // The range ends early when the chain is rolled back while it is being streamed
             let block = match self.filter {
-                Some(ref filter) => self
+                Some(ref filter) => self.chain.get_filtered_block(self.min_height, filter),
+                None => self
                     .chain
-                    .get_filtered_block(self.min_height, filter)
-                    .unwrap_or_else(|| unreachable!()),
-                None => FilteredBlock::Block(
-                    self.chain
-                        .get_block(self.min_height)
-                        .unwrap_or_else(|| unreachable!()),
-                ),
+                    .get_block(self.min_height)
+                    .map(FilteredBlock::Block),
             };
             self.min_height += 1;
-            Poll::Ready(Some(block))
+            Poll::Ready(block)
         } else {
             Poll::Ready(None)
         }
@@ -21,6 +21,13 @@ use tokio_tungstenite::tungstenite::{protocol, Message as WsMessage};
 use tracing::{debug, error, info, warn};
 use tracing_futures::Instrument;
 
+/// Default interval between heartbeat pings.
+pub const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(20);
+
+/// Default number of heartbeat intervals a client may stay silent after a ping before it is
+/// considered dead and is disconnected.
+pub const DEFAULT_HEARTBEAT_TIMEOUT_INTERVALS: u32 = 3;
+
 pub struct WsClient {
     filter: Option<BlockFilter>,
     addr: SocketAddr,
@@ -65,7 +72,13 @@ impl WsClient {
     }
 }
 
-pub fn handle_new_client(stream: TcpStream, peer_addr: SocketAddr, data: Arc<ServerData>) {
+pub fn handle_new_client(
+    stream: TcpStream,
+    peer_addr: SocketAddr,
+    data: Arc<ServerData>,
+    heartbeat_interval: Duration,
+    heartbeat_timeout: Duration,
+) {
     let config = Some(protocol::WebSocketConfig {
         // # of protocol Message's
         max_send_queue: Some(16),
@@ -123,36 +136,10 @@ pub fn handle_new_client(stream: TcpStream, peer_addr: SocketAddr, data: Arc<Ser
                 warn!("Sink send error: {:?}", e);
             }));
 
-        let heartbeat_interval = async move {
-            let dur = Duration::from_secs(20);
-            let mut interval = time::interval_at(time::Instant::now() + dur, dur);
-            loop {
-                interval.tick().await;
-                if needs_pong.swap(true, Ordering::AcqRel) {
-                    debug!("Did not receive pong in time from peer");
-                    break;
-                }
-
-                let nonce = get_epoch_time();
-                let msg = Msg {
-                    id: u32::max_value(),
-                    body: Body::Ping(nonce),
-                };
-                debug!("Sending ping: {}", nonce);
-
-                let mut buf = Vec::with_capacity(16);
-                msg.serialize(&mut buf);
-
-                if tx.clone().send(WsMessage::Binary(buf)).await.is_err() {
-                    break;
-                }
-            }
-        };
-
         tokio::select! {
             _ = ws_reader => {},
             _ = ws_writer => {},
-            _ = heartbeat_interval => {},
+            _ = heartbeat(heartbeat_interval, heartbeat_timeout, needs_pong, tx) => {},
         };
 
         info!("Connection closed");
@@ -164,6 +151,43 @@ pub fn handle_new_client(stream: TcpStream, peer_addr: SocketAddr, data: Arc<Ser
     tokio::spawn(client_fut.instrument(span));
 }
 
+/// Sends a ping to the client every `interval`. The future completes when the client has not
+/// responded to the oldest unanswered ping within `timeout` or when the client's sender is closed.
+pub async fn heartbeat(
+    interval: Duration,
+    timeout: Duration,
+    needs_pong: Arc<AtomicBool>,
+    tx: Sender<WsMessage>,
+) {
+    let mut interval = time::interval_at(time::Instant::now() + interval, interval);
+    let mut unanswered_since = time::Instant::now();
+    loop {
+        let now = interval.tick().await;
+        if needs_pong.swap(true, Ordering::AcqRel) {
+            if now.duration_since(unanswered_since) >= timeout {
+                debug!("Did not receive pong in time from peer");
+                break;
+            }
+        } else {
+            unanswered_since = now;
+        }
+
+        let nonce = get_epoch_time();
+        let msg = Msg {
+            id: u32::max_value(),
+            body: Body::Ping(nonce),
+        };
+        debug!("Sending ping: {}", nonce);
+
+        let mut buf = Vec::with_capacity(16);
+        msg.serialize(&mut buf);
+
+        if tx.clone().send(WsMessage::Binary(buf)).await.is_err() {
+            break;
+        }
+    }
+}
+
 pub fn process_ws_msg(
     data: &ServerData,
     state: &mut WsClient,
@@ -245,10 +269,16 @@ fn handle_rpc_request(
         rpc::Request::Broadcast(tx) => {
             REQ_BROADCAST_TOTAL.inc();
             let req_timer = REQ_BROADCAST_DUR.start_timer();
-            let res = data.minter.push_tx(tx);
+            let res = data.minter.push_tx(tx.clone());
             req_timer.stop_and_record();
             match res {
-                Ok(_) => Body::Response(rpc::Response::Broadcast),
+                Ok(_) => {
+                    data.sub_pool.broadcast(
+                        rpc::SubscriptionKind::Txs,
+                        Body::Request(rpc::Request::Broadcast(tx)),
+                    );
+                    Body::Response(rpc::Response::Broadcast)
+                }
                 Err(e) => {
                     REQ_BROADCAST_FAIL.inc();
                     Body::Error(ErrorKind::TxValidation(e))
@@ -270,9 +300,9 @@ fn handle_rpc_request(
             req_timer.stop_and_record();
             Body::Response(rpc::Response::ClearBlockFilter)
         }
-        rpc::Request::Subscribe => {
+        rpc::Request::Subscribe(kind) => {
             let req_timer = REQ_SUBSCRIBE_DUR.start_timer();
-            data.sub_pool.insert(state.addr(), state.sender());
+            data.sub_pool.insert(state.addr(), state.sender(), kind);
             req_timer.stop_and_record();
             Body::Response(rpc::Response::Subscribe)
         }
@@ -378,5 +408,97 @@ fn handle_rpc_request(
                 Err(e) => Body::Error(ErrorKind::TxValidation(e)),
             }
         }
+        rpc::Request::GetBlockHeader(height) => {
+            let req_timer = REQ_GET_BLOCK_HEADER_DUR.start_timer();
+            let res = match data.chain.get_block_header(height) {
+                Some((header, signer)) => {
+                    Body::Response(rpc::Response::GetBlockHeader { header, signer })
+                }
+                None => Body::Error(ErrorKind::InvalidHeight),
+            };
+            req_timer.stop_and_record();
+            res
+        }
+        rpc::Request::GetBlockHeaderRange(min_height, max_height) => {
+            let req_timer = REQ_GET_BLOCK_HEADER_RANGE_DUR.start_timer();
+            if min_height > max_height || max_height > data.chain.get_chain_height() {
+                req_timer.stop_and_record();
+                return Some(Body::Error(ErrorKind::InvalidHeight));
+            } else if max_height - min_height >= rpc::MAX_BLOCK_HEADER_RANGE {
+                req_timer.stop_and_record();
+                return Some(Body::Error(ErrorKind::InvalidRequest));
+            }
+
+            let mut headers = Vec::with_capacity((max_height - min_height + 1) as usize);
+            for height in min_height..=max_height {
+                match data.chain.get_block_header(height) {
+                    Some(header) => headers.push(header),
+                    None => {
+                        // The chain was rolled back after the heights were checked
+                        req_timer.stop_and_record();
+                        return Some(Body::Error(ErrorKind::InvalidHeight));
+                    }
+                }
+            }
+            req_timer.stop_and_record();
+            Body::Response(rpc::Response::GetBlockHeaderRange(headers))
+        }
+        rpc::Request::GetBlockBatch { start, count } => {
+            let req_timer = REQ_GET_BLOCK_BATCH_DUR.start_timer();
+            let count = u64::from(count.min(rpc::MAX_BLOCK_BATCH));
+            let mut blocks = Vec::with_capacity(count as usize);
+            // Only the blocks that are available are returned when the batch extends past the
+            // chain head
+            for height in start..start.saturating_add(count) {
+                match data.chain.get_block(height) {
+                    Some(block) => blocks.push(block),
+                    None => break,
+                }
+            }
+            req_timer.stop_and_record();
+            Body::Response(rpc::Response::GetBlockBatch(blocks))
+        }
+        rpc::Request::GetAccount(acc) => {
+            let req_timer = REQ_GET_ACC_DUR.start_timer();
+            let acc = data.chain.get_account(acc, &[]);
+            req_timer.stop_and_record();
+            Body::Response(rpc::Response::GetAccount(acc))
+        }
+        rpc::Request::GetTransaction(id) => {
+            let req_timer = REQ_GET_TX_DUR.start_timer();
+            let tx = data.chain.get_transaction(&id);
+            req_timer.stop_and_record();
+            Body::Response(rpc::Response::GetTransaction(tx))
+        }
+        rpc::Request::CheckTx(tx) => {
+            let req_timer = REQ_CHECK_TX_DUR.start_timer();
+            let res = data.minter.check_tx(tx);
+            req_timer.stop_and_record();
+            match res {
+                Ok(()) => Body::Response(rpc::Response::CheckTx),
+                Err(e) => Body::Error(ErrorKind::TxValidation(e)),
+            }
+        }
+        rpc::Request::GetScript(hash) => {
+            let req_timer = REQ_GET_SCRIPT_DUR.start_timer();
+            let script = data.chain.get_script(&hash);
+            req_timer.stop_and_record();
+            Body::Response(rpc::Response::GetScript(script))
+        }
+        rpc::Request::GetBalances(ids) => {
+            let req_timer = REQ_GET_BALANCES_DUR.start_timer();
+            if ids.len() > usize::from(rpc::MAX_BALANCE_BATCH) {
+                return Some(Body::Error(ErrorKind::InvalidRequest));
+            }
+            let balances = data.chain.get_balances(&ids, &[]);
+            req_timer.stop_and_record();
+            Body::Response(rpc::Response::GetBalances(balances))
+        }
+        rpc::Request::GetAccountNonce(acc) => {
+            let req_timer = REQ_GET_ACC_NONCE_DUR.start_timer();
+            let nonce = data.minter.get_account_nonce(acc);
+            req_timer.stop_and_record();
+            Body::Response(rpc::Response::GetAccountNonce(nonce))
+        }
     })
 }
@@ -4,8 +4,16 @@ pub mod pool;
 
 mod metrics;
 
-use godcoin::{blockchain::ReindexOpts, prelude::*};
-use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
+use godcoin::{
+    blockchain::{ReindexErr, ReindexOpts},
+    prelude::*,
+};
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 use tokio::{net::TcpListener, prelude::*, time};
 use tracing::{error, info, warn};
 
@@ -16,13 +24,22 @@ pub mod prelude {
 
 use prelude::*;
 
+/// Number of blocks from the chain head verified for continuity when the server starts.
+const CHAIN_VALIDATION_DEPTH: u64 = 1000;
+
 pub struct ServerOpts {
     pub blocklog_loc: PathBuf,
     pub index_loc: PathBuf,
     pub minter_key: KeyPair,
     pub bind_addr: String,
     pub reindex: Option<ReindexOpts>,
+    /// Whether an incomplete index is rebuilt on startup when no reindex was requested.
+    pub auto_reindex: bool,
     pub enable_stale_production: bool,
+    pub heartbeat_interval: Duration,
+    /// How long a client may leave a ping unanswered before it is disconnected.
+    pub heartbeat_timeout: Duration,
+    pub compress_blocks: bool,
 }
 
 #[derive(Clone)]
@@ -36,8 +53,25 @@ pub fn init() {
     metrics::register_metrics();
 }
 
+/// Opens the chain stored at the provided locations using the chain parameters of the server.
+pub fn open_chain(blocklog_loc: &Path, index_loc: &Path, compress_blocks: bool) -> Blockchain {
+    Blockchain::new(
+        blocklog_loc,
+        index_loc,
+        ChainParams::default(),
+        NodeOptions {
+            compress_blocks,
+            ..NodeOptions::default()
+        },
+    )
+}
+
 pub fn start(opts: ServerOpts) {
-    let blockchain = Arc::new(Blockchain::new(&opts.blocklog_loc, &opts.index_loc));
+    let blockchain = Arc::new(open_chain(
+        &opts.blocklog_loc,
+        &opts.index_loc,
+        opts.compress_blocks,
+    ));
 
     let is_empty = blockchain.is_empty();
     if !is_empty && blockchain.index_status() != IndexStatus::Complete {
@@ -45,10 +79,19 @@ pub fn start(opts: ServerOpts) {
             "Indexing not complete (status = {:?})",
             blockchain.index_status()
         );
-        match opts.reindex {
-            Some(opts) => blockchain.reindex(opts),
-            None => panic!("index incomplete, aborting..."),
-        }
+        let reindex_opts = match opts.reindex {
+            Some(reindex_opts) => reindex_opts,
+            None if opts.auto_reindex => {
+                warn!(
+                    "Automatically rebuilding the index from the block log (auto_reindex = true)"
+                );
+                ReindexOpts { auto_trim: false }
+            }
+            None => panic!("index incomplete, restart with --reindex or set auto_reindex"),
+        };
+        blockchain
+            .reindex(reindex_opts)
+            .expect("Failed to rebuild the index");
     }
 
     if is_empty {
@@ -65,6 +108,11 @@ pub fn start(opts: ServerOpts) {
         }
     }
 
+    if let Err((height, e)) = blockchain.validate_chain_continuity(CHAIN_VALIDATION_DEPTH) {
+        error!("Block log is inconsistent at height {}: {:?}", height, e);
+        panic!("block log failed the chain continuity check");
+    }
+
     info!(
         "Using height in block log at {}",
         blockchain.get_chain_height()
@@ -86,10 +134,30 @@ pub fn start(opts: ServerOpts) {
     });
 
     let addr = opts.bind_addr.parse::<SocketAddr>().unwrap();
-    start_server(addr, data);
+    start_server(addr, data, opts.heartbeat_interval, opts.heartbeat_timeout);
+}
+
+/// Rebuilds the index of `chain` by replaying every block in the block log. Any existing index
+/// data is wiped before replaying, which makes this suitable for recovering a corrupted index.
+/// Returns the chain height after the replay.
+pub fn replay_block_log(chain: &Blockchain, opts: ReindexOpts) -> Result<u64, ReindexErr> {
+    if chain.is_empty() {
+        return Err(ReindexErr::EmptyBlockLog);
+    }
+    info!("Replaying block log (status = {:?})", chain.index_status());
+    chain.clear_index();
+    chain.reindex(opts)?;
+    let height = chain.get_chain_height();
+    info!("Replayed block log up to height {}", height);
+    Ok(height)
 }
 
-fn start_server(server_addr: SocketAddr, data: Arc<ServerData>) {
+fn start_server(
+    server_addr: SocketAddr,
+    data: Arc<ServerData>,
+    heartbeat_interval: Duration,
+    heartbeat_timeout: Duration,
+) {
     fn is_connection_error(e: &io::Error) -> bool {
         match e.kind() {
             io::ErrorKind::ConnectionRefused
@@ -104,7 +172,13 @@ fn start_server(server_addr: SocketAddr, data: Arc<ServerData>) {
         loop {
             match server.accept().await {
                 Ok((stream, peer_addr)) => {
-                    client::handle_new_client(stream, peer_addr, Arc::clone(&data));
+                    client::handle_new_client(
+                        stream,
+                        peer_addr,
+                        Arc::clone(&data),
+                        heartbeat_interval,
+                        heartbeat_timeout,
+                    );
                 }
                 Err(e) => {
                     error!("Accept error: {:?}", e);
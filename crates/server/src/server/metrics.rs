@@ -47,6 +47,23 @@ lazy_static::lazy_static! {
     pub static ref REQ_GET_ACC_INFO_DUR: Histogram = REQ_DUR.with_label_values(
         &["get_account_info"]
     );
+    pub static ref REQ_GET_BLOCK_HEADER_DUR: Histogram = REQ_DUR.with_label_values(
+        &["get_block_header"]
+    );
+    pub static ref REQ_GET_BLOCK_HEADER_RANGE_DUR: Histogram = REQ_DUR.with_label_values(
+        &["get_block_header_range"]
+    );
+    pub static ref REQ_GET_BLOCK_BATCH_DUR: Histogram = REQ_DUR.with_label_values(
+        &["get_block_batch"]
+    );
+    pub static ref REQ_GET_ACC_DUR: Histogram = REQ_DUR.with_label_values(&["get_account"]);
+    pub static ref REQ_GET_TX_DUR: Histogram = REQ_DUR.with_label_values(&["get_transaction"]);
+    pub static ref REQ_CHECK_TX_DUR: Histogram = REQ_DUR.with_label_values(&["check_tx"]);
+    pub static ref REQ_GET_SCRIPT_DUR: Histogram = REQ_DUR.with_label_values(&["get_script"]);
+    pub static ref REQ_GET_BALANCES_DUR: Histogram = REQ_DUR.with_label_values(&["get_balances"]);
+    pub static ref REQ_GET_ACC_NONCE_DUR: Histogram = REQ_DUR.with_label_values(
+        &["get_account_nonce"]
+    );
 }
 
 pub fn register_metrics() {
@@ -75,4 +92,13 @@ pub fn register_metrics() {
     lazy_static::initialize(&REQ_GET_FULL_BLOCK_DUR);
     lazy_static::initialize(&REQ_GET_BLOCK_RANGE_DUR);
     lazy_static::initialize(&REQ_GET_ACC_INFO_DUR);
+    lazy_static::initialize(&REQ_GET_BLOCK_HEADER_DUR);
+    lazy_static::initialize(&REQ_GET_BLOCK_HEADER_RANGE_DUR);
+    lazy_static::initialize(&REQ_GET_BLOCK_BATCH_DUR);
+    lazy_static::initialize(&REQ_GET_ACC_DUR);
+    lazy_static::initialize(&REQ_GET_TX_DUR);
+    lazy_static::initialize(&REQ_CHECK_TX_DUR);
+    lazy_static::initialize(&REQ_GET_SCRIPT_DUR);
+    lazy_static::initialize(&REQ_GET_BALANCES_DUR);
+    lazy_static::initialize(&REQ_GET_ACC_NONCE_DUR);
 }
@@ -57,7 +57,7 @@ impl Minter {
 
     fn produce(&self, force_stale_production: bool) -> Result<(), blockchain::BlockErr> {
         let mut receipt_pool_lock = self.receipt_pool.lock();
-        let receipts = receipt_pool_lock.flush();
+        let receipts = receipt_pool_lock.flush(self.chain.params().max_block_byte_size);
         let should_produce =
             if force_stale_production || self.enable_stale_production || !receipts.is_empty() {
                 true
@@ -78,13 +78,11 @@ impl Minter {
         }
 
         let head = self.chain.get_chain_head();
-        let block = match head.as_ref() {
-            Block::V0(block) => {
-                let mut b = block.new_child(receipts);
-                b.sign(&self.minter_key);
-                b
-            }
+        let mut block = match head.as_ref() {
+            Block::V0(block) => block.new_child(receipts),
+            Block::V1(block) => block.new_child(receipts),
         };
+        block.sign(&self.minter_key);
 
         let height = block.height();
         let receipt_len = block.receipts().len();
@@ -106,10 +104,12 @@ impl Minter {
             height, receipt_len, receipts
         );
 
-        self.client_pool
-            .broadcast(rpc::Response::GetBlock(FilteredBlock::Block(Arc::new(
+        self.client_pool.broadcast(
+            rpc::SubscriptionKind::Blocks,
+            Body::Response(rpc::Response::GetBlock(FilteredBlock::Block(Arc::new(
                 block,
-            ))));
+            )))),
+        );
         Ok(())
     }
 
@@ -119,10 +119,24 @@ impl Minter {
             .push(tx.precompute(), blockchain::skip_flags::SKIP_NONE)
     }
 
+    /// Validates the transaction as if it were broadcasted without adding it to the receipt pool.
+    pub fn check_tx(&self, tx: TxVariant) -> Result<(), blockchain::TxErr> {
+        self.receipt_pool
+            .lock()
+            .check(&tx.precompute(), blockchain::skip_flags::SKIP_NONE)
+            .map(|_| ())
+    }
+
     pub fn get_account_info(&self, id: AccountId) -> Result<AccountInfo, blockchain::TxErr> {
         self.receipt_pool
             .lock()
             .get_account_info(id)
             .ok_or(blockchain::TxErr::Arithmetic)
     }
+
+    /// Returns the nonce of the last transfer sent from the account, including transfers pending
+    /// in the receipt pool.
+    pub fn get_account_nonce(&self, id: AccountId) -> Option<u32> {
+        self.receipt_pool.lock().get_account_nonce(id)
+    }
 }
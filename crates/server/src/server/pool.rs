@@ -1,12 +1,14 @@
 use futures::channel::mpsc::Sender;
-use godcoin::prelude::*;
+use godcoin::{net::rpc::SubscriptionKind, prelude::*};
 use parking_lot::RwLock;
 use std::{collections::HashMap, net::SocketAddr, sync::Arc};
 use tokio_tungstenite::tungstenite::Message;
 
+type ClientMap = HashMap<SocketAddr, (Sender<Message>, SubscriptionKind)>;
+
 #[derive(Clone)]
 pub struct SubscriptionPool {
-    clients: Arc<RwLock<HashMap<SocketAddr, Sender<Message>>>>,
+    clients: Arc<RwLock<ClientMap>>,
 }
 
 impl SubscriptionPool {
@@ -17,9 +19,11 @@ impl SubscriptionPool {
         }
     }
 
+    /// Subscribes the client to events of the provided `kind`, replacing any previous
+    /// subscription.
     #[inline]
-    pub fn insert(&self, addr: SocketAddr, tx: Sender<Message>) {
-        self.clients.write().insert(addr, tx);
+    pub fn insert(&self, addr: SocketAddr, tx: Sender<Message>, kind: SubscriptionKind) {
+        self.clients.write().insert(addr, (tx, kind));
     }
 
     #[inline]
@@ -27,19 +31,24 @@ impl SubscriptionPool {
         self.clients.write().remove(&addr);
     }
 
-    pub fn broadcast(&self, msg: rpc::Response) {
+    /// Sends the `body` to every client subscribed to events of the provided `kind`.
+    pub fn broadcast(&self, kind: SubscriptionKind, body: Body) {
         let msg = {
             let mut buf = Vec::with_capacity(65536);
             let res = Msg {
                 id: u32::max_value(),
-                body: Body::Response(msg),
+                body,
             };
             res.serialize(&mut buf);
             Message::Binary(buf)
         };
 
         let clients = self.clients.read();
-        for client in clients.values() {
+        let subscribers = clients
+            .values()
+            .filter(|(_, sub_kind)| sub_kind.contains(kind))
+            .map(|(client, _)| client);
+        for client in subscribers {
             // Errors only occur when the other end is dropped, it is the pool managers responsibility to remove any
             // disconnected clients
             let _ = client.clone().try_send(msg.clone());
@@ -1,5 +1,8 @@
 use godcoin::{
-    blockchain::error::TxErr,
+    blockchain::{
+        error::TxErr, skip_flags::SKIP_NONE, BlockErr, ChainParams, GenesisBlockInfo, IndexerError,
+        ReindexErr, ReindexOpts, WriteBatch,
+    },
     constants,
     prelude::{net::ErrorKind, script::EvalErrKind, *},
 };
@@ -29,6 +32,242 @@ fn fresh_blockchain() {
     assert_eq!(chain.index_status(), IndexStatus::Complete);
 }
 
+#[test]
+fn account_info_matches_individual_getters() {
+    let minter = TestMinter::new();
+    let chain = minter.chain();
+    let owner_id = minter.genesis_info().owner_id;
+
+    let info = chain.get_account_info(owner_id, &[]).unwrap();
+    assert_eq!(info.account, chain.get_account(owner_id, &[]).unwrap());
+    assert_eq!(info.net_fee, chain.get_network_fee().unwrap());
+    assert_eq!(
+        info.account_fee,
+        chain.get_account_fee(owner_id, &[]).unwrap()
+    );
+    assert_eq!(
+        info.total_fee().unwrap(),
+        info.net_fee.checked_add(info.account_fee).unwrap()
+    );
+
+    assert!(chain.get_account_info(owner_id + 1000, &[]).is_none());
+}
+
+#[test]
+fn check_block() {
+    let minter = TestMinter::new();
+    let chain = minter.chain();
+
+    let create_child = || {
+        let head = chain.get_chain_head();
+        match head.as_ref() {
+            Block::V0(block) => block.new_child(vec![]),
+            Block::V1(block) => block.new_child(vec![]),
+        }
+    };
+
+    let mut block = create_child();
+    block.sign(&minter.genesis_info().minter_key);
+    assert_eq!(chain.check_block(&block, SKIP_NONE), Ok(()));
+    // Checking a block must not insert it
+    assert_eq!(chain.get_chain_height(), 1);
+
+    let mut block = create_child();
+    match &mut block {
+        Block::V0(block) => {
+            block.header.receipt_root = blockchain::calc_receipt_root(&[Receipt {
+                tx: TxVariant::V0(TxVariantV0::MintTx(MintTx {
+                    base: create_tx_header("0.00000 TEST"),
+                    to: minter.genesis_info().owner_id,
+                    amount: get_asset("10.00000 TEST"),
+                    attachment: vec![],
+                    attachment_name: "".to_string(),
+                })),
+                log: vec![],
+            }]);
+        }
+        Block::V1(_) => unreachable!(),
+    }
+    block.sign(&minter.genesis_info().minter_key);
+    assert_eq!(
+        chain.check_block(&block, SKIP_NONE),
+        Err(BlockErr::InvalidReceiptRoot)
+    );
+
+    let mut block = create_child();
+    block.sign(&KeyPair::gen());
+    assert_eq!(
+        chain.check_block(&block, SKIP_NONE),
+        Err(BlockErr::InvalidSignature)
+    );
+    assert_eq!(chain.get_chain_height(), 1);
+}
+
+#[test]
+fn check_block_reports_failed_tx_index() {
+    let minter = TestMinter::new();
+    let chain = minter.chain();
+
+    let create_mint_tx = |amount: &str| {
+        TxVariant::V0(TxVariantV0::MintTx(MintTx {
+            base: create_tx_header("0.00000 TEST"),
+            to: minter.genesis_info().owner_id,
+            amount: get_asset(amount),
+            attachment: vec![],
+            attachment_name: "".to_string(),
+        }))
+    };
+
+    let valid_tx = {
+        let mut tx = create_mint_tx("10.00000 TEST");
+        tx.append_sign(&minter.genesis_info().wallet_keys[1]);
+        tx.append_sign(&minter.genesis_info().wallet_keys[0]);
+        tx
+    };
+    // Missing signatures causes the script to fail
+    let invalid_tx = create_mint_tx("20.00000 TEST");
+
+    let mut block = match chain.get_chain_head().as_ref() {
+        Block::V0(block) => block.new_child(vec![
+            Receipt {
+                tx: valid_tx,
+                log: vec![],
+            },
+            Receipt {
+                tx: invalid_tx,
+                log: vec![],
+            },
+        ]),
+        Block::V1(_) => unreachable!(),
+    };
+    block.sign(&minter.genesis_info().minter_key);
+
+    match chain.check_block(&block, SKIP_NONE) {
+        Err(BlockErr::Tx { index, err }) => {
+            assert_eq!(index, 1);
+            match err {
+                TxErr::ScriptEval(_) => {}
+                _ => panic!("Expected a script eval error: {:?}", err),
+            }
+        }
+        res => panic!("Expected a tx error: {:?}", res),
+    }
+}
+
+fn create_signed_mint_tx(info: &GenesisBlockInfo) -> TxVariant {
+    let mut tx = TxVariant::V0(TxVariantV0::MintTx(MintTx {
+        base: create_tx_header("0.00000 TEST"),
+        to: info.owner_id,
+        amount: get_asset("10.00000 TEST"),
+        attachment: vec![0; 100],
+        attachment_name: "".to_string(),
+    }));
+    tx.append_sign(&info.wallet_keys[1]);
+    tx.append_sign(&info.wallet_keys[0]);
+    tx
+}
+
+fn mint_tx_byte_size() -> usize {
+    // The size does not depend on the keys that sign the transaction
+    let info = GenesisBlockInfo::new(KeyPair::gen(), 0);
+    create_signed_mint_tx(&info).byte_size()
+}
+
+#[test]
+fn check_block_size_limit() {
+    let tx_size = mint_tx_byte_size();
+    let check_block = |max_block_byte_size: usize| {
+        let minter = TestMinter::with_params(ChainParams {
+            max_block_byte_size,
+            ..ChainParams::default()
+        });
+        let info = minter.genesis_info();
+        let receipts = (0..2)
+            .map(|_| Receipt {
+                tx: create_signed_mint_tx(info),
+                log: vec![],
+            })
+            .collect();
+        let mut block = match minter.chain().get_chain_head().as_ref() {
+            Block::V0(block) => block.new_child(receipts),
+            Block::V1(block) => block.new_child(receipts),
+        };
+        block.sign(&info.minter_key);
+        minter.chain().check_block(&block, SKIP_NONE)
+    };
+
+    assert_eq!(check_block(tx_size * 2), Ok(()));
+    assert_eq!(check_block(tx_size * 2 - 1), Err(BlockErr::BlockTooLarge));
+}
+
+#[test]
+fn produce_block_within_size_limit() {
+    let tx_size = mint_tx_byte_size();
+    let minter = TestMinter::with_params(ChainParams {
+        max_block_byte_size: tx_size * 2 - 1,
+        ..ChainParams::default()
+    });
+    let info = minter.genesis_info();
+
+    for _ in 0..2 {
+        let res = minter.send_req(rpc::Request::Broadcast(create_signed_mint_tx(info)));
+        assert_eq!(res, Some(Ok(rpc::Response::Broadcast)));
+    }
+
+    // Only one transaction fits in each block
+    minter.produce_block().unwrap();
+    assert_eq!(minter.chain().get_chain_head().receipts().len(), 1);
+    minter.produce_block().unwrap();
+    assert_eq!(minter.chain().get_chain_head().receipts().len(), 1);
+    assert_eq!(minter.chain().get_chain_height(), 3);
+
+    let minter = TestMinter::with_params(ChainParams {
+        max_block_byte_size: tx_size - 1,
+        ..ChainParams::default()
+    });
+    let tx = create_signed_mint_tx(minter.genesis_info());
+    assert_eq!(
+        minter.chain().execute_tx(&tx.precompute(), &[], SKIP_NONE),
+        Err(TxErr::TxTooLarge)
+    );
+}
+
+#[test]
+fn subscribe_receives_inserted_blocks() {
+    let minter = TestMinter::new();
+    let chain = minter.chain();
+    let receiver = chain.subscribe();
+    assert!(receiver.try_recv().is_none());
+
+    minter.produce_block().unwrap();
+    minter.produce_block().unwrap();
+
+    let block = receiver.recv().unwrap();
+    assert_eq!(block.height(), 2);
+    assert_eq!(block, chain.get_block(2).unwrap());
+    let block = receiver.recv().unwrap();
+    assert_eq!(block.height(), 3);
+    assert!(receiver.try_recv().is_none());
+}
+
+#[test]
+fn subscribe_drops_oldest_blocks_when_lagging() {
+    let minter = TestMinter::new();
+    let chain = minter.chain();
+    let receiver = chain.subscribe();
+
+    let max_pending = blockchain::subscription::MAX_PENDING_BLOCKS as u64;
+    for _ in 0..max_pending + 2 {
+        minter.produce_block().unwrap();
+    }
+
+    // The first two blocks (heights 2 and 3) are dropped
+    for height in 4..max_pending + 4 {
+        assert_eq!(receiver.try_recv().unwrap().height(), height);
+    }
+    assert!(receiver.try_recv().is_none());
+}
+
 #[test]
 fn reindexed_blockchain() {
     let mut minter = TestMinter::new();
@@ -99,6 +338,7 @@ fn reindexed_blockchain() {
     assert!(chain.get_block(4).is_none());
     assert_eq!(chain.get_chain_height(), 3);
     assert!(chain.indexer().has_txid(tx_data.txid()));
+    assert_eq!(chain.get_transaction(tx_data.txid()), Some((3, tx.clone())));
 
     let owner = match chain.get_owner() {
         TxVariant::V0(tx) => match tx {
@@ -133,6 +373,130 @@ fn reindexed_blockchain() {
     assert_eq!(res, Some(Err(ErrorKind::TxValidation(TxErr::TxDupe))));
 }
 
+#[test]
+fn reindex_resumes_partial_index() {
+    let minter = TestMinter::new();
+
+    let owner_id = minter.genesis_info().owner_id;
+    let acc = {
+        let mut acc = Account::create_default(
+            1,
+            Permissions {
+                threshold: 1,
+                keys: vec![KeyPair::gen().0],
+            },
+        );
+        acc.balance = get_asset("4.00000 TEST");
+        minter.create_account(acc, "2.00000 TEST", true)
+    };
+
+    let chain = minter.chain();
+    let expected_props = chain.get_properties();
+    let expected_owner = chain.get_account(owner_id, &[]).unwrap();
+    let expected_acc = chain.get_account(acc.id, &[]).unwrap();
+
+    // Simulate a reindex that was interrupted after partially writing to the index
+    chain.indexer().set_index_status(IndexStatus::Partial);
+    {
+        let mut batch = WriteBatch::new(chain.indexer());
+        let mut bad_acc = expected_acc.clone();
+        bad_acc.balance = get_asset("0.00000 TEST");
+        batch.insert_or_update_account(bad_acc);
+        batch.commit().unwrap();
+    }
+    assert_ne!(chain.get_account(acc.id, &[]).unwrap(), expected_acc);
+
+    assert_eq!(chain.reindex(ReindexOpts { auto_trim: false }), Ok(()));
+    assert_eq!(chain.index_status(), IndexStatus::Complete);
+    assert_eq!(chain.get_properties(), expected_props);
+    assert_eq!(chain.get_account(owner_id, &[]).unwrap(), expected_owner);
+    assert_eq!(chain.get_account(acc.id, &[]).unwrap(), expected_acc);
+}
+
+#[test]
+fn replay_block_log_restores_corrupted_accounts() {
+    let minter = TestMinter::new();
+
+    let owner_id = minter.genesis_info().owner_id;
+    let acc = {
+        let mut acc = Account::create_default(
+            1,
+            Permissions {
+                threshold: 1,
+                keys: vec![KeyPair::gen().0],
+            },
+        );
+        acc.balance = get_asset("4.00000 TEST");
+        minter.create_account(acc, "2.00000 TEST", true)
+    };
+
+    let chain = minter.chain();
+    let expected_owner = chain.get_account(owner_id, &[]).unwrap();
+    let expected_acc = chain.get_account(acc.id, &[]).unwrap();
+
+    // Corrupt the account index of a completely indexed chain
+    assert_eq!(chain.index_status(), IndexStatus::Complete);
+    {
+        let mut batch = WriteBatch::new(chain.indexer());
+        for id in &[owner_id, acc.id] {
+            let mut bad_acc = chain.get_account(*id, &[]).unwrap();
+            bad_acc.balance = get_asset("0.00000 TEST");
+            batch.insert_or_update_account(bad_acc);
+        }
+        batch.commit().unwrap();
+    }
+    assert_ne!(chain.get_account(owner_id, &[]).unwrap(), expected_owner);
+    assert_ne!(chain.get_account(acc.id, &[]).unwrap(), expected_acc);
+
+    assert_eq!(
+        chain.reindex(ReindexOpts { auto_trim: false }),
+        Err(ReindexErr::IndexComplete)
+    );
+    let height = godcoin_server::replay_block_log(chain, ReindexOpts { auto_trim: false });
+    assert_eq!(height, Ok(chain.get_chain_height()));
+    assert_eq!(chain.index_status(), IndexStatus::Complete);
+    assert_eq!(chain.get_account(owner_id, &[]).unwrap(), expected_owner);
+    assert_eq!(chain.get_account(acc.id, &[]).unwrap(), expected_acc);
+}
+
+#[test]
+fn get_transaction() {
+    let minter = TestMinter::new();
+    let chain = minter.chain();
+
+    let genesis = chain.get_block(0).unwrap();
+    for (index, receipt) in genesis.receipts().iter().enumerate() {
+        let txid = receipt.tx.calc_txid();
+        assert_eq!(chain.get_transaction(&txid), Some((0, receipt.tx.clone())));
+        assert_eq!(
+            chain.indexer().get_tx_location(&txid).unwrap(),
+            Some((0, index as u32))
+        );
+    }
+
+    let tx = {
+        let mut tx = TxVariant::V0(TxVariantV0::MintTx(MintTx {
+            base: create_tx_header("0.00000 TEST"),
+            to: minter.genesis_info().owner_id,
+            amount: get_asset("10.00000 TEST"),
+            attachment: vec![],
+            attachment_name: "".to_owned(),
+        }));
+        tx.append_sign(&minter.genesis_info().wallet_keys[3]);
+        tx.append_sign(&minter.genesis_info().wallet_keys[0]);
+        tx
+    };
+    let txid = tx.calc_txid();
+    assert_eq!(chain.get_transaction(&txid), None);
+
+    let res = minter.send_req(rpc::Request::Broadcast(tx.clone()));
+    assert_eq!(res, Some(Ok(rpc::Response::Broadcast)));
+    minter.produce_block().unwrap();
+
+    let height = chain.get_chain_height();
+    assert_eq!(chain.get_transaction(&txid), Some((height, tx)));
+}
+
 #[test]
 fn tx_dupe() {
     let minter = TestMinter::new();
@@ -358,3 +722,70 @@ fn tx_with_bad_chain_id() {
         }
     }
 }
+
+#[test]
+fn rollback_to_height() {
+    let mut minter = TestMinter::new();
+    let chain = minter.chain();
+    let owner_id = minter.genesis_info().owner_id;
+
+    let height = chain.get_chain_height();
+    let props = chain.get_properties();
+    let owner_acc = chain.get_account(owner_id, &[]).unwrap();
+    let next_id = chain.next_account_id();
+
+    let mut acc = Account::create_default(
+        next_id,
+        Permissions {
+            threshold: 1,
+            keys: vec![KeyPair::gen().0],
+        },
+    );
+    acc.balance = get_asset("4.00000 TEST");
+    minter.create_account(acc, "2.00000 TEST", true);
+
+    let mut tx = TxVariant::V0(TxVariantV0::MintTx(MintTx {
+        base: create_tx_header("0.00000 TEST"),
+        to: owner_id,
+        amount: get_asset("10.00000 TEST"),
+        attachment: vec![],
+        attachment_name: "".to_string(),
+    }));
+    tx.append_sign(&minter.genesis_info().wallet_keys[1]);
+    tx.append_sign(&minter.genesis_info().wallet_keys[0]);
+    let res = minter.send_req(rpc::Request::Broadcast(tx.clone()));
+    assert_eq!(res, Some(Ok(rpc::Response::Broadcast)));
+    minter.produce_block().unwrap();
+
+    assert_eq!(chain.get_chain_height(), height + 2);
+    assert!(chain.get_account(next_id, &[]).is_some());
+    assert_ne!(chain.get_properties().token_supply, props.token_supply);
+
+    chain.rollback_to_height(height).unwrap();
+    assert_eq!(chain.get_chain_height(), height);
+    assert!(chain.get_block(height + 1).is_none());
+    assert_eq!(chain.get_properties(), props);
+    assert_eq!(chain.get_account(owner_id, &[]), Some(owner_acc));
+    assert!(chain.get_account(next_id, &[]).is_none());
+    assert_eq!(chain.next_account_id(), next_id);
+
+    // Transactions of the removed blocks can be broadcasted again
+    let res = minter.send_req(rpc::Request::Broadcast(tx));
+    assert_eq!(res, Some(Ok(rpc::Response::Broadcast)));
+
+    // The chain continues from the rolled back height
+    minter.produce_block().unwrap();
+    assert_eq!(chain.get_chain_height(), height + 1);
+    assert_eq!(chain.validate_chain_continuity(10), Ok(()));
+    assert_ne!(chain.get_properties().token_supply, props.token_supply);
+
+    // Reindexed blocks have no undo data
+    minter.unindexed();
+    minter.reindex();
+    let chain = minter.chain();
+    match chain.rollback_to_height(height) {
+        Err(IndexerError::MissingUndoData(h)) => assert_eq!(h, height + 1),
+        res => panic!("expected missing undo data: {:?}", res),
+    }
+    assert_eq!(chain.get_chain_height(), height + 1);
+}
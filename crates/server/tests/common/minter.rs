@@ -1,6 +1,6 @@
 use super::create_tx_header;
 use godcoin::{
-    blockchain::{GenesisBlockInfo, ReindexOpts},
+    blockchain::{ChainParams, GenesisBlockInfo, ReindexOpts},
     prelude::*,
     tx::CreateAccountTx,
 };
@@ -21,6 +21,10 @@ pub struct TestMinter(ServerData, GenesisBlockInfo, PathBuf, Indexed);
 
 impl TestMinter {
     pub fn new() -> Self {
+        Self::with_params(ChainParams::default())
+    }
+
+    pub fn with_params(params: ChainParams) -> Self {
         godcoin::init().unwrap();
         let tmp_dir = {
             let mut tmp_dir = env::temp_dir();
@@ -33,7 +37,12 @@ impl TestMinter {
 
         let blocklog_loc = &Path::join(&tmp_dir, "blklog");
         let index_loc = &Path::join(&tmp_dir, "index");
-        let chain = Arc::new(Blockchain::new(blocklog_loc, index_loc));
+        let chain = Arc::new(Blockchain::new(
+            blocklog_loc,
+            index_loc,
+            params,
+            NodeOptions::default(),
+        ));
         let minter_key = KeyPair::gen();
         let info = chain.create_genesis_block(minter_key.clone());
 
@@ -56,13 +65,11 @@ impl TestMinter {
             };
 
             let head = chain.get_chain_head();
-            let child = match head.as_ref() {
-                Block::V0(block) => {
-                    let mut b = block.new_child(receipts);
-                    b.sign(&info.minter_key);
-                    b
-                }
+            let mut child = match head.as_ref() {
+                Block::V0(block) => block.new_child(receipts),
+                Block::V1(block) => block.new_child(receipts),
             };
+            child.sign(&info.minter_key);
             chain.insert_block(child).unwrap();
         }
 
@@ -91,14 +98,20 @@ impl TestMinter {
 
         let blocklog_loc = &Path::join(&unindexed_path, "blklog");
         let index_loc = &Path::join(&unindexed_path, "index");
-        self.0.chain = Arc::new(Blockchain::new(blocklog_loc, index_loc));
+        let params = self.0.chain.params().clone();
+        self.0.chain = Arc::new(Blockchain::new(
+            blocklog_loc,
+            index_loc,
+            params,
+            NodeOptions::default(),
+        ));
         self.3 = false;
     }
 
     pub fn reindex(&mut self) {
         let chain = Arc::clone(&self.0.chain);
         assert_eq!(chain.index_status(), IndexStatus::None);
-        chain.reindex(ReindexOpts { auto_trim: true });
+        chain.reindex(ReindexOpts { auto_trim: true }).unwrap();
         let key = self.1.minter_key.clone();
         let pool = self.0.sub_pool.clone();
         self.0.minter = Minter::new(chain, key, pool, false);
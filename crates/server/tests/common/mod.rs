@@ -1,4 +1,5 @@
 use godcoin::{constants::MAX_TX_SIGNATURES, prelude::*};
+use std::sync::atomic::{AtomicU32, Ordering};
 
 pub mod minter;
 pub use minter::*;
@@ -13,11 +14,9 @@ pub fn create_tx_header(fee: &str) -> Tx {
 }
 
 pub fn create_tx_header_with_expiry(fee: &str, expiry: u64) -> Tx {
-    let nonce: u32 = {
-        let mut nonce = [0; 4];
-        sodiumoxide::randombytes::randombytes_into(&mut nonce);
-        u32::from_ne_bytes(nonce)
-    };
+    // Transfers must use a nonce greater than the last transfer sent from the same account
+    static NONCE: AtomicU32 = AtomicU32::new(1);
+    let nonce = NONCE.fetch_add(1, Ordering::Relaxed);
     Tx {
         nonce,
         expiry,
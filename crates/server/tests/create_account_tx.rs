@@ -329,6 +329,55 @@ fn create_acc_tx_fail_insufficient_balance() {
     );
 }
 
+#[test]
+fn create_acc_tx_with_next_account_id() {
+    let minter = TestMinter::new();
+    let owner_id = minter.genesis_info().owner_id;
+    assert_eq!(minter.chain().next_account_id(), owner_id + 1);
+
+    let create_acc_tx = |id: AccountId| {
+        let mut account = Account::create_default(
+            id,
+            Permissions {
+                threshold: 0,
+                keys: vec![],
+            },
+        );
+        account.balance = get_asset("2.00000 TEST");
+
+        let mut tx = TxVariant::V0(TxVariantV0::CreateAccountTx(CreateAccountTx {
+            base: create_tx_header("1.00000 TEST"),
+            creator: owner_id,
+            account,
+        }));
+        tx.append_sign(&minter.genesis_info().wallet_keys[1]);
+        tx.append_sign(&minter.genesis_info().wallet_keys[0]);
+        tx
+    };
+
+    let first_id = minter.chain().next_account_id();
+    let res = minter.send_req(rpc::Request::Broadcast(create_acc_tx(first_id)));
+    assert_eq!(res, Some(Ok(rpc::Response::Broadcast)));
+    minter.produce_block().unwrap();
+
+    let second_id = minter.chain().next_account_id();
+    assert_ne!(first_id, second_id);
+    assert!(minter.chain().get_account(second_id, &[]).is_none());
+
+    let res = minter.send_req(rpc::Request::Broadcast(create_acc_tx(first_id)));
+    assert_eq!(
+        res,
+        Some(Err(net::ErrorKind::TxValidation(
+            blockchain::TxErr::AccountAlreadyExists,
+        )))
+    );
+
+    let res = minter.send_req(rpc::Request::Broadcast(create_acc_tx(second_id)));
+    assert_eq!(res, Some(Ok(rpc::Response::Broadcast)));
+    minter.produce_block().unwrap();
+    assert_eq!(minter.chain().next_account_id(), second_id + 1);
+}
+
 #[test]
 fn create_acc_tx_fail_create_account_twice() {
     let minter = TestMinter::new();
@@ -390,3 +439,30 @@ fn create_acc_tx_fail_create_account_twice() {
         assert_eq!(res, expected_res);
     }
 }
+
+#[test]
+fn get_script_of_created_account() {
+    let minter = TestMinter::new();
+
+    let script = script::Builder::new()
+        .push(script::FnBuilder::new(0, OpFrame::OpDefine(vec![])).push(OpFrame::True))
+        .build()
+        .unwrap();
+    let hash = script.hash();
+    let res = minter.send_req(rpc::Request::GetScript(hash.clone()));
+    assert_eq!(res, Some(Ok(rpc::Response::GetScript(None))));
+
+    let mut account = Account::create_default(
+        100,
+        Permissions {
+            threshold: 1,
+            keys: vec![KeyPair::gen().0],
+        },
+    );
+    account.balance = get_asset("4.00000 TEST");
+    account.script = script.clone();
+    minter.create_account(account, "2.00000 TEST", true);
+
+    let res = minter.send_req(rpc::Request::GetScript(hash));
+    assert_eq!(res, Some(Ok(rpc::Response::GetScript(Some(script)))));
+}
@@ -1,4 +1,5 @@
 use godcoin::{
+    blockchain::ChainParams,
     crypto::Signature,
     prelude::{script::EvalErrKind, *},
 };
@@ -99,3 +100,103 @@ fn mint_tx_updates_balances() {
         .balance;
     assert_eq!(bal, expected_bal);
 }
+
+#[test]
+fn token_supply_at_height() {
+    let minter = TestMinter::new();
+    let chain = minter.chain();
+
+    let mint = |amount: &str| {
+        let mut tx = TxVariant::V0(TxVariantV0::MintTx(MintTx {
+            base: create_tx_header("0.00000 TEST"),
+            to: minter.genesis_info().owner_id,
+            amount: get_asset(amount),
+            attachment: vec![],
+            attachment_name: "".to_string(),
+        }));
+        tx.append_sign(&minter.genesis_info().wallet_keys[1]);
+        tx.append_sign(&minter.genesis_info().wallet_keys[0]);
+
+        let res = minter.send_req(rpc::Request::Broadcast(tx));
+        assert_eq!(res, Some(Ok(rpc::Response::Broadcast)));
+        minter.produce_block().unwrap();
+    };
+
+    mint("10.00000 TEST");
+    // Block without any mint transactions
+    minter.produce_block().unwrap();
+    mint("25.00000 TEST");
+
+    let expected = [
+        "0.00000 TEST",
+        "1000.00000 TEST",
+        "1010.00000 TEST",
+        "1010.00000 TEST",
+        "1035.00000 TEST",
+    ];
+    assert_eq!(chain.get_chain_height(), expected.len() as u64 - 1);
+
+    let mut prev_supply = Asset::default();
+    for (height, expected) in expected.iter().enumerate() {
+        let supply = chain.token_supply_at_height(height as u64).unwrap();
+        assert_eq!(supply, get_asset(expected));
+        assert!(supply.amount >= prev_supply.amount);
+        prev_supply = supply;
+    }
+    assert_eq!(prev_supply, chain.get_properties().token_supply);
+
+    assert_eq!(chain.token_supply_at_height(expected.len() as u64), None);
+}
+
+#[test]
+fn token_supply_at_height_with_checkpoints() {
+    let minter = TestMinter::with_params(ChainParams {
+        supply_checkpoint_interval: 2,
+        ..ChainParams::default()
+    });
+    let chain = minter.chain();
+
+    for amount in &[
+        "10.00000 TEST",
+        "0.50000 TEST",
+        "25.00000 TEST",
+        "3.00000 TEST",
+    ] {
+        let mut tx = TxVariant::V0(TxVariantV0::MintTx(MintTx {
+            base: create_tx_header("0.00000 TEST"),
+            to: minter.genesis_info().owner_id,
+            amount: get_asset(amount),
+            attachment: vec![],
+            attachment_name: "".to_string(),
+        }));
+        tx.append_sign(&minter.genesis_info().wallet_keys[1]);
+        tx.append_sign(&minter.genesis_info().wallet_keys[0]);
+
+        let res = minter.send_req(rpc::Request::Broadcast(tx));
+        assert_eq!(res, Some(Ok(rpc::Response::Broadcast)));
+        minter.produce_block().unwrap();
+        // Block without any mint transactions
+        minter.produce_block().unwrap();
+    }
+
+    let head = chain.get_chain_height();
+    let indexer = chain.indexer();
+    let mut replayed_supply = Asset::default();
+    for height in 0..=head {
+        for receipt in chain.get_block(height).unwrap().receipts() {
+            if let TxVariant::V0(TxVariantV0::MintTx(tx)) = &receipt.tx {
+                replayed_supply = replayed_supply.checked_add(tx.amount).unwrap();
+            }
+        }
+
+        let checkpoint = indexer.get_supply_checkpoint(height).unwrap();
+        if height > 0 && height % 2 == 0 {
+            assert_eq!(checkpoint, Some(replayed_supply));
+        } else {
+            assert_eq!(checkpoint, None);
+        }
+        assert_eq!(chain.token_supply_at_height(height), Some(replayed_supply));
+    }
+    assert_eq!(replayed_supply, get_asset("1038.50000 TEST"));
+    assert_eq!(replayed_supply, chain.get_properties().token_supply);
+}
@@ -45,6 +45,57 @@ fn owner_tx_minter_key_change() {
     assert_eq!(res.unwrap_err(), blockchain::BlockErr::InvalidSignature);
 }
 
+#[test]
+fn owner_tx_history() {
+    let minter = TestMinter::new();
+    let genesis_owner = match minter.chain().get_owner() {
+        TxVariant::V0(TxVariantV0::OwnerTx(owner)) => owner,
+        _ => unreachable!(),
+    };
+
+    let wallet_acc = {
+        let mut acc = Account::create_default(
+            1,
+            Permissions {
+                threshold: 1,
+                keys: vec![KeyPair::gen().0],
+            },
+        );
+        acc.balance = get_asset("4.00000 TEST");
+        minter.create_account(acc, "2.00000 TEST", true)
+    };
+
+    let owner_tx = OwnerTx {
+        base: create_tx_header("0.00000 TEST"),
+        minter: minter.genesis_info().minter_key.0.clone(),
+        wallet: wallet_acc.id,
+    };
+    let tx = {
+        let mut tx = TxVariant::V0(TxVariantV0::OwnerTx(owner_tx));
+        tx.append_sign(&minter.genesis_info().wallet_keys[3]);
+        tx.append_sign(&minter.genesis_info().wallet_keys[0]);
+        tx
+    };
+    let res = minter.send_req(rpc::Request::Broadcast(tx.clone()));
+    assert_eq!(res, Some(Ok(rpc::Response::Broadcast)));
+    minter.produce_block().unwrap();
+    let height = minter.chain().get_chain_height();
+    assert!(height > 1);
+
+    let owner_tx = match tx {
+        TxVariant::V0(TxVariantV0::OwnerTx(owner)) => owner,
+        _ => unreachable!(),
+    };
+    let history = minter.chain().get_owner_history(0);
+    assert_eq!(
+        history,
+        vec![(0, genesis_owner), (height, owner_tx.clone())]
+    );
+
+    let history = minter.chain().get_owner_history(1);
+    assert_eq!(history, vec![(height, owner_tx)]);
+}
+
 #[test]
 fn owner_tx_deny_mint_tokens() {
     let minter = TestMinter::new();
@@ -1,13 +1,15 @@
 use futures::{channel::*, prelude::*};
 use godcoin::{
     constants,
+    crypto::Digest,
     prelude::{net::ErrorKind, *},
 };
-use godcoin_server::client::WsClient;
+use godcoin_server::client::{heartbeat, WsClient};
 use std::{
     io::Cursor,
     net::SocketAddr,
     sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
 };
 use tokio_tungstenite::tungstenite::Message;
 
@@ -498,6 +500,7 @@ fn get_block_range_filter_all() {
                             BlockHeader::V0(header) => {
                                 assert_eq!(header.height, height);
                             }
+                            BlockHeader::V1(_) => panic!("Expected a version 0 header"),
                         },
                         _ => panic!("Expected a partial block"),
                     }
@@ -516,6 +519,120 @@ fn get_block_range_filter_all() {
     runtime.block_on(rx).unwrap();
 }
 
+#[test]
+fn get_block_header() {
+    let minter = TestMinter::new();
+
+    let res = minter.send_req(rpc::Request::GetBlockHeader(1)).unwrap();
+    let block = minter.chain().get_block(1).unwrap();
+    let (header, signer) = match res {
+        Ok(rpc::Response::GetBlockHeader { header, signer }) => (header, signer),
+        unexp => panic!("Expected GetBlockHeader response: {:?}", unexp),
+    };
+    assert_eq!(header, block.header());
+    assert_eq!(header.height(), block.height());
+    assert_eq!(header.timestamp(), block.timestamp());
+    assert_eq!(&signer, block.signer().unwrap());
+    assert!(signer.verify(block.calc_header_hash().as_ref()));
+    match header {
+        BlockHeader::V0(header) => {
+            assert_eq!(
+                header.receipt_root,
+                blockchain::calc_receipt_root(block.receipts())
+            );
+        }
+        BlockHeader::V1(_) => panic!("Expected a version 0 header"),
+    }
+
+    let res = minter.send_req(rpc::Request::GetBlockHeader(2)).unwrap();
+    assert_eq!(res, Err(ErrorKind::InvalidHeight));
+}
+
+#[test]
+fn get_block_header_range() {
+    let minter = TestMinter::new();
+    for _ in 0..5 {
+        minter.produce_block().unwrap();
+    }
+    assert_eq!(minter.chain().get_chain_height(), 6);
+
+    let res = minter
+        .send_req(rpc::Request::GetBlockHeaderRange(2, 6))
+        .unwrap();
+    let expected = (2..=6)
+        .map(|height| minter.chain().get_block_header(height).unwrap())
+        .collect();
+    assert_eq!(res, Ok(rpc::Response::GetBlockHeaderRange(expected)));
+
+    let res = minter
+        .send_req(rpc::Request::GetBlockHeaderRange(2, 7))
+        .unwrap();
+    assert_eq!(res, Err(ErrorKind::InvalidHeight));
+
+    let res = minter
+        .send_req(rpc::Request::GetBlockHeaderRange(3, 2))
+        .unwrap();
+    assert_eq!(res, Err(ErrorKind::InvalidHeight));
+}
+
+#[test]
+fn get_block_batch() {
+    let minter = TestMinter::new();
+    for _ in 0..5 {
+        minter.produce_block().unwrap();
+    }
+    assert_eq!(minter.chain().get_chain_height(), 6);
+
+    let res = minter
+        .send_req(rpc::Request::GetBlockBatch { start: 2, count: 3 })
+        .unwrap();
+    let expected = (2..5)
+        .map(|height| minter.chain().get_block(height).unwrap())
+        .collect();
+    assert_eq!(res, Ok(rpc::Response::GetBlockBatch(expected)));
+
+    // Only the available blocks are returned past the chain head
+    let res = minter
+        .send_req(rpc::Request::GetBlockBatch {
+            start: 4,
+            count: 10,
+        })
+        .unwrap();
+    let expected = (4..=6)
+        .map(|height| minter.chain().get_block(height).unwrap())
+        .collect();
+    assert_eq!(res, Ok(rpc::Response::GetBlockBatch(expected)));
+
+    let res = minter
+        .send_req(rpc::Request::GetBlockBatch { start: 7, count: 1 })
+        .unwrap();
+    assert_eq!(res, Ok(rpc::Response::GetBlockBatch(vec![])));
+}
+
+#[test]
+fn get_block_batch_is_capped() {
+    let minter = TestMinter::new();
+    for _ in 0..rpc::MAX_BLOCK_BATCH {
+        minter.produce_block().unwrap();
+    }
+
+    let res = minter
+        .send_req(rpc::Request::GetBlockBatch {
+            start: 0,
+            count: u16::max_value(),
+        })
+        .unwrap();
+    match res {
+        Ok(rpc::Response::GetBlockBatch(blocks)) => {
+            assert_eq!(blocks.len(), usize::from(rpc::MAX_BLOCK_BATCH));
+            for (height, block) in blocks.iter().enumerate() {
+                assert_eq!(block.height(), height as u64);
+            }
+        }
+        unexp => panic!("Expected GetBlockBatch response: {:?}", unexp),
+    }
+}
+
 #[test]
 fn get_account_info() {
     let minter = TestMinter::new();
@@ -536,6 +653,89 @@ fn get_account_info() {
     assert_eq!(res, expected);
 }
 
+#[test]
+fn get_account() {
+    let minter = TestMinter::new();
+    let acc_id = minter.genesis_info().owner_id;
+    let owner_acc = minter.chain().get_account(acc_id, &[]).unwrap();
+    let res = minter.send_req(rpc::Request::GetAccount(acc_id)).unwrap();
+    assert_eq!(res, Ok(rpc::Response::GetAccount(Some(owner_acc))));
+
+    let acc = {
+        let mut acc = Account::create_default(
+            100,
+            Permissions {
+                threshold: 1,
+                keys: vec![KeyPair::gen().0],
+            },
+        );
+        acc.balance = get_asset("4.00000 TEST");
+        acc
+    };
+    let res = minter.send_req(rpc::Request::GetAccount(acc.id)).unwrap();
+    assert_eq!(res, Ok(rpc::Response::GetAccount(None)));
+
+    minter.create_account(acc.clone(), "2.00000 TEST", true);
+    let res = minter.send_req(rpc::Request::GetAccount(acc.id)).unwrap();
+    assert_eq!(res, Ok(rpc::Response::GetAccount(Some(acc))));
+}
+
+#[test]
+fn get_balances() {
+    let minter = TestMinter::new();
+    let owner_id = minter.genesis_info().owner_id;
+    for (id, balance) in &[(1, "4.00000 TEST"), (2, "1.00000 TEST")] {
+        let mut acc = Account::create_default(
+            *id,
+            Permissions {
+                threshold: 1,
+                keys: vec![KeyPair::gen().0],
+            },
+        );
+        acc.balance = get_asset(balance);
+        minter.create_account(acc, "2.00000 TEST", true);
+    }
+
+    let ids = vec![2, 500, owner_id, 1, 501];
+    let expected = ids
+        .iter()
+        .map(|id| minter.chain().get_account(*id, &[]).map(|acc| acc.balance))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        expected,
+        vec![
+            Some(get_asset("1.00000 TEST")),
+            None,
+            Some(minter.chain().get_account(owner_id, &[]).unwrap().balance),
+            Some(get_asset("4.00000 TEST")),
+            None,
+        ]
+    );
+    assert_eq!(minter.chain().get_balances(&ids, &[]), expected);
+
+    let res = minter.send_req(rpc::Request::GetBalances(ids)).unwrap();
+    assert_eq!(res, Ok(rpc::Response::GetBalances(expected)));
+
+    let res = minter.send_req(rpc::Request::GetBalances(vec![])).unwrap();
+    assert_eq!(res, Ok(rpc::Response::GetBalances(vec![])));
+}
+
+#[test]
+fn get_transaction() {
+    let minter = TestMinter::new();
+    let genesis = minter.chain().get_block(0).unwrap();
+    let tx = genesis.receipts()[0].tx.clone();
+
+    let res = minter
+        .send_req(rpc::Request::GetTransaction(tx.calc_txid()))
+        .unwrap();
+    assert_eq!(res, Ok(rpc::Response::GetTransaction(Some((0, tx)))));
+
+    let txid = TxId::from_digest(Digest::from_slice(&[0; 32]).unwrap());
+    let res = minter.send_req(rpc::Request::GetTransaction(txid)).unwrap();
+    assert_eq!(res, Ok(rpc::Response::GetTransaction(None)));
+}
+
 #[test]
 fn receives_pong_after_ping() {
     let minter = TestMinter::new();
@@ -557,6 +757,62 @@ fn receives_pong_after_ping() {
     );
 }
 
+#[test]
+fn heartbeat_stops_without_pong() {
+    let mut runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let (state, mut rx) = create_uninit_state();
+        let fut = heartbeat(
+            Duration::from_millis(50),
+            Duration::from_millis(150),
+            state.needs_pong(),
+            state.sender(),
+        );
+        tokio::time::timeout(Duration::from_secs(5), fut)
+            .await
+            .expect("Expected heartbeat to stop without a pong");
+
+        // Pings continue every interval until the first one has been unanswered for the timeout
+        for _ in 0..3 {
+            let msg = match rx.next().await.unwrap() {
+                Message::Binary(msg) => Msg::deserialize(&mut Cursor::<&[u8]>::new(&msg)).unwrap(),
+                _ => panic!("Expected binary response"),
+            };
+            assert_eq!(msg.id, u32::max_value());
+            match msg.body {
+                Body::Ping(_) => {}
+                unexp => panic!("Expected ping: {:?}", unexp),
+            }
+        }
+        std::mem::drop(state);
+        assert!(rx.next().await.is_none());
+    });
+}
+
+#[test]
+fn heartbeat_continues_with_pong() {
+    let mut runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let (state, mut rx) = create_uninit_state();
+        let needs_pong = state.needs_pong();
+        let fut = heartbeat(
+            Duration::from_millis(50),
+            Duration::from_millis(150),
+            state.needs_pong(),
+            state.sender(),
+        );
+        tokio::spawn(async move {
+            while rx.next().await.is_some() {
+                // Simulate the client responding with a pong
+                needs_pong.store(false, Ordering::Release);
+            }
+        });
+
+        let res = tokio::time::timeout(Duration::from_millis(500), fut).await;
+        assert!(res.is_err(), "Expected heartbeat to remain alive");
+    });
+}
+
 #[test]
 fn error_with_bytes_remaining() {
     let minter = TestMinter::new();
@@ -569,8 +825,10 @@ fn error_with_bytes_remaining() {
         let mut buf = Vec::with_capacity(4096);
         req.serialize(&mut buf);
 
-        // Push an extra byte that should trigger the error
+        // Push an extra byte within the frame that should trigger the error
         buf.push(0);
+        let frame_len = (buf.len() - 4) as u32;
+        buf[0..4].copy_from_slice(&frame_len.to_be_bytes());
 
         buf
     };
@@ -594,7 +852,7 @@ fn eof_returns_max_u32_id() {
         let mut buf = Vec::with_capacity(4096);
         req.serialize(&mut buf);
 
-        // Delete an extra byte causing an EOF error triggering a failure to deserialize the message
+        // Delete a byte causing a frame length mismatch triggering a failure to deserialize the message
         buf.truncate(buf.len() - 1);
 
         buf
@@ -640,6 +898,103 @@ fn response_id_matches_request() {
     assert_eq!(res, expected);
 }
 
+#[test]
+fn subscriptions_filter_events_by_kind() {
+    let minter = TestMinter::new();
+    let create_state = |port: u16| {
+        let (tx, rx) = mpsc::channel(8);
+        (
+            WsClient::new(SocketAddr::from(([127, 0, 0, 1], port)), tx),
+            rx,
+        )
+    };
+    let (mut block_state, mut block_rx) = create_state(8000);
+    // A newly connected client is not subscribed to any events
+    let (_idle_state, mut idle_rx) = create_state(8001);
+
+    let res = minter.send_msg(
+        &mut block_state,
+        Msg {
+            id: 0,
+            body: Body::Request(rpc::Request::Subscribe(rpc::SubscriptionKind::Blocks)),
+        },
+    );
+    assert_eq!(res.unwrap().body, Body::Response(rpc::Response::Subscribe));
+
+    let tx = {
+        let mut tx = TxVariant::V0(TxVariantV0::MintTx(MintTx {
+            base: create_tx_header("0.00000 TEST"),
+            to: minter.genesis_info().owner_id,
+            amount: get_asset("10.00000 TEST"),
+            attachment: vec![],
+            attachment_name: "".to_string(),
+        }));
+        tx.append_sign(&minter.genesis_info().wallet_keys[1]);
+        tx.append_sign(&minter.genesis_info().wallet_keys[0]);
+        tx
+    };
+    let res = minter.send_req(rpc::Request::Broadcast(tx));
+    assert_eq!(res, Some(Ok(rpc::Response::Broadcast)));
+    // Transaction events reach neither client
+    assert!(block_rx.try_next().is_err());
+    assert!(idle_rx.try_next().is_err());
+
+    minter.produce_block().unwrap();
+    let msg = match block_rx.try_next() {
+        Ok(Some(Message::Binary(msg))) => msg,
+        res => panic!("Expected a binary message: {:?}", res),
+    };
+    let msg = Msg::deserialize(&mut Cursor::<&[u8]>::new(&msg)).unwrap();
+    assert_eq!(msg.id, u32::MAX);
+    let block = minter.chain().get_chain_head();
+    assert_eq!(
+        msg.body,
+        Body::Response(rpc::Response::GetBlock(FilteredBlock::Block(block)))
+    );
+    assert!(idle_rx.try_next().is_err());
+}
+
+#[test]
+fn subscribed_clients_receive_tx_events() {
+    let minter = TestMinter::new();
+    let (tx, mut rx) = mpsc::channel(8);
+    let mut state = WsClient::new(SocketAddr::from(([127, 0, 0, 1], 8000)), tx);
+    let res = minter.send_msg(
+        &mut state,
+        Msg {
+            id: 0,
+            body: Body::Request(rpc::Request::Subscribe(rpc::SubscriptionKind::Txs)),
+        },
+    );
+    assert_eq!(res.unwrap().body, Body::Response(rpc::Response::Subscribe));
+
+    let tx = {
+        let mut tx = TxVariant::V0(TxVariantV0::MintTx(MintTx {
+            base: create_tx_header("0.00000 TEST"),
+            to: minter.genesis_info().owner_id,
+            amount: get_asset("10.00000 TEST"),
+            attachment: vec![],
+            attachment_name: "".to_string(),
+        }));
+        tx.append_sign(&minter.genesis_info().wallet_keys[1]);
+        tx.append_sign(&minter.genesis_info().wallet_keys[0]);
+        tx
+    };
+    let res = minter.send_req(rpc::Request::Broadcast(tx.clone()));
+    assert_eq!(res, Some(Ok(rpc::Response::Broadcast)));
+
+    let msg = match rx.try_next() {
+        Ok(Some(Message::Binary(msg))) => msg,
+        res => panic!("Expected a binary message: {:?}", res),
+    };
+    let msg = Msg::deserialize(&mut Cursor::<&[u8]>::new(&msg)).unwrap();
+    assert_eq!(msg.body, Body::Request(rpc::Request::Broadcast(tx)));
+
+    // Block events are not part of the subscription
+    minter.produce_block().unwrap();
+    assert!(rx.try_next().is_err());
+}
+
 fn create_uninit_state() -> (WsClient, mpsc::Receiver<Message>) {
     let (tx, rx) = mpsc::channel(8);
     (
@@ -356,6 +356,208 @@ fn invalid_amt_caused_by_insufficient_balance() {
     assert_eq!(cur_bal, get_asset("996.00000 TEST"));
 }
 
+#[test]
+fn check_tx_does_not_broadcast() {
+    let minter = TestMinter::new();
+
+    let from_acc = minter.genesis_info().owner_id;
+    let from_bal = minter.chain().get_account(from_acc, &[]).unwrap().balance;
+    let create_tx = |amount: &str| {
+        let mut tx = TxVariant::V0(TxVariantV0::TransferTx(TransferTx {
+            base: create_tx_header("1.00000 TEST"),
+            from: from_acc,
+            call_fn: 0,
+            args: vec![],
+            amount: get_asset(amount),
+            memo: vec![],
+        }));
+        tx.append_sign(&minter.genesis_info().wallet_keys[3]);
+        tx.append_sign(&minter.genesis_info().wallet_keys[0]);
+        tx
+    };
+
+    let valid_tx = create_tx("1.00000 TEST");
+    let res = minter.send_req(rpc::Request::CheckTx(valid_tx.clone()));
+    assert_eq!(res, Some(Ok(rpc::Response::CheckTx)));
+
+    let underfunded_tx = create_tx("500000.00000 TEST");
+    let res = minter.send_req(rpc::Request::CheckTx(underfunded_tx));
+    assert_eq!(
+        res,
+        Some(Err(net::ErrorKind::TxValidation(
+            blockchain::TxErr::InvalidAmount
+        )))
+    );
+
+    // Checked transactions are never added to the receipt pool
+    minter.produce_block().unwrap();
+    let chain = minter.chain();
+    let head = chain.get_chain_head();
+    assert!(head.receipts().is_empty());
+    let cur_bal = chain.get_account(from_acc, &[]).unwrap().balance;
+    assert_eq!(cur_bal, from_bal);
+
+    // A checked transaction can still be broadcasted
+    let res = minter.send_req(rpc::Request::Broadcast(valid_tx));
+    assert_eq!(res, Some(Ok(rpc::Response::Broadcast)));
+}
+
+#[test]
+fn transfer_nonce_must_increase() {
+    let minter = TestMinter::new();
+
+    let from_acc = minter.genesis_info().owner_id;
+    let create_tx = |nonce: u32, amount: &str| {
+        let mut base = create_tx_header("1.00000 TEST");
+        base.nonce = nonce;
+        let mut tx = TxVariant::V0(TxVariantV0::TransferTx(TransferTx {
+            base,
+            from: from_acc,
+            call_fn: 0,
+            args: vec![],
+            amount: get_asset(amount),
+            memo: vec![],
+        }));
+        tx.append_sign(&minter.genesis_info().wallet_keys[3]);
+        tx.append_sign(&minter.genesis_info().wallet_keys[0]);
+        tx
+    };
+    let broadcast = |tx: TxVariant| minter.send_req(rpc::Request::Broadcast(tx)).unwrap();
+    let get_nonce = || {
+        minter
+            .send_req(rpc::Request::GetAccountNonce(from_acc))
+            .unwrap()
+    };
+    let nonce_too_low = Err(net::ErrorKind::TxValidation(blockchain::TxErr::NonceTooLow));
+
+    assert_eq!(get_nonce(), Ok(rpc::Response::GetAccountNonce(None)));
+    assert_eq!(
+        broadcast(create_tx(10, "1.00000 TEST")),
+        Ok(rpc::Response::Broadcast)
+    );
+    assert_eq!(
+        broadcast(create_tx(11, "1.00000 TEST")),
+        Ok(rpc::Response::Broadcast)
+    );
+    // The node reports the nonce of pending transfers
+    assert_eq!(get_nonce(), Ok(rpc::Response::GetAccountNonce(Some(11))));
+    // Stale nonces are checked against transfers pending in the receipt pool
+    assert_eq!(broadcast(create_tx(11, "2.00000 TEST")), nonce_too_low);
+    minter.produce_block().unwrap();
+    assert_eq!(minter.chain().get_account_nonce(from_acc, &[]), Some(11));
+    assert_eq!(get_nonce(), Ok(rpc::Response::GetAccountNonce(Some(11))));
+
+    // Stale nonces are checked against indexed transfers
+    let replay = create_tx(11, "1.00000 TEST");
+    assert_eq!(
+        broadcast(replay),
+        Err(net::ErrorKind::TxValidation(blockchain::TxErr::TxDupe))
+    );
+    assert_eq!(broadcast(create_tx(5, "1.00000 TEST")), nonce_too_low);
+    assert_eq!(
+        broadcast(create_tx(12, "1.00000 TEST")),
+        Ok(rpc::Response::Broadcast)
+    );
+}
+
+#[test]
+fn transfer_nonce_checked_from_activation_height() {
+    let minter = TestMinter::with_params(ChainParams {
+        nonce_check_height: 3,
+        ..ChainParams::default()
+    });
+    assert_eq!(minter.chain().get_chain_height(), 1);
+
+    let from_acc = minter.genesis_info().owner_id;
+    let create_tx = |nonce: u32, amount: &str| {
+        let mut base = create_tx_header("1.00000 TEST");
+        base.nonce = nonce;
+        let mut tx = TxVariant::V0(TxVariantV0::TransferTx(TransferTx {
+            base,
+            from: from_acc,
+            call_fn: 0,
+            args: vec![],
+            amount: get_asset(amount),
+            memo: vec![],
+        }));
+        tx.append_sign(&minter.genesis_info().wallet_keys[3]);
+        tx.append_sign(&minter.genesis_info().wallet_keys[0]);
+        tx
+    };
+    let broadcast = |tx: TxVariant| minter.send_req(rpc::Request::Broadcast(tx)).unwrap();
+
+    // Stale nonces are accepted in blocks before the activation height
+    assert_eq!(
+        broadcast(create_tx(10, "1.00000 TEST")),
+        Ok(rpc::Response::Broadcast)
+    );
+    assert_eq!(
+        broadcast(create_tx(5, "1.00000 TEST")),
+        Ok(rpc::Response::Broadcast)
+    );
+    minter.produce_block().unwrap();
+    assert_eq!(minter.chain().get_account_nonce(from_acc, &[]), Some(5));
+
+    // The next block is at the activation height
+    assert_eq!(
+        broadcast(create_tx(5, "2.00000 TEST")),
+        Err(net::ErrorKind::TxValidation(blockchain::TxErr::NonceTooLow))
+    );
+    assert_eq!(
+        broadcast(create_tx(6, "1.00000 TEST")),
+        Ok(rpc::Response::Broadcast)
+    );
+}
+
+#[test]
+fn verify_tx_collects_all_errors() {
+    let minter = TestMinter::new();
+
+    let from_acc = minter.genesis_info().owner_id;
+    let create_tx = |nonce: u32, fee: &str, amount: &str, memo_len: usize| {
+        let mut base = create_tx_header(fee);
+        base.nonce = nonce;
+        let mut tx = TxVariant::V0(TxVariantV0::TransferTx(TransferTx {
+            base,
+            from: from_acc,
+            call_fn: 0,
+            args: vec![],
+            amount: get_asset(amount),
+            memo: vec![0; memo_len],
+        }));
+        tx.append_sign(&minter.genesis_info().wallet_keys[3]);
+        tx.append_sign(&minter.genesis_info().wallet_keys[0]);
+        tx
+    };
+
+    let tx = create_tx(10, "1.00000 TEST", "1.00000 TEST", 0);
+    let res = minter.send_req(rpc::Request::Broadcast(tx));
+    assert_eq!(res, Some(Ok(rpc::Response::Broadcast)));
+    minter.produce_block().unwrap();
+
+    let chain = minter.chain();
+    let skip_flags = blockchain::skip_flags::SKIP_NONE;
+    let valid_tx = create_tx(11, "1.00000 TEST", "1.00000 TEST", 0).precompute();
+    assert_eq!(chain.verify_tx_collect(&valid_tx, &[], skip_flags), vec![]);
+
+    let tx = create_tx(5, "0.00000 TEST", "-1.00000 TEST", MAX_MEMO_BYTE_SIZE + 1).precompute();
+    let errs = chain.verify_tx_collect(&tx, &[], skip_flags);
+    for err in &[
+        blockchain::TxErr::TxTooLarge,
+        blockchain::TxErr::InvalidAmount,
+        blockchain::TxErr::NonceTooLow,
+        blockchain::TxErr::InvalidFeeAmount,
+    ] {
+        assert!(errs.contains(err), "missing {:?} in {:?}", err, errs);
+    }
+
+    // The fast-fail path only reports the first error
+    assert_eq!(
+        chain.execute_tx(&tx, &[], skip_flags),
+        Err(blockchain::TxErr::TxTooLarge)
+    );
+}
+
 #[test]
 fn invalid_amt_caused_by_negative_amt() {
     let minter = TestMinter::new();
@@ -578,6 +780,48 @@ fn tx_acc_dynamic_fee_increase() {
     assert_eq!(acc_info.account_fee, expected_fee);
 }
 
+#[test]
+fn tx_acc_fee_resets_with_small_window() {
+    let params = ChainParams {
+        fee_reset_window: 1,
+        ..ChainParams::default()
+    };
+    assert!(params.fee_reset_window < FEE_RESET_WINDOW);
+    let minter = TestMinter::with_params(params);
+    let from_acc = minter.genesis_info().owner_id;
+
+    let get_acc_fee = || minter.chain().get_account_fee(from_acc, &[]).unwrap();
+    let base_fee = GRAEL_FEE_MIN.checked_mul(GRAEL_FEE_MULT).unwrap();
+    assert_eq!(get_acc_fee(), base_fee);
+
+    let tx = {
+        let info = minter.chain().get_account_info(from_acc, &[]).unwrap();
+        let mut tx = TxVariant::V0(TxVariantV0::TransferTx(TransferTx {
+            base: create_tx_header(&info.total_fee().unwrap().to_string()),
+            from: from_acc,
+            call_fn: 0,
+            args: vec![],
+            amount: Asset::new(0),
+            memo: vec![],
+        }));
+        tx.append_sign(&minter.genesis_info().wallet_keys[3]);
+        tx.append_sign(&minter.genesis_info().wallet_keys[0]);
+        tx
+    };
+    let res = minter.send_req(rpc::Request::Broadcast(tx)).unwrap();
+    assert_eq!(res, Ok(rpc::Response::Broadcast));
+    minter.produce_block().unwrap();
+
+    let expected_fee = GRAEL_FEE_MIN
+        .checked_mul(GRAEL_FEE_MULT.checked_pow(2).unwrap())
+        .unwrap();
+    assert_eq!(get_acc_fee(), expected_fee);
+
+    // A single block without any account transactions is enough to reset the fee
+    minter.produce_block().unwrap();
+    assert_eq!(get_acc_fee(), base_fee);
+}
+
 #[test]
 fn net_fee_dynamic_increase() {
     let minter = Arc::new(TestMinter::new());
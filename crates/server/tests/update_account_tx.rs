@@ -278,7 +278,7 @@ fn update_acc_tx_fail_attempt_update_destroyed_acc() {
         let indexer = minter.chain().indexer();
         let mut batch = WriteBatch::new(indexer);
         batch.insert_or_update_account(account);
-        batch.commit();
+        batch.commit().unwrap();
     }
 
     let tx = TxVariant::V0(TxVariantV0::UpdateAccountTx(UpdateAccountTx {